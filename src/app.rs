@@ -1,31 +1,213 @@
-use crate::arxiv::ArxivQueryResult;
-use crate::config::HighlightConfig;
-use crate::ui::{ArticleDetails, ArticleFeed, Theme};
-use arboard::Clipboard;
+/// Commands typed after `:`
+pub mod command;
+
+use crate::arxiv::categories::category_name;
+use crate::arxiv::{parse_arxiv_id, ArxivEntry, ArxivQuery, ArxivQueryError, ArxivQueryResult};
+use crate::config::{Config, ConfigWarning};
+use crate::date::format_local;
+use crate::editor::{ConfigEditor, ConfigField, QueryEditor};
+use crate::export::{export_articles, to_citation, to_summary_block, CitationStyle, ExportFormat};
+use crate::handler::KEY_HELP;
+use crate::search::{CrossListMode, SearchState, SortMode};
+use crate::session::{self, Session};
+use crate::stats::FeedStats;
+use crate::tools::{BrowserOpener, ClipboardWriter, SystemClipboard, UrlOpener};
+use crate::ui::{
+    empty_feed_message, pinned_article_indices, pinned_keyword_indices, scroll_to_cursor,
+    ArticleDetails, ArticleFeed, ChoicePopup, ConfigPopup, ConfirmPopup, ErrorPopup, FeedOptions,
+    Footer, Header, HelpPopup, InputPopup, LoadingScreen, PinnedFeed, PinnedKind, QueryPopup,
+    Shortcut, StatsPopup, Theme, PINNED_MAX_HEIGHT,
+};
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::error::Error;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use command::{Command, CommandError, Setting};
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
-    widgets::{Block, Paragraph},
+    layout::{Alignment, Constraint, Direction, Layout},
+    text::Line,
+    widgets::{Block, ListState, Paragraph},
     Frame,
 };
 
+/// Number of browser tabs above which opening the visible articles asks for a confirmation.
+const OPEN_ALL_CONFIRM_THRESHOLD: usize = 5;
+
+/// Shown in place of the details when no article is selected.
+const NO_SELECTION_MESSAGE: &str = "No article selected: move with j/k to show one.";
+/// Articles left below the selection when the next ones are fetched, unless configured.
+const PREFETCH_MARGIN: usize = 20;
+/// Narrowest terminal the interface is drawn in, unless configured.
+const MIN_WIDTH: u16 = 30;
+/// Lowest terminal the interface is drawn in, unless configured.
+const MIN_HEIGHT: u16 = 10;
+/// Number of previous feeds kept to go back to, the oldest ones are dropped.
+const MAX_FEED_STACK: usize = 10;
+
 /// Application result type.
 pub type AppResult<T> = std::result::Result<T, Box<dyn Error>>;
 
+/// Context in which the key events are interpreted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    /// Navigating the article feed.
+    #[default]
+    ArticleList,
+    /// Navigating the feed of the pinned authors.
+    Pinned,
+    /// Choosing the format of the export.
+    Export,
+    /// Typing a search query.
+    Search,
+    /// Confirming an action, see [`ConfirmAction`].
+    Confirm,
+    /// Editing the config in a popup.
+    Config,
+    /// Choosing what to watch after `+`: an author or a keyword.
+    Watch,
+    /// Picking an author of the selected article to pin.
+    AuthorPicker,
+    /// Typing a keyword to highlight.
+    KeywordPrompt,
+    /// Reading the keys of each context.
+    Help,
+    /// Reading the statistics of the feed.
+    Stats,
+    /// Typing a command after `:`, see [`Command`].
+    Command,
+    /// Editing the query in a popup, to run another one.
+    QueryEditor,
+    /// Showing an error until a key is pressed.
+    Error,
+}
+
+/// State of the feed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum FeedStatus {
+    /// The query is running.
+    Loading,
+    /// The articles are shown.
+    #[default]
+    Ready,
+    /// The query failed, with the error message.
+    Error(String),
+}
+
+/// Feed of a previous query, to go back to it as it was left.
+#[derive(Debug, Clone)]
+pub struct PreviousFeed {
+    pub query: ArxivQuery,
+    pub query_result: ArxivQueryResult,
+    /// Selection and scroll position in the feed.
+    pub session: Session,
+}
+
+/// Action waiting for the confirmation of the user.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmAction {
+    /// Opening all the visible articles in the browser.
+    #[default]
+    OpenAll,
+    /// Running the query again after the category changed.
+    Requery,
+}
+
 /// Application.
 #[derive(Debug)]
 pub struct App<'a> {
     /// Is the application running?
     pub running: bool,
-    /// Arxiv entry list:
-    pub query_result: &'a ArxivQueryResult,
-    /// Configuration for the hilighting
-    pub highlight_config: &'a HighlightConfig,
+    /// Arxiv entry list, owned once refreshed:
+    pub query_result: Cow<'a, ArxivQueryResult>,
+    /// Bumped on every change of `query_result`, for the search to tell the same articles
+    pub feed_generation: u64,
+    /// The arXiv query, run again on refresh
+    pub query: Option<ArxivQuery>,
+    /// Is a refresh of the feed waiting to be run by the main loop?
+    pub refresh_pending: bool,
+    /// Query replaced by the one being fetched, its feed is pushed onto `feed_stack` once
+    /// the new articles arrive
+    changed_from: Option<ArxivQuery>,
+    /// Feeds of the previous queries, the last one on top
+    pub feed_stack: Vec<PreviousFeed>,
+    /// Start of the next articles of the query, once asked to be fetched in the background
+    pub prefetch_start: Option<usize>,
+    /// Is the fetch of the next articles waiting to be run by the main loop?
+    pub prefetch_pending: bool,
+    /// Whether the articles are shown, or still loading
+    pub feed_status: FeedStatus,
+    /// When the running query started, for the loading screen
+    pub loading_started: Instant,
+    /// Number of ticks received, to animate the loading screen
+    pub ticks: usize,
+    /// Configuration of the application, owned once edited
+    pub config: Cow<'a, Config>,
+    /// Configuration with the settings of the queried categories applied
+    pub effective_config: Config,
+    /// Path of the config file, where the edits are saved
+    pub config_path: Option<PathBuf>,
+    /// Profile of the config file applied to the config, if any
+    pub profile: Option<String>,
+    /// Problems found while loading the config, listed in the config popup
+    pub config_warnings: Vec<ConfigWarning>,
+    /// State of the config popup
+    pub config_editor: ConfigEditor,
+    /// State of the query popup
+    pub query_editor: QueryEditor,
+    /// Scroll state of the config popup
+    pub config_state: ListState,
+    /// Position of the author selected in the author picker
+    pub author_choice: usize,
+    /// Keyword typed in the keyword prompt
+    pub keyword_input: String,
+    /// Command typed after `:`
+    pub command_input: String,
+    /// Error of the typed command, or its completions, shown next to it
+    pub command_message: Option<String>,
     /// The title of articles feeds
     pub article_feed: ArticleFeed<'a>,
+    /// The articles of the pinned authors and keywords, in the order of [`PinnedKind::ALL`]
+    pub pinned_feeds: Vec<PinnedFeed<'a>>,
+    /// Position of the pinned feed focused in [`Context::Pinned`]
+    pub pinned_focus: usize,
     /// Theme
     pub theme: Theme,
+    /// Current context of the application
+    pub context: Context,
+    /// Action to run if the user confirms
+    pub confirm_action: ConfirmAction,
+    /// Message displayed in the footer, e.g. the result of an export
+    pub status_message: Option<String>,
+    /// Error shown over the interface, see [`Context::Error`]
+    pub error_message: Option<String>,
+    /// Context to return to once the error is dismissed
+    context_before_error: Context,
+    /// Scroll position of the help or stats popup
+    pub popup_state: ListState,
+    /// Number of rows of the help or stats popup shown at once, to scroll it by pages
+    pub popup_page: usize,
+    /// Context to return to once the help or the stats are closed
+    context_before_popup: Context,
+    /// Statistics of the articles matching the search, computed when first shown
+    pub stats: Option<FeedStats>,
+    /// Search through the feed
+    pub search_state: SearchState,
+    /// Opens the articles in the browser
+    pub opener: Box<dyn UrlOpener>,
+    /// Receives the ids and citations copied by the user
+    pub clipboard: Box<dyn ClipboardWriter>,
+    /// Style of the citations copied with `Y`
+    pub citation_style: CitationStyle,
+    /// Time of the previous run, the articles published since are marked as new
+    pub new_since: Option<String>,
+    /// Time of the last successful query, in the format of the arXiv dates
+    pub last_refresh: Option<String>,
+    /// Session of the previous run, its selection is restored with the first articles
+    /// loaded
+    pub pending_session: Option<Session>,
 }
 
 fn option_vec_to_option_slice(option_vec: &Option<Vec<String>>) -> Option<Vec<&str>> {
@@ -36,26 +218,427 @@ fn option_vec_to_option_slice(option_vec: &Option<Vec<String>>) -> Option<Vec<&s
 }
 
 impl<'a> App<'a> {
-    pub fn new(
-        query_result: &'a ArxivQueryResult,
-        highlight_config: &'a HighlightConfig,
-        theme: Theme,
-    ) -> Self {
-        // Constructing the highlighed feed of titles.
-        let patterns = option_vec_to_option_slice(&highlight_config.authors);
-        let article_feed = ArticleFeed::new(query_result, patterns.as_deref(), &theme);
-
-        Self {
-            running: true,
+    pub fn new(query_result: &'a ArxivQueryResult, config: &'a Config, theme: Theme) -> Self {
+        let mut search_state = SearchState::default();
+        search_state.title_only = config.search.title_only;
+        search_state.authors = config.search.authors.unwrap_or(true);
+        search_state.set_articles(&query_result.articles, 0);
+        search_state.set_cross_lists(config.ui.cross_lists.unwrap_or_default());
+        let effective_config = config.for_query_categories();
+
+        let article_feed = Self::build_feed(
             query_result,
-            highlight_config,
+            &effective_config,
+            &mut search_state,
+            None,
+            None,
+            &theme,
+        );
+
+        let pinned_feeds = PinnedKind::ALL
+            .iter()
+            .map(|&kind| {
+                Self::build_pinned_feed(
+                    query_result,
+                    &effective_config,
+                    &search_state,
+                    kind,
+                    &theme,
+                )
+            })
+            .collect();
+
+        let mut app = Self {
+            running: true,
+            query_result: Cow::Borrowed(query_result),
+            feed_generation: 0,
+            query: None,
+            refresh_pending: false,
+            changed_from: None,
+            feed_stack: Vec::new(),
+            prefetch_start: None,
+            prefetch_pending: false,
+            feed_status: FeedStatus::Ready,
+            loading_started: Instant::now(),
+            ticks: 0,
+            config: Cow::Borrowed(config),
+            effective_config,
+            config_path: None,
+            profile: None,
+            config_warnings: Vec::new(),
+            config_editor: ConfigEditor::default(),
+            query_editor: QueryEditor::default(),
+            config_state: ListState::default(),
+            author_choice: 0,
+            keyword_input: String::new(),
+            command_input: String::new(),
+            command_message: None,
             article_feed,
+            pinned_feeds,
+            pinned_focus: 0,
+            theme,
+            context: Context::default(),
+            confirm_action: ConfirmAction::default(),
+            status_message: None,
+            error_message: None,
+            context_before_error: Context::default(),
+            popup_state: ListState::default(),
+            popup_page: 1,
+            context_before_popup: Context::default(),
+            stats: None,
+            search_state,
+            opener: Box::new(BrowserOpener::new(&config.tools.browser)),
+            clipboard: Box::new(SystemClipboard),
+            citation_style: config.export.citation_style.unwrap_or_default(),
+            new_since: None,
+            last_refresh: None,
+            pending_session: None,
+        };
+        if app.auto_select_first() && app.search_state.filtered_count() > 0 {
+            app.article_feed.state.select(Some(0));
+        }
+        app
+    }
+
+    /// Whether the first article is selected when the feed is loaded.
+    fn auto_select_first(&self) -> bool {
+        self.config.ui.auto_select_first.unwrap_or(true)
+    }
+
+    /// Constructing the highlighed feed of titles.
+    fn build_feed(
+        query_result: &ArxivQueryResult,
+        config: &Config,
+        search_state: &mut SearchState,
+        new_since: Option<&str>,
+        query: Option<&ArxivQuery>,
+        theme: &Theme,
+    ) -> ArticleFeed<'a> {
+        let patterns = option_vec_to_option_slice(&config.highlight.authors);
+        let keywords = option_vec_to_option_slice(&config.highlight.keywords);
+        let options = FeedOptions {
+            highlight_authors: patterns.as_deref(),
+            highlight_keywords: keywords.as_deref(),
+            max_title_len: config.ui.max_title_len,
+            new_since,
+            date_format: config.ui.date_format.clone().unwrap_or_default(),
+            queried_categories: query.map(ArxivQuery::categories).unwrap_or_default(),
+            page_label: query.map(|query| query.page_label(query_result.total_results)),
+            abstract_snippet: config.ui.show_abstract_snippet.unwrap_or(false),
+            scroll_mode: config.ui.scroll_mode.unwrap_or_default(),
+        };
+        search_state.set_queried_categories(&options.queried_categories);
+        ArticleFeed::new(query_result, search_state, &options, theme)
+    }
+
+    /// Constructing the feed of the pinned authors or keywords.
+    ///
+    /// While searching, only the pinned articles matching the search are shown.
+    fn build_pinned_feed(
+        query_result: &ArxivQueryResult,
+        config: &Config,
+        search_state: &SearchState,
+        kind: PinnedKind,
+        theme: &Theme,
+    ) -> PinnedFeed<'a> {
+        let max_articles = config.pinned.max_articles;
+        let mut pinned_indices = match kind {
+            PinnedKind::Authors => {
+                let authors = option_vec_to_option_slice(&config.pinned.authors);
+                pinned_article_indices(query_result, authors.as_deref(), max_articles)
+            }
+            PinnedKind::Keywords => {
+                let keywords = option_vec_to_option_slice(&config.pinned.keywords);
+                pinned_keyword_indices(query_result, keywords.as_deref(), max_articles)
+            }
+        };
+        let total = pinned_indices.len();
+        if search_state.is_active() {
+            let matching: HashSet<usize> = search_state.filtered_indices.iter().copied().collect();
+            pinned_indices.retain(|i| matching.contains(i));
+        }
+        let date_format = config.ui.date_format.clone().unwrap_or_default();
+        PinnedFeed::new(
+            query_result,
+            kind,
+            &pinned_indices,
+            total,
+            &date_format,
             theme,
+        )
+        .expanded(config.ui.pinned_expanded.unwrap_or(true))
+        .max_collapsed(config.pinned.max_collapsed)
+    }
+
+    /// Ask the main loop to run the query again, showing the loading state meanwhile.
+    ///
+    /// The current feed stays visible while refreshing it; if there is none, the loading
+    /// screen is shown.
+    pub fn request_refresh(&mut self) {
+        self.refresh_pending = true;
+        if self.feed_status == FeedStatus::Ready {
+            self.status_message = Some("Refreshing the feed…".to_string());
+        } else {
+            self.start_loading();
+        }
+    }
+
+    /// Run another query, the current feed being kept to go back to once the articles of
+    /// the new query arrive.
+    fn change_query(&mut self, query: ArxivQuery) {
+        if let Some(previous) = self.query.replace(query) {
+            // The feed shown is the one of the first query replaced.
+            self.changed_from.get_or_insert(previous);
+        }
+        self.request_refresh();
+    }
+
+    /// Go back to the feed of the previous query, as it was left.
+    pub fn go_back(&mut self) {
+        let Some(previous) = self.feed_stack.pop() else {
+            self.status_message = Some("No previous query to go back to".to_string());
+            return;
+        };
+        self.status_message = Some(format!("Back to {}", previous.query.terms()));
+        self.query = Some(previous.query);
+        self.query_result = Cow::Owned(previous.query_result);
+        self.feed_generation += 1;
+        self.prefetch_start = None;
+        self.prefetch_pending = false;
+        self.search_state
+            .set_articles(&self.query_result.articles, self.feed_generation);
+        self.rebuild_feeds(&previous.session);
+    }
+
+    /// Show the loading screen until the result of the query arrives.
+    pub fn start_loading(&mut self) {
+        self.feed_status = FeedStatus::Loading;
+        self.loading_started = Instant::now();
+    }
+
+    /// Advance the animations.
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+    }
+
+    /// Url of the query to run if a refresh was requested, clearing the request.
+    pub fn take_refresh_query(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.refresh_pending) {
+            self.query.as_ref().map(ArxivQuery::url)
+        } else {
+            None
+        }
+    }
+
+    /// Position of the next articles of the query, `None` once all were fetched.
+    fn next_start(&self) -> Option<usize> {
+        let query = self.query.as_ref()?;
+        let fetched = self.query_result.articles.len();
+        let start = query.start_index.max(0) as usize + fetched;
+        let exhausted = self
+            .query_result
+            .total_results
+            .is_some_and(|total| start >= total);
+        (fetched > 0 && !exhausted).then_some(start)
+    }
+
+    /// Ask the main loop to fetch the next articles of the query in the background when
+    /// the selection nears the end of the main feed, once per start position.
+    ///
+    /// Nothing is fetched while searching, the narrowed feed being always near its end.
+    fn prefetch_if_near_end(&mut self) {
+        let margin = self
+            .effective_config
+            .query
+            .prefetch_margin
+            .unwrap_or(PREFETCH_MARGIN);
+        let visible = self.search_state.filtered_count();
+        let Some(position) = self.article_feed.state.selected() else {
+            return;
+        };
+        let below = visible.saturating_sub(position.min(visible) + 1);
+        if margin == 0
+            || below >= margin
+            || self.context == Context::Pinned
+            || self.search_state.is_active()
+            || self.feed_status != FeedStatus::Ready
+            || self.refresh_pending
+        {
+            return;
+        }
+        if let Some(start) = self.next_start() {
+            if self.prefetch_start != Some(start) {
+                self.prefetch_start = Some(start);
+                self.prefetch_pending = true;
+            }
+        }
+    }
+
+    /// Start and url of the next articles to fetch if asked, clearing the request.
+    pub fn take_prefetch_query(&mut self) -> Option<(usize, String)> {
+        if !std::mem::take(&mut self.prefetch_pending) {
+            return None;
+        }
+        let start = self.prefetch_start?;
+        let query = ArxivQuery {
+            start_index: start as i32,
+            ..self.query.clone()?
+        };
+        Some((start, query.url()))
+    }
+
+    /// Append the articles fetched in the background to the feed, keeping the selection.
+    ///
+    /// The result is dropped if the feed was refreshed or extended since it was asked. On
+    /// error, the feed is kept as is and the articles are asked again when moving the
+    /// selection.
+    pub fn finish_prefetch(
+        &mut self,
+        start: usize,
+        result: Result<ArxivQueryResult, ArxivQueryError>,
+    ) {
+        if self.prefetch_start != Some(start) || self.next_start() != Some(start) {
+            return;
+        }
+        let fetched = match result {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                self.prefetch_start = None;
+                self.status_message = Some(format!("Could not fetch more articles: {e}"));
+                return;
+            }
+        };
+        // The last article may be selected past the end of the feed, see `select_last`.
+        let visible = self.search_state.filtered_count();
+        if let Some(position) = self.article_feed.state.selected() {
+            self.article_feed
+                .state
+                .select(Some(position.min(visible.saturating_sub(1))));
+        }
+        let previous = self.session("");
+        self.feed_generation += 1;
+        self.search_state
+            .append_articles(&fetched.articles, self.feed_generation);
+        let query_result = self.query_result.to_mut();
+        query_result.articles.extend(fetched.articles);
+        query_result.total_results = fetched.total_results.or(query_result.total_results);
+        self.rebuild_feeds(&previous);
+    }
+
+    /// Replace the feed by the result of the refreshed query.
+    ///
+    /// The search is run again on the new articles and the selected article is kept if it
+    /// is still in the feed. On error, the current feed is kept under the error, or the
+    /// error screen is shown if the feed was loading.
+    pub fn finish_refresh(&mut self, result: Result<ArxivQueryResult, ArxivQueryError>) {
+        let changed_from = self.changed_from.take();
+        let query_result = match result {
+            Ok(query_result) => query_result,
+            Err(e) if self.feed_status == FeedStatus::Ready => {
+                // The feed kept is the one of the previous query.
+                if changed_from.is_some() {
+                    self.query = changed_from;
+                }
+                self.status_message = None;
+                self.show_error(format!("Refresh failed: {e}"));
+                return;
+            }
+            Err(e) => {
+                self.feed_status = FeedStatus::Error(format!("Could not query arXiv: {e}"));
+                return;
+            }
+        };
+        let was_loading = self.feed_status == FeedStatus::Loading;
+        self.feed_status = FeedStatus::Ready;
+        self.prefetch_start = None;
+        self.prefetch_pending = false;
+        self.last_refresh = Some(session::now_timestamp());
+        let pending = if was_loading {
+            self.pending_session.take()
+        } else {
+            None
+        };
+        let previous = pending.unwrap_or_else(|| self.session(""));
+        let replaced = std::mem::replace(&mut self.query_result, Cow::Owned(query_result));
+        self.feed_generation += 1;
+        if let (Some(query), false) = (changed_from, was_loading) {
+            self.feed_stack.push(PreviousFeed {
+                query,
+                query_result: replaced.into_owned(),
+                session: previous.clone(),
+            });
+            if self.feed_stack.len() > MAX_FEED_STACK {
+                self.feed_stack.remove(0);
+            }
+        }
+        self.search_state
+            .set_articles(&self.query_result.articles, self.feed_generation);
+        self.rebuild_feeds(&previous);
+        if !was_loading {
+            self.status_message = Some(format!(
+                "Refreshed: {} articles",
+                self.query_result.articles.len()
+            ));
         }
     }
 }
 
 impl App<'_> {
+    /// Show an error over the interface until a key is pressed.
+    pub fn show_error(&mut self, message: String) {
+        if self.context != Context::Error {
+            self.context_before_error = self.context;
+            self.context = Context::Error;
+        }
+        self.error_message = Some(message);
+    }
+
+    /// Hide the error, back to what was shown before.
+    pub fn dismiss_error(&mut self) {
+        self.error_message = None;
+        self.context = std::mem::take(&mut self.context_before_error);
+    }
+
+    /// Show the keys of each context, from the top.
+    pub fn show_help(&mut self) {
+        self.context_before_popup = self.context;
+        self.popup_state = ListState::default();
+        self.context = Context::Help;
+    }
+
+    /// Close the help, back to what was shown before.
+    pub fn close_help(&mut self) {
+        self.context = std::mem::take(&mut self.context_before_popup);
+    }
+
+    /// Show the statistics of the articles matching the search, from the top.
+    pub fn show_stats(&mut self) {
+        if self.stats.is_none() {
+            let articles = &self.query_result.articles;
+            self.stats = Some(FeedStats::new(
+                self.search_state
+                    .filtered_indices
+                    .iter()
+                    .filter_map(|&i| articles.get(i)),
+            ));
+        }
+        self.context_before_popup = self.context;
+        self.popup_state = ListState::default();
+        self.context = Context::Stats;
+    }
+
+    /// Close the stats, back to what was shown before.
+    pub fn close_stats(&mut self) {
+        self.context = std::mem::take(&mut self.context_before_popup);
+    }
+
+    /// Scroll the help or the stats by the given number of rows, down if positive. The
+    /// popup stops at its last row when rendered.
+    pub fn scroll_popup(&mut self, rows: isize) {
+        let offset = self.popup_state.offset_mut();
+        *offset = offset.saturating_add_signed(rows);
+    }
+
     /// Set running to false to quit the application.
     pub fn quit(&mut self) {
         self.running = false;
@@ -66,70 +649,3062 @@ impl App<'_> {
         self.article_feed.state.select(None)
     }
 
+    /// The focused pinned feed in [`Context::Pinned`].
+    fn focused_pinned_feed(&self) -> Option<&PinnedFeed<'_>> {
+        (self.context == Context::Pinned)
+            .then(|| self.pinned_feeds.get(self.pinned_focus))
+            .flatten()
+    }
+
+    /// State of the focused feed: a pinned one in [`Context::Pinned`], else the main one.
+    fn focused_feed_state(&mut self) -> &mut ListState {
+        match self.pinned_feeds.get_mut(self.pinned_focus) {
+            Some(feed) if self.context == Context::Pinned => &mut feed.state,
+            _ => &mut self.article_feed.state,
+        }
+    }
+
     /// Select next item:
     pub fn select_next(&mut self) {
-        self.article_feed.state.select_next();
+        self.focused_feed_state().select_next();
+        self.prefetch_if_near_end();
     }
     pub fn select_previous(&mut self) {
-        self.article_feed.state.select_previous();
+        self.focused_feed_state().select_previous();
     }
 
     pub fn select_first(&mut self) {
-        self.article_feed.state.select_first();
+        self.focused_feed_state().select_first();
     }
 
     pub fn select_last(&mut self) {
-        self.article_feed.state.select_last();
+        self.focused_feed_state().select_last();
+        self.prefetch_if_near_end();
     }
 
-    pub fn yank_id(&mut self) {
-        // The abstract of the manuscript
-        let id = if let Some(i) = self.article_feed.state.selected() {
-            self.query_result.articles[i].id.clone()
+    /// Move the focus from the main feed to the pinned feeds in turn, then back to the
+    /// main feed, each keeping its own selection. Only the pinned feeds with articles
+    /// are focused.
+    pub fn focus_next_feed(&mut self) {
+        let start = if self.context == Context::Pinned {
+            self.pinned_focus + 1
+        } else {
+            0
+        };
+        let next = (start..self.pinned_feeds.len()).find(|&i| !self.pinned_feeds[i].is_empty());
+        match next {
+            Some(i) => {
+                self.pinned_focus = i;
+                let state = &mut self.pinned_feeds[i].state;
+                if state.selected().is_none() {
+                    state.select(Some(0));
+                }
+                self.set_context(Context::Pinned);
+            }
+            None => self.set_context(Context::ArticleList),
+        }
+    }
+
+    /// Expand or collapse the focused pinned feed.
+    pub fn toggle_pinned_expanded(&mut self) {
+        if let Some(feed) = self.pinned_feeds.get_mut(self.pinned_focus) {
+            if self.context == Context::Pinned {
+                feed.expanded = !feed.expanded;
+            }
+        }
+    }
+
+    /// Expand all the pinned feeds if they are all collapsed, collapse them otherwise,
+    /// whichever feed has the focus. The choice is saved to the config file for the next
+    /// runs.
+    pub fn toggle_all_pinned_expanded(&mut self) {
+        let expanded = !self.pinned_feeds.iter().any(|feed| feed.expanded);
+        for feed in &mut self.pinned_feeds {
+            feed.expanded = expanded;
+        }
+        self.config.to_mut().ui.pinned_expanded = Some(expanded);
+        let done = if expanded {
+            "Expanded the pinned feeds"
         } else {
-            "Nothing selected".to_string()
+            "Collapsed the pinned feeds"
         };
+        match &self.config_path {
+            Some(path) => {
+                let value = toml::Value::Boolean(expanded);
+                let profile = self.profile.as_deref();
+                match Config::set_file_value(path, profile, "ui", "pinned_expanded", value) {
+                    Ok(()) => self.status_message = Some(done.to_string()),
+                    Err(e) => self.show_error(format!("Could not save the config: {e}")),
+                }
+            }
+            None => self.status_message = Some(format!("{done} for this session")),
+        }
+    }
 
-        // Set the clipboard
-        let mut clipboard = Clipboard::new().unwrap();
-        clipboard.set_text(id).unwrap();
+    /// Height of a pinned feed: up to `ui.pinned_max_height` rows when expanded, a few
+    /// when collapsed.
+    pub fn pinned_height(&self, index: usize) -> u16 {
+        let max_height = self
+            .config
+            .ui
+            .pinned_max_height
+            .unwrap_or(PINNED_MAX_HEIGHT);
+        self.pinned_feeds
+            .get(index)
+            .map_or(0, |feed| feed.height(max_height))
     }
 
-    /// Render the app:
-    pub fn render(&mut self, frame: &mut Frame) {
-        // First we create a Layout
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(100), Constraint::Min(1)])
-            .split(frame.size());
+    /// Copy the url of the selected article.
+    pub fn yank_id(&mut self) {
+        let Some(article) = self.selected_article() else {
+            self.status_message = Some(self.placeholder_message());
+            return;
+        };
+        let id = article.id.clone();
+        self.copy_to_clipboard(id);
+    }
+
+    /// Copy the citation of the selected article in the current style.
+    pub fn yank_citation(&mut self) {
+        let Some(article) = self.selected_article() else {
+            self.status_message = Some(self.placeholder_message());
+            return;
+        };
+        let citation = to_citation(article, self.citation_style);
+        if self.copy_to_clipboard(citation) {
+            self.status_message = Some(format!(
+                "Copied the {} citation",
+                self.citation_style.name()
+            ));
+        }
+    }
+
+    /// Copy the title, authors, url and the beginning of the abstract of the selected
+    /// article, to paste in an email.
+    pub fn yank_summary_block(&mut self) {
+        let Some(article) = self.selected_article() else {
+            self.status_message = Some(self.placeholder_message());
+            return;
+        };
+        let block = to_summary_block(article);
+        if self.copy_to_clipboard(block) {
+            self.status_message = Some("Copied the summary of the article".to_string());
+        }
+    }
+
+    /// Copy the url of the query of the feed, to share it or run it elsewhere.
+    pub fn yank_query_url(&mut self) {
+        let Some(url) = self.query.as_ref().map(ArxivQuery::url) else {
+            self.status_message = Some("No query to copy".to_string());
+            return;
+        };
+        if self.copy_to_clipboard(url) {
+            self.status_message = Some("Copied the url of the query".to_string());
+        }
+    }
+
+    /// Switch to the next style of the copied citations.
+    pub fn cycle_citation_style(&mut self) {
+        self.citation_style = self.citation_style.next();
+        self.status_message = Some(format!("Citation style: {}", self.citation_style.name()));
+    }
 
-        // adding the shortcut
-        frame.render_widget(
-            Paragraph::new("   quit: q  |  up: k  | down: j | yank url: y")
-                .style(self.theme.shortcut)
-                .left_aligned()
-                .block(Block::new()),
-            layout[1],
+    /// Copy the text to the clipboard, telling the user if it failed.
+    fn copy_to_clipboard(&mut self, text: String) -> bool {
+        match self.clipboard.set_text(text) {
+            Ok(()) => true,
+            Err(e) => {
+                self.status_message = Some(format!("Could not copy to the clipboard: {e}"));
+                false
+            }
+        }
+    }
+
+    /// Change the context in which the key events are interpreted.
+    ///
+    /// Leaving the search clears the query.
+    pub fn set_context(&mut self, context: Context) {
+        if self.context == Context::Search && context != Context::Search {
+            self.search_state.clear();
+            self.update_feed();
+        }
+        self.context = context;
+    }
+
+    /// Rebuild the feeds after the search results changed.
+    ///
+    /// The first article is selected, unless the config turns it off and no search is
+    /// narrowing the feed.
+    pub fn update_feed(&mut self) {
+        self.stats = None;
+        self.article_feed = Self::build_feed(
+            &self.query_result,
+            &self.effective_config,
+            &mut self.search_state,
+            self.new_since.as_deref(),
+            self.query.as_ref(),
+            &self.theme,
         );
+        let select_first = self.auto_select_first() || self.search_state.is_active();
+        if select_first && self.search_state.filtered_count() > 0 {
+            self.article_feed.state.select(Some(0));
+        }
+        self.update_pinned_feeds();
+    }
 
-        let layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .horizontal_margin(2)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(layout[0]);
+    /// Mark the articles published after the given time as new.
+    pub fn mark_new_since(&mut self, timestamp: Option<String>) {
+        if self.search_state.is_new_only() {
+            self.search_state.set_published_after(timestamp.clone());
+        }
+        self.new_since = timestamp;
+        self.update_feed();
+    }
 
-        // Render the slectable feed
-        self.article_feed.render(frame, layout[0]);
+    /// Number of the visible articles published since the previous run.
+    fn new_count(&self) -> usize {
+        let Some(since) = &self.new_since else {
+            return 0;
+        };
+        self.search_state
+            .filtered_indices
+            .iter()
+            .filter(|&&i| self.query_result.articles[i].is_published_after(since))
+            .count()
+    }
 
-        // Render the detail of the article selected:
-        let current_entry = if let Some(i) = self.article_feed.state.selected() {
-            &self.query_result.articles[i]
+    /// Select the first visible article published since the previous run.
+    pub fn select_first_new(&mut self) {
+        let position = self.new_since.as_ref().and_then(|since| {
+            self.search_state
+                .filtered_indices
+                .iter()
+                .position(|&i| self.query_result.articles[i].is_published_after(since))
+        });
+        match position {
+            Some(position) => self.article_feed.state.select(Some(position)),
+            None => self.status_message = Some("No new articles".to_string()),
+        }
+    }
+
+    /// Show only the articles published since the previous run, or all of them again.
+    ///
+    /// The selected article stays selected if it is still shown.
+    pub fn toggle_new_only(&mut self) {
+        self.set_new_only(!self.search_state.is_new_only());
+    }
+
+    /// Show only the articles published since the previous run, or all of them.
+    pub fn set_new_only(&mut self, new_only: bool) {
+        let Some(since) = &self.new_since else {
+            self.status_message = Some("No previous run to compare with".to_string());
+            return;
+        };
+        let previous = self.session("");
+        self.search_state
+            .set_published_after(new_only.then(|| since.clone()));
+        self.rebuild_feeds(&previous);
+        self.status_message = Some(if new_only {
+            "Only the new articles".to_string()
+        } else {
+            "All the articles".to_string()
+        });
+    }
+
+    /// Add a character at the cursor of the search query and filter the feed.
+    pub fn search_push_char(&mut self, c: char) {
+        self.search_state.push_char(c);
+        self.update_feed();
+    }
+
+    /// Move the cursor of the search query one character to the left.
+    pub fn search_cursor_left(&mut self) {
+        self.search_state.move_cursor_left();
+    }
+
+    /// Move the cursor of the search query one character to the right.
+    pub fn search_cursor_right(&mut self) {
+        self.search_state.move_cursor_right();
+    }
+
+    /// Insert a pasted text at the cursor of the search query and filter the feed.
+    pub fn search_insert_str(&mut self, text: &str) {
+        self.search_state.insert_str(text);
+        self.update_feed();
+    }
+
+    /// Move the cursor to the start of the search query.
+    pub fn search_cursor_home(&mut self) {
+        self.search_state.move_cursor_home();
+    }
+
+    /// Move the cursor to the end of the search query.
+    pub fn search_cursor_end(&mut self) {
+        self.search_state.move_cursor_end();
+    }
+
+    /// Remove the character before the cursor of the search query and filter the feed.
+    pub fn search_pop_char(&mut self) {
+        self.search_state.pop_char();
+        self.update_feed();
+    }
+
+    /// Remove the character under the cursor of the search query and filter the feed.
+    pub fn search_delete_char(&mut self) {
+        self.search_state.delete_char();
+        self.update_feed();
+    }
+
+    /// Remove the word before the cursor of the search query and filter the feed.
+    pub fn search_delete_word(&mut self) {
+        self.search_state.delete_word();
+        self.update_feed();
+    }
+
+    /// Remove the search query before the cursor and filter the feed.
+    pub fn search_delete_to_start(&mut self) {
+        self.search_state.delete_to_start();
+        self.update_feed();
+    }
+
+    /// Start typing a search query, editing the one applied to the feed if any.
+    pub fn start_search(&mut self) {
+        self.search_state.move_cursor_end();
+        self.set_context(Context::Search);
+    }
+
+    /// Leave the search keeping the feed filtered by it, until [`Self::clear_search`],
+    /// or fetch the article instead if the search is an arXiv identifier.
+    pub fn search_submit(&mut self) {
+        let id = parse_arxiv_id(&self.search_state.query).map(str::to_string);
+        let (Some(id), Some(query)) = (id, &self.query) else {
+            // Not through `set_context`, which would clear the search.
+            self.context = Context::ArticleList;
+            return;
+        };
+        let query = query.for_id(&id);
+        self.set_context(Context::ArticleList);
+        self.change_query(query);
+        self.status_message = Some(format!("Fetching arXiv:{id}…"));
+    }
+
+    /// Clear the search applied to the feed with [`Self::search_submit`], telling
+    /// whether there was one.
+    pub fn clear_search(&mut self) -> bool {
+        if !self.search_state.is_active() {
+            return false;
+        }
+        self.search_state.clear();
+        self.update_feed();
+        self.status_message = Some("Search cleared".to_string());
+        true
+    }
+
+    /// Switch the search between the titles only and the titles with the abstracts.
+    pub fn search_toggle_title_only(&mut self) {
+        self.search_state.toggle_title_only();
+        self.update_feed();
+    }
+
+    /// Sort the feed by the next order: published date, updated date, title, then back to
+    /// the order of arXiv, or of the best matches while searching.
+    ///
+    /// The selected article stays selected.
+    pub fn cycle_sort(&mut self) {
+        self.set_sort(self.search_state.sort_mode.next());
+    }
+
+    /// Sort the feed in the given order, keeping the selected article selected.
+    pub fn set_sort(&mut self, mode: SortMode) {
+        let previous = self.session("");
+        self.search_state.set_sort_mode(mode);
+        self.rebuild_feeds(&previous);
+        self.status_message = Some(format!("Sorted by {}", self.sort_label()));
+    }
+
+    /// Show the cross-lists in the next mode: dimmed after the other articles, hidden,
+    /// then among the others again.
+    ///
+    /// The selected article stays selected, unless it is hidden.
+    pub fn cycle_cross_lists(&mut self) {
+        self.set_cross_lists(self.search_state.cross_lists().next());
+    }
+
+    /// Show the cross-lists in the given mode.
+    pub fn set_cross_lists(&mut self, mode: CrossListMode) {
+        let previous = self.session("");
+        self.search_state.set_cross_lists(mode);
+        self.rebuild_feeds(&previous);
+        self.status_message = Some(mode.description().to_string());
+    }
+
+    /// Name of the order of the feed.
+    fn sort_label(&self) -> &'static str {
+        self.search_state
+            .sort_mode
+            .label(self.search_state.is_active())
+    }
+
+    /// Index in `query_result.articles` of the selected article.
+    ///
+    /// The selection is a position in the (possibly filtered) feed, which is mapped back
+    /// to the article it shows.
+    pub fn get_actual_article_index(&self) -> Option<usize> {
+        self.article_feed
+            .state
+            .selected()
+            .and_then(|i| self.search_state.filtered_indices.get(i))
+            .copied()
+    }
+
+    /// The article selected in the focused feed, if any.
+    fn selected_article(&self) -> Option<&ArxivEntry> {
+        self.selected_article_index()
+            .and_then(|i| self.query_result.articles.get(i))
+    }
+
+    /// Why no article is shown in the details, and what to do about it: the feed is
+    /// empty, the search matches nothing, or nothing is selected yet.
+    fn placeholder_message(&self) -> String {
+        if self.query_result.articles.is_empty() {
+            let terms = self
+                .query
+                .as_ref()
+                .map_or_else(|| "this query".to_string(), ArxivQuery::terms);
+            format!("No articles for {terms}: press r to refresh or c to change the query.")
+        } else if self.search_state.filtered_count() == 0 {
+            empty_feed_message(&self.query_result).to_string()
         } else {
-            // Should implement a default print here ?
-            &self.query_result.articles[0]
+            NO_SELECTION_MESSAGE.to_string()
+        }
+    }
+
+    /// Index in `query_result.articles` of the article selected in the focused feed,
+    /// the one shown in the details.
+    pub fn selected_article_index(&self) -> Option<usize> {
+        match self.focused_pinned_feed() {
+            Some(feed) => feed.selected_index(),
+            None => self.get_actual_article_index(),
+        }
+    }
+
+    /// Rebuild the feeds after the articles or the config changed, selecting the article
+    /// of `previous` if it is still in the feed. The pinned feeds keep their selected
+    /// article too.
+    fn rebuild_feeds(&mut self, previous: &Session) {
+        self.update_feed();
+        self.restore_session(previous);
+    }
+
+    /// Rebuild the pinned feeds after the articles, the config or the search changed.
+    ///
+    /// In each feed, the selected article stays selected if it is still in the feed,
+    /// otherwise the selection is clamped to the new feed, and the feed stays expanded or
+    /// collapsed. The focused feed loses the focus if it is now empty.
+    fn update_pinned_feeds(&mut self) {
+        for (i, &kind) in PinnedKind::ALL.iter().enumerate() {
+            let mut feed = Self::build_pinned_feed(
+                &self.query_result,
+                &self.effective_config,
+                &self.search_state,
+                kind,
+                &self.theme,
+            );
+            if let Some(previous) = self.pinned_feeds.get(i) {
+                feed.expanded = previous.expanded;
+                feed.select_id(previous.selected_id());
+                if feed.state.selected().is_none() && !feed.is_empty() {
+                    let last = feed.len() - 1;
+                    feed.state
+                        .select(previous.state.selected().map(|i| i.min(last)));
+                }
+            }
+            match self.pinned_feeds.get_mut(i) {
+                Some(previous) => *previous = feed,
+                None => self.pinned_feeds.push(feed),
+            }
+        }
+        if self.context == Context::Pinned
+            && self
+                .pinned_feeds
+                .get(self.pinned_focus)
+                .is_none_or(PinnedFeed::is_empty)
+        {
+            self.context = Context::ArticleList;
+        }
+    }
+
+    /// Run the confirmed action.
+    pub fn confirm(&mut self) {
+        match self.confirm_action {
+            ConfirmAction::OpenAll => self.open_all_visible_confirmed(),
+            ConfirmAction::Requery => {
+                self.set_context(Context::ArticleList);
+                self.requery();
+            }
+        }
+    }
+
+    /// Ask for a confirmation before running the action.
+    fn ask_confirmation(&mut self, action: ConfirmAction) {
+        self.confirm_action = action;
+        self.set_context(Context::Confirm);
+    }
+
+    /// Run the query again for the articles of the first author of the selected article.
+    pub fn search_selected_author(&mut self) {
+        let Some(author) = self.selected_authors().first().cloned() else {
+            self.status_message = Some(self.placeholder_message());
+            return;
+        };
+        self.search_author(&author);
+    }
+
+    /// Run the query again for the articles of the author.
+    fn search_author(&mut self, author: &str) {
+        if let Some(query) = &self.query {
+            self.change_query(query.for_author(author));
+            self.status_message = Some(format!("Fetching the articles of {author}…"));
+        }
+    }
+
+    /// Run the query again in the categories, clearing the search.
+    fn search_categories(&mut self, categories: &[String]) {
+        if let Some(query) = &self.query {
+            self.change_query(query.with_categories(categories));
+            if self.search_state.is_active() {
+                self.search_state.clear();
+                self.update_feed();
+            }
+            self.status_message = Some(format!(
+                "Fetching the articles of {}…",
+                categories.join(", ")
+            ));
+        }
+    }
+
+    /// Run the query again in the primary category of the selected article, clearing the
+    /// search.
+    pub fn search_selected_category(&mut self) {
+        let Some(article) = self.selected_article() else {
+            self.status_message = Some(self.placeholder_message());
+            return;
+        };
+        let Some(category) = article.primary_category.clone() else {
+            return;
+        };
+        if let Some(query) = &self.query {
+            self.change_query(query.for_category(&category));
+            if self.search_state.is_active() {
+                self.search_state.clear();
+                self.update_feed();
+            }
+            self.status_message = Some(format!("Fetching the articles of {category}…"));
+        }
+    }
+
+    /// Run the query again in the categories of the config.
+    pub fn requery(&mut self) {
+        if let Some(query) = &self.query {
+            self.change_query(query.with_categories(&self.config.query.categories));
+        }
+    }
+
+    /// Start editing the field selected in the config popup.
+    pub fn config_start_edit(&mut self) {
+        self.config_editor.start_edit(&self.config);
+    }
+
+    /// Apply the edited field to the config and save it to the config file.
+    ///
+    /// Changing the category offers to run the query again. Invalid values, like an
+    /// unknown category, are reported and the edit goes on.
+    pub fn config_commit_edit(&mut self) {
+        let (field, values) = match self.config_editor.commit() {
+            Some(Ok(edit)) => edit,
+            Some(Err(e)) => {
+                self.status_message = Some(e);
+                return;
+            }
+            None => return,
         };
+        self.set_config_field(field, values);
+
+        if field == ConfigField::Category && self.query.is_some() {
+            self.ask_confirmation(ConfirmAction::Requery);
+        }
+    }
+
+    /// Set the values of a config field, rebuild the feeds and save the field to the
+    /// config file, which is created if needed.
+    fn set_config_field(&mut self, field: ConfigField, values: Vec<String>) {
+        let previous = self.session("");
+        field.set_values(self.config.to_mut(), values.clone());
+        self.effective_config = self.config.for_query_categories();
+        self.rebuild_feeds(&previous);
+
+        let (section, key) = field.key();
+        match &self.config_path {
+            Some(path) => match Config::set_file_value(
+                path,
+                self.profile.as_deref(),
+                section,
+                key,
+                values.into(),
+            ) {
+                Ok(()) => {
+                    let profile = self
+                        .profile
+                        .as_ref()
+                        .map_or_else(String::new, |name| format!(" (profile {name})"));
+                    self.status_message = Some(format!(
+                        "Saved {} to {}{profile}",
+                        field.label(),
+                        path.display()
+                    ));
+                }
+                Err(e) => self.show_error(format!("Could not save the config: {e}")),
+            },
+            None => {
+                self.status_message = Some(format!("Changed {} for this session", field.label()));
+            }
+        }
+    }
+
+    /// Authors of the selected article.
+    fn selected_authors(&self) -> &[String] {
+        self.selected_article()
+            .map_or(&[], |article| article.authors.as_slice())
+    }
+
+    /// Open the picker over the authors of the selected article.
+    pub fn start_watch_author(&mut self) {
+        self.author_choice = 0;
+        if self.selected_article().is_none() {
+            self.set_context(Context::ArticleList);
+            self.status_message = Some(self.placeholder_message());
+        } else if self.selected_authors().is_empty() {
+            self.set_context(Context::ArticleList);
+        } else {
+            self.set_context(Context::AuthorPicker);
+        }
+    }
+
+    pub fn author_choice_next(&mut self) {
+        let last = self.selected_authors().len().saturating_sub(1);
+        self.author_choice = (self.author_choice + 1).min(last);
+    }
+
+    pub fn author_choice_previous(&mut self) {
+        self.author_choice = self.author_choice.saturating_sub(1);
+    }
+
+    /// Pin the author chosen in the picker.
+    pub fn watch_chosen_author(&mut self) {
+        self.set_context(Context::ArticleList);
+        if let Some(author) = self.selected_authors().get(self.author_choice).cloned() {
+            self.watch(ConfigField::PinnedAuthors, &author);
+        }
+    }
+
+    /// Pin the first author of the selected article, or unpin them if they are pinned.
+    ///
+    /// Only the same name is unpinned, not a shorter pinned name matching it.
+    pub fn toggle_pin_first_author(&mut self) {
+        let Some(author) = self.selected_authors().first().cloned() else {
+            self.status_message = Some("No article selected".to_string());
+            return;
+        };
+        match ConfigField::PinnedAuthors.without_value(&self.config, &author) {
+            Some(values) => self.set_config_field(ConfigField::PinnedAuthors, values),
+            None => self.watch(ConfigField::PinnedAuthors, &author),
+        }
+    }
+
+    /// Open the prompt for a keyword to highlight.
+    pub fn start_watch_keyword(&mut self) {
+        self.keyword_input.clear();
+        self.set_context(Context::KeywordPrompt);
+    }
+
+    /// Highlight the keyword typed in the prompt.
+    pub fn watch_typed_keyword(&mut self) {
+        self.set_context(Context::ArticleList);
+        let keyword = self.keyword_input.trim().to_string();
+        if !keyword.is_empty() {
+            self.watch(ConfigField::Keywords, &keyword);
+        }
+    }
+
+    /// Open the query popup, filled from the current query.
+    pub fn open_query_editor(&mut self) {
+        match &self.query {
+            Some(query) => {
+                self.query_editor = QueryEditor::from_query(query);
+                self.set_context(Context::QueryEditor);
+            }
+            None => self.status_message = Some("No query to edit".to_string()),
+        }
+    }
+
+    /// Run the query of the popup, or show why it can not be run and let the user fix it.
+    pub fn query_editor_submit(&mut self) {
+        let Some(query) = &self.query else {
+            return;
+        };
+        match self.query_editor.to_query(query) {
+            Ok(query) => {
+                self.set_context(Context::ArticleList);
+                if self.search_state.is_active() {
+                    self.search_state.clear();
+                    self.update_feed();
+                }
+                let description = query.description();
+                self.change_query(query);
+                self.status_message = Some(description);
+            }
+            Err(e) => self.query_editor.message = Some(e),
+        }
+    }
+
+    /// Open the prompt for a command.
+    pub fn start_command(&mut self) {
+        self.command_input.clear();
+        self.command_message = None;
+        self.set_context(Context::Command);
+    }
+
+    /// Add a character to the command.
+    pub fn command_push_char(&mut self, c: char) {
+        self.command_input.push(c);
+        self.command_message = None;
+    }
+
+    /// Remove the last character of the command, or close the prompt if it is empty.
+    pub fn command_pop_char(&mut self) {
+        if self.command_input.pop().is_none() {
+            self.set_context(Context::ArticleList);
+        }
+        self.command_message = None;
+    }
+
+    /// Complete the last word of the command, listing the candidates if there are several.
+    pub fn command_complete(&mut self) {
+        let (input, candidates) = command::complete(&self.command_input);
+        self.command_input = input;
+        self.command_message = (!candidates.is_empty()).then(|| candidates.join(" "));
+    }
+
+    /// Run the typed command, or show why it can not be run and let the user fix it.
+    pub fn command_submit(&mut self) {
+        match command::parse(&self.command_input) {
+            Ok(command) => {
+                self.set_context(Context::ArticleList);
+                self.run_command(command);
+            }
+            Err(CommandError::Empty) => self.set_context(Context::ArticleList),
+            Err(e) => self.command_message = Some(e.to_string()),
+        }
+    }
+
+    /// Run the command like the matching key would.
+    pub fn run_command(&mut self, command: Command) {
+        match command {
+            Command::Category(categories) => self.search_categories(&categories),
+            Command::Author(author) => self.search_author(&author),
+            Command::Sort(mode) => self.set_sort(mode),
+            Command::Export(format, path) => {
+                let path = path.unwrap_or_else(|| self.export_path(format));
+                self.export_to(format, path);
+            }
+            Command::Set(Setting::CrossLists(mode)) => self.set_cross_lists(mode),
+            Command::Set(Setting::NewOnly(new_only)) => self.set_new_only(new_only),
+            Command::Refresh => self.request_refresh(),
+            Command::Stats => self.show_stats(),
+            Command::Help => self.show_help(),
+            Command::Quit => self.quit(),
+        }
+    }
+
+    /// Append a value to a list of the config, unless it is already there.
+    pub fn watch(&mut self, field: ConfigField, value: &str) {
+        match field.with_value(&self.config, value) {
+            Some(values) => self.set_config_field(field, values),
+            None => {
+                self.status_message = Some(format!("{value} is already in {}", field.label()));
+            }
+        }
+    }
+
+    /// Open the abstract page of every visible article in the browser.
+    ///
+    /// Above a few articles, the user is asked to confirm first to avoid spawning hundreds
+    /// of tabs by accident.
+    pub fn open_all_visible(&mut self) {
+        if self.search_state.filtered_count() > OPEN_ALL_CONFIRM_THRESHOLD {
+            self.ask_confirmation(ConfirmAction::OpenAll);
+        } else {
+            self.open_all_visible_confirmed();
+        }
+    }
+
+    /// Open every visible article without asking.
+    pub fn open_all_visible_confirmed(&mut self) {
+        let result = self
+            .search_state
+            .filtered_indices
+            .iter()
+            .try_for_each(|&i| self.opener.open(&self.query_result.articles[i].id));
+        self.status_message = Some(match result {
+            Ok(()) => format!(
+                "Opened {} articles in the browser",
+                self.search_state.filtered_count()
+            ),
+            Err(e) => format!("Could not open the browser: {e}"),
+        });
+        self.set_context(Context::ArticleList);
+    }
+
+    /// Message of the confirmation popup.
+    fn confirm_message(&self) -> String {
+        match self.confirm_action {
+            ConfirmAction::OpenAll => format!(
+                "Open {} articles in the browser?",
+                self.search_state.filtered_count()
+            ),
+            ConfirmAction::Requery => format!(
+                "Run the query again in {}?",
+                self.config.query.categories.join(", ")
+            ),
+        }
+    }
+
+    /// Path of the exported file: `<export dir>/arxivlens-<timestamp>.<ext>`.
+    fn export_path(&self, format: ExportFormat) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let dir = self.config.export.dir.as_deref().unwrap_or(".");
+        PathBuf::from(dir).join(format!("arxivlens-{timestamp}.{}", format.extension()))
+    }
+
+    /// Export the articles shown in the feed to a new file of the export directory.
+    pub fn export(&mut self, format: ExportFormat) {
+        self.export_to(format, self.export_path(format));
+    }
+
+    /// Export the articles shown in the feed, only those of the search if any, to the
+    /// file and report the outcome in the status message.
+    pub fn export_to(&mut self, format: ExportFormat, path: PathBuf) {
+        let digest = self.config.export.digest_options();
+        let articles: Vec<ArxivEntry> = self
+            .search_state
+            .filtered_indices
+            .iter()
+            .map(|&i| self.query_result.articles[i].clone())
+            .collect();
+        let result = export_articles(&articles, format, &digest)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+        self.status_message = Some(match result {
+            Ok(()) => format!("Exported {} articles to {}", articles.len(), path.display()),
+            Err(e) => format!("Export failed: {e}"),
+        });
+        self.set_context(Context::ArticleList);
+    }
+
+    /// Restore the selection and scroll position of a previous session.
+    ///
+    /// The article is looked up by id, since the feed may have changed in between. If the
+    /// previously selected article is not in the feed anymore, nothing is selected.
+    pub fn restore_session(&mut self, session: &Session) {
+        let index = session.selected_id.as_ref().and_then(|id| {
+            self.search_state
+                .filtered_indices
+                .iter()
+                .position(|&i| &self.query_result.articles[i].id == id)
+        });
+        if let Some(i) = index {
+            *self.article_feed.state.offset_mut() = session.offset.min(i);
+            self.article_feed.state.select(Some(i));
+        }
+    }
+
+    /// Snapshot of the current state to be persisted for the next session.
+    pub fn session(&self, query: &str) -> Session {
+        let selected_id = self
+            .get_actual_article_index()
+            .and_then(|i| self.query_result.articles.get(i))
+            .map(|article| article.id.clone());
+        Session::new(query, selected_id, self.article_feed.state.offset())
+    }
+
+    /// Counts shown on the right of the footer: the position of the selected article
+    /// among the visible ones, the search narrowing the feed, the hidden cross-lists, the
+    /// new articles and the pinned ones, e.g.
+    /// `3/12 (filtered from 200 by "qubit") · 4 new · 2 pinned`.
+    fn footer_counts(&self) -> String {
+        let visible = self.search_state.filtered_count();
+        let position = self
+            .article_feed
+            .state
+            .selected()
+            .filter(|&i| i < visible)
+            .map_or_else(|| "-".to_string(), |i| (i + 1).to_string());
+        let mut counts = format!("{position}/{visible}");
+        if self.search_state.is_active() {
+            counts.push_str(&format!(
+                " (filtered from {} by \"{}\")",
+                self.search_state.total_count(),
+                self.search_state.query
+            ));
+        }
+        if self.search_state.cross_lists() == CrossListMode::Hide {
+            let hidden = self.search_state.cross_list_count();
+            if hidden > 0 {
+                counts.push_str(&format!(" · {hidden} cross-lists hidden"));
+            }
+        }
+        let new = self.new_count();
+        if new > 0 {
+            counts.push_str(&format!(" · {new} new"));
+        }
+        let pinned: usize = self.pinned_feeds.iter().map(PinnedFeed::len).sum();
+        if pinned > 0 {
+            counts.push_str(&format!(" · {pinned} pinned"));
+        }
+        counts
+    }
+
+    /// Footer of the current context: its shortcuts with hints for the secondary actions,
+    /// or the status message if any, with the counts on the right.
+    fn footer(&self, width: u16) -> Footer {
+        let (label, shortcuts, hints): (Option<&str>, &[Shortcut], &[Shortcut]) =
+            match (self.context, &self.status_message) {
+                (Context::Export, _) => (
+                    Some("export as"),
+                    &[
+                        ("BibTeX", "b"),
+                        ("JSON", "j"),
+                        ("CSV", "c"),
+                        ("Markdown", "m"),
+                        ("HTML", "h"),
+                        ("cancel", "Esc"),
+                    ],
+                    &[],
+                ),
+                (Context::Search, _) => (
+                    self.search_state.title_only.then_some("Title-only"),
+                    &[("cancel", "Esc"), ("apply", "Enter"), ("move", "↑/↓")],
+                    &[("cursor", "←/→"), ("title only", "Ctrl-t")],
+                ),
+                (Context::Config, None) if self.config_editor.is_editing() => (
+                    None,
+                    &[("save", "Enter"), ("cancel", "Esc")],
+                    &[("lists", "comma separated")],
+                ),
+                (Context::QueryEditor, _) => (
+                    Some("Query"),
+                    &[("move", "↑/↓"), ("run", "Enter"), ("cancel", "Esc")],
+                    &[("complete the category", "Tab")],
+                ),
+                (Context::Config, None) => (
+                    None,
+                    &[("move", "j/k"), ("edit", "Enter"), ("close", "Esc")],
+                    &[],
+                ),
+                (Context::Watch, _) => (
+                    Some("watch"),
+                    &[
+                        ("author of the article", "a"),
+                        ("keyword", "k"),
+                        ("cancel", "Esc"),
+                    ],
+                    &[],
+                ),
+                (Context::AuthorPicker, _) => (
+                    None,
+                    &[("move", "j/k"), ("pin", "Enter"), ("cancel", "Esc")],
+                    &[],
+                ),
+                (Context::KeywordPrompt, _) => {
+                    (None, &[("highlight", "Enter"), ("cancel", "Esc")], &[])
+                }
+                (Context::Error, _) => (None, &[("dismiss", "any key")], &[]),
+                (Context::Help, _) => (
+                    Some("Help"),
+                    &[("scroll", "j/k"), ("page", "PgDn/PgUp"), ("close", "Esc")],
+                    &[],
+                ),
+                (Context::Stats, _) => (
+                    Some("Stats"),
+                    &[("scroll", "j/k"), ("page", "PgDn/PgUp"), ("close", "Esc")],
+                    &[],
+                ),
+                (Context::Pinned, None) => (
+                    Some("Pinned"),
+                    &[
+                        ("move", "j/k"),
+                        ("yank url", "y"),
+                        ("feed", "Tab"),
+                        ("back", "Esc"),
+                    ],
+                    &[("expand/collapse", "z"), ("help", "?")],
+                ),
+                (Context::Command, _) => {
+                    return Footer::prompt(&self.command_input, self.command_message.as_deref())
+                }
+                (_, Some(message)) => return Footer::message(message, self.footer_counts()),
+                (Context::Confirm, None) => (None, &[("yes", "y"), ("no", "n")], &[]),
+                (Context::ArticleList, None) => (
+                    self.search_state.is_active().then_some("clear search: Esc"),
+                    &[
+                        ("quit", "q"),
+                        ("move", "j/k"),
+                        ("search", "/"),
+                        ("yank url", "y"),
+                        ("export", "e"),
+                        ("refresh", "r"),
+                        ("config", "c"),
+                    ],
+                    &[("open all", "O"), ("watch", "+a/+k"), ("help", "?")],
+                ),
+            };
+        let hints = if self.config.ui.footer_hints.unwrap_or(true) {
+            hints
+        } else {
+            &[]
+        };
+        Footer::shortcuts(label, shortcuts, hints, self.footer_counts(), width)
+    }
+
+    /// Header above the feeds: the query, when arXiv updated the feed and when it was
+    /// last refreshed, in local time.
+    fn header(&self, width: u16) -> Header {
+        let mut parts: Vec<String> = self.query.iter().map(ArxivQuery::summary).collect();
+        if self.search_state.sort_mode != SortMode::Feed {
+            parts.push(format!("sorted by {}", self.sort_label()));
+        }
+        parts.extend(
+            format_local(&self.query_result.updated).map(|updated| format!("updated {updated}")),
+        );
+        parts.extend(
+            self.last_refresh
+                .as_deref()
+                .and_then(format_local)
+                .map(|refreshed| format!("refreshed {refreshed}")),
+        );
+        // The names of the categories come last, being left out first.
+        let names: Vec<&str> = self
+            .query
+            .iter()
+            .flat_map(ArxivQuery::categories)
+            .filter_map(category_name)
+            .collect();
+        if !names.is_empty() {
+            parts.push(names.join(", "));
+        }
+        Header::new(&parts, width)
+    }
+
+    /// Render the app:
+    pub fn render(&mut self, frame: &mut Frame) {
+        // The layout does not fit in a too small terminal, a message replaces it
+        let min_width = self.config.ui.min_width.unwrap_or(MIN_WIDTH);
+        let min_height = self.config.ui.min_height.unwrap_or(MIN_HEIGHT);
+        let size = frame.size();
+        if size.width < min_width || size.height < min_height {
+            LoadingScreen::too_small(min_width, min_height).render_bare(frame, size, &self.theme);
+            return;
+        }
+
+        // The loading and error screens replace the whole interface
+        match &self.feed_status {
+            FeedStatus::Loading => {
+                let description = self.query.as_ref().map_or_else(
+                    || "Fetching the papers…".to_string(),
+                    ArxivQuery::description,
+                );
+                LoadingScreen::loading(&description, self.ticks, self.loading_started.elapsed())
+                    .render(frame, frame.size(), &self.theme);
+                return;
+            }
+            FeedStatus::Error(message) => {
+                LoadingScreen::error(message).render(frame, frame.size(), &self.theme);
+                return;
+            }
+            FeedStatus::Ready => {}
+        }
+
+        // First we create a Layout
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Percentage(100),
+                Constraint::Min(1),
+            ])
+            .split(frame.size());
+
+        // adding the query and the times of the feed on top
+        self.header(layout[0].width)
+            .render(frame, layout[0], &self.theme);
+
+        // adding the shortcut, or the status message if any
+        self.footer(layout[2].width)
+            .render(frame, layout[2], &self.theme);
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .horizontal_margin(2)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(layout[1]);
+
+        // Render the pinned feeds stacked on top of the main feed
+        let pinned_heights: Vec<u16> = (0..self.pinned_feeds.len())
+            .map(|i| self.pinned_height(i))
+            .collect();
+        let feed_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                pinned_heights
+                    .iter()
+                    .map(|&height| Constraint::Length(height))
+                    .chain([Constraint::Min(1)]),
+            )
+            .split(layout[0]);
+        let pinned_focused = self.context == Context::Pinned;
+        for (i, feed) in self.pinned_feeds.iter_mut().enumerate() {
+            let focused = pinned_focused && i == self.pinned_focus;
+            feed.render(frame, feed_layout[i], focused);
+        }
+        let feed_area = feed_layout[self.pinned_feeds.len()];
+
+        // Render the search bar on top of the feed while searching
+        let feed_area = if self.context == Context::Search {
+            let search_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(1)])
+                .split(feed_area);
+            let (before, after) = self.search_state.query.split_at(self.search_state.cursor());
+            let width = usize::from(search_layout[0].width.saturating_sub(2));
+            frame.render_widget(
+                Paragraph::new(scroll_to_cursor(&format!("/{before}"), after, width))
+                    .style(self.theme.main)
+                    .block(
+                        Block::bordered()
+                            .title_style(self.theme.title)
+                            .title(" Search ")
+                            .title(
+                                Line::from(format!(" {} ", self.search_state.status()))
+                                    .alignment(Alignment::Right),
+                            ),
+                    ),
+                search_layout[0],
+            );
+            search_layout[1]
+        } else {
+            feed_area
+        };
+
+        // Render the slectable feed
+        self.article_feed
+            .render(frame, feed_area, &self.theme, !pinned_focused);
+
+        // Render the detail of the article selected:
+        let placeholder = self.placeholder_message();
+        let current_entry = self
+            .selected_article_index()
+            .and_then(|i| self.query_result.articles.get(i));
+
+        let article_view = match current_entry {
+            Some(entry) => ArticleDetails::new(
+                entry,
+                layout[1].width,
+                &self.effective_config.highlight,
+                &mut self.search_state,
+                &self
+                    .effective_config
+                    .ui
+                    .date_format
+                    .clone()
+                    .unwrap_or_default(),
+                &self.theme,
+            )
+            .with_summary_width(self.effective_config.ui.summary_wrap_width),
+            None => ArticleDetails::placeholder(&placeholder),
+        };
+        article_view.render(frame, layout[1], &self.theme);
+
+        match self.context {
+            Context::Confirm => {
+                let message = self.confirm_message();
+                ConfirmPopup::new(&message).render(frame, frame.size(), &self.theme);
+            }
+            Context::Config => {
+                ConfigPopup::new(&self.effective_config, &self.config_editor)
+                    .source(self.config_path.as_deref(), self.profile.as_deref())
+                    .warnings(&self.config_warnings)
+                    .render(frame, frame.size(), &self.theme, &mut self.config_state);
+            }
+            Context::AuthorPicker => {
+                ChoicePopup::new(
+                    " Pin an author ",
+                    self.selected_authors(),
+                    self.author_choice,
+                )
+                .render(frame, frame.size(), &self.theme);
+            }
+            Context::QueryEditor => {
+                QueryPopup::new(&self.query_editor).render(frame, frame.size(), &self.theme);
+            }
+            Context::KeywordPrompt => {
+                InputPopup::new(" Highlight a keyword ", &self.keyword_input).render(
+                    frame,
+                    frame.size(),
+                    &self.theme,
+                );
+            }
+            Context::Help => {
+                self.popup_page = HelpPopup::new(KEY_HELP).render(
+                    frame,
+                    frame.size(),
+                    &self.theme,
+                    &mut self.popup_state,
+                );
+            }
+            Context::Stats => {
+                if let Some(stats) = &self.stats {
+                    self.popup_page = StatsPopup::new(stats).render(
+                        frame,
+                        frame.size(),
+                        &self.theme,
+                        &mut self.popup_state,
+                    );
+                }
+            }
+            Context::Error => {
+                let message = self.error_message.as_deref().unwrap_or_default();
+                ErrorPopup::new(message).render(frame, frame.size(), &self.theme);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arxiv::{get_query_url, SearchQuery, SortBy, SortOrder};
+    use crate::config::ExportConfig;
+    use crate::ui::PINNED_COLLAPSED_HEIGHT;
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+
+    /// Records the opened urls instead of launching a browser.
+    #[derive(Debug, Default)]
+    struct RecordingOpener {
+        urls: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl UrlOpener for RecordingOpener {
+        fn open(&mut self, url: &str) -> io::Result<()> {
+            self.urls.borrow_mut().push(url.to_string());
+            Ok(())
+        }
+    }
+
+    /// Clipboard keeping the copied texts.
+    #[derive(Debug)]
+    struct RecordingClipboard {
+        texts: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl ClipboardWriter for RecordingClipboard {
+        fn set_text(&mut self, text: String) -> io::Result<()> {
+            self.texts.borrow_mut().push(text);
+            Ok(())
+        }
+    }
+
+    fn fixture_feed_of(count: usize) -> ArxivQueryResult {
+        let articles = (0..count)
+            .map(|i| {
+                ArxivEntry::new(
+                    format!("Title {i}"),
+                    vec![format!("Author {i}")],
+                    format!("Summary {i}"),
+                    format!("http://arxiv.org/abs/0000.0000{i}"),
+                    "2024-01-01T00:00:00Z".to_string(),
+                    "2024-01-01T00:00:00Z".to_string(),
+                )
+            })
+            .collect();
+        ArxivQueryResult {
+            updated: "2024-01-01T00:00:00Z".to_string(),
+            total_results: None,
+            articles,
+        }
+    }
+
+    fn fixture_feed() -> ArxivQueryResult {
+        fixture_feed_of(5)
+    }
+
+    #[test]
+    fn test_pending_session_restored_with_first_articles() {
+        let no_articles = ArxivQueryResult::default();
+        let config = Config::default();
+        let mut app = App::new(&no_articles, &config, Theme::default());
+        let session = Session::new("query", Some("http://arxiv.org/abs/0000.00003".into()), 2);
+        app.pending_session = Some(session.clone());
+        app.start_loading();
+
+        app.finish_refresh(Ok(fixture_feed()));
+        assert_eq!(app.session("query"), session);
+        assert!(app.pending_session.is_none());
+
+        // Later refreshes keep the current selection.
+        app.article_feed.state.select(Some(1));
+        app.finish_refresh(Ok(fixture_feed()));
+        assert_eq!(app.article_feed.state.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_restore_session_selection() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        let session = Session::new("query", Some("http://arxiv.org/abs/0000.00003".into()), 2);
+        app.restore_session(&session);
+
+        assert_eq!(app.article_feed.state.selected(), Some(3));
+        assert_eq!(app.article_feed.state.offset(), 2);
+        assert_eq!(app.session("query"), session);
+    }
+
+    /// Lines of the rendered app.
+    fn render_lines(app: &mut App) -> Vec<String> {
+        render_lines_sized(app, 120, 40)
+    }
+
+    /// Lines of the app rendered in a terminal of the given size.
+    fn render_lines_sized(app: &mut App, width: u16, height: u16) -> Vec<String> {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_too_small_terminal() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let lines = render_lines_sized(&mut app, 10, 4);
+        assert_eq!(lines[0].trim(), "Terminal");
+        assert_eq!(lines[1].trim(), "too small");
+        assert!(!lines.iter().any(|line| line.contains("Title")));
+
+        config.ui.min_width = Some(8);
+        config.ui.min_height = Some(4);
+        let mut app = App::new(&feed, &config, Theme::default());
+        let lines = render_lines_sized(&mut app, 10, 4);
+        assert!(!lines.iter().any(|line| line.contains("too small")));
+    }
+
+    #[test]
+    fn test_cycle_sort_keeps_the_selection() {
+        let mut feed = fixture_feed();
+        for (i, article) in feed.articles.iter_mut().enumerate() {
+            article.published = format!("2024-01-0{}T00:00:00Z", [3, 5, 1, 4, 2][i]);
+            article.updated = format!("2024-02-0{}T00:00:00Z", [1, 2, 3, 4, 5][i]);
+            article.title = ["delta", "Alpha", "echo", "charlie", "bravo"][i].to_string();
+        }
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.article_feed.state.select(Some(2));
+
+        let mut orders = Vec::new();
+        for _ in 0..4 {
+            app.cycle_sort();
+            // The article selected before stays selected, wherever it moved.
+            assert_eq!(app.get_actual_article_index(), Some(2));
+            orders.push((
+                app.status_message.clone().unwrap(),
+                app.search_state.filtered_indices.clone(),
+            ));
+        }
+        let order = |message: &str, indices: [usize; 5]| (message.to_string(), indices.to_vec());
+        assert_eq!(
+            orders,
+            [
+                order("Sorted by published date", [1, 3, 0, 4, 2]),
+                order("Sorted by updated date", [4, 3, 2, 1, 0]),
+                order("Sorted by title", [1, 4, 3, 0, 2]),
+                order("Sorted by feed order", [0, 1, 2, 3, 4]),
+            ]
+        );
+
+        // The preview shows the article at the selected position of the sorted feed.
+        app.cycle_sort();
+        app.select_first();
+        let selected = app.get_actual_article_index().unwrap();
+        assert_eq!(app.query_result.articles[selected].title, "Alpha");
+        assert!(app.header(120).text().contains("sorted by published date"));
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("Summary 1")));
+        assert!(!lines.iter().any(|line| line.contains("Summary 0")));
+    }
+
+    #[test]
+    fn test_cross_lists_dimmed_or_hidden() {
+        let mut feed = fixture_feed();
+        for (article, category) in feed
+            .articles
+            .iter_mut()
+            .zip(["quant-ph", "cs.LG", "quant-ph", "math-ph", "quant-ph"])
+        {
+            article.primary_category = Some(category.to_string());
+        }
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+        app.update_feed();
+        app.article_feed.state.select(Some(2));
+
+        app.cycle_cross_lists();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Cross-lists dimmed, after the other articles")
+        );
+        assert_eq!(app.search_state.filtered_indices, [0, 2, 4, 1, 3]);
+        assert_eq!(app.get_actual_article_index(), Some(2));
+        // The rows of the cross-lists are dimmed.
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row_of = |title: &str| {
+            (0..buffer.area.height)
+                .find(|&y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer.get(x, y).symbol())
+                        .collect::<String>()
+                        .contains(title)
+                })
+                .unwrap()
+        };
+        let is_dim = |y: u16| {
+            buffer
+                .get(5, y)
+                .modifier
+                .contains(ratatui::style::Modifier::DIM)
+        };
+        assert!(is_dim(row_of("Title 1")));
+        assert!(!is_dim(row_of("Title 4")));
+
+        app.cycle_cross_lists();
+        assert_eq!(app.search_state.filtered_indices, [0, 2, 4]);
+        assert_eq!(app.get_actual_article_index(), Some(2));
+        assert!(app
+            .footer_counts()
+            .starts_with("2/3 · 2 cross-lists hidden"));
+
+        // The search only goes through the articles left.
+        app.set_context(Context::Search);
+        app.search_push_char('3');
+        assert_eq!(app.search_state.filtered_count(), 0);
+        app.search_pop_char();
+        app.search_push_char('4');
+        assert_eq!(app.search_state.filtered_indices, [4]);
+        app.set_context(Context::ArticleList);
+
+        app.cycle_cross_lists();
+        assert_eq!(app.status_message.as_deref(), Some("Cross-lists shown"));
+        assert_eq!(app.search_state.filtered_count(), 5);
+    }
+
+    #[test]
+    fn test_stats_of_the_search() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.show_stats();
+        assert_eq!(app.context, Context::Stats);
+        assert_eq!(app.stats.as_ref().map(|stats| stats.articles), Some(5));
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("Stats · 5 articles")));
+        assert!(lines.iter().any(|line| line.contains("2024-01-01 Mon")));
+        app.close_stats();
+        assert_eq!(app.context, Context::ArticleList);
+
+        // The stats are computed again once the search narrows the feed.
+        app.set_context(Context::Search);
+        app.search_push_char('3');
+        assert_eq!(app.stats, None);
+        app.show_stats();
+        let stats = app.stats.clone().unwrap();
+        assert_eq!(stats.articles, 1);
+        assert_eq!(stats.authors, [("Author 3".to_string(), 1)]);
+        app.close_stats();
+        assert_eq!(app.context, Context::Search);
+    }
+
+    #[test]
+    fn test_help_scroll_and_close() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        let mut app = pinned_app(&feed, &mut config);
+        app.focus_next_feed();
+        app.show_help();
+        // One row per key, a title per section and a blank line between sections.
+        let rows = KEY_HELP
+            .iter()
+            .map(|(_, keys)| keys.len() + 2)
+            .sum::<usize>()
+            - 1;
+        let lines = render_lines_sized(&mut app, 80, 24);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains(&format!("1-22 of {rows}"))));
+        assert_eq!(app.popup_page, 22);
+
+        // Past the end, the popup stops at its last row.
+        for _ in 0..rows.div_ceil(app.popup_page) {
+            app.scroll_popup(app.popup_page as isize);
+        }
+        let lines = render_lines_sized(&mut app, 80, 24);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains(&format!("{}-{rows} of {rows}", rows - 21))));
+        app.scroll_popup(-1);
+        let lines = render_lines_sized(&mut app, 80, 24);
+        assert!(lines.iter().any(|line| line.contains(&format!(
+            "{}-{} of {rows}",
+            rows - 22,
+            rows - 1
+        ))));
+
+        // Back to the pinned feed the help was opened from.
+        app.close_help();
+        assert_eq!(app.context, Context::Pinned);
+        app.show_help();
+        assert_eq!(app.popup_state.offset(), 0);
+    }
+
+    #[test]
+    fn test_yank_citation() {
+        use crate::export::CitationStyle;
+
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        config.export.citation_style = Some(CitationStyle::Mla);
+        let mut app = App::new(&feed, &config, Theme::default());
+        let texts = Rc::new(RefCell::new(Vec::new()));
+        app.clipboard = Box::new(RecordingClipboard {
+            texts: texts.clone(),
+        });
+
+        app.yank_citation();
+        app.cycle_citation_style();
+        assert_eq!(app.status_message.as_deref(), Some("Citation style: APA"));
+        app.yank_citation();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Copied the APA citation")
+        );
+        assert_eq!(
+            *texts.borrow(),
+            [
+                "0, Author. \"Title 0.\" arXiv preprint arXiv:0000.00000, 2024.",
+                "0, A. (2024). Title 0. arXiv preprint arXiv:0000.00000.",
+            ]
+        );
+
+        app.select_none();
+        app.yank_citation();
+        assert_eq!(texts.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_yank_summary_block() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        let mut app = pinned_app(&feed, &mut config);
+        let texts = Rc::new(RefCell::new(Vec::new()));
+        app.clipboard = Box::new(RecordingClipboard {
+            texts: texts.clone(),
+        });
+
+        app.focus_next_feed();
+        app.yank_summary_block();
+        assert_eq!(
+            *texts.borrow(),
+            ["Title 1\nAuthor 1\nhttp://arxiv.org/abs/0000.00001\n\nSummary 1\n"]
+        );
+    }
+
+    #[test]
+    fn test_yank_query_url() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let texts = Rc::new(RefCell::new(Vec::new()));
+        app.clipboard = Box::new(RecordingClipboard {
+            texts: texts.clone(),
+        });
+
+        app.yank_query_url();
+        assert_eq!(app.status_message.as_deref(), Some("No query to copy"));
+
+        let search_queries = vec![
+            SearchQuery::Category("quant-ph".to_string()),
+            SearchQuery::Author("Preskill".to_string()),
+        ];
+        let url = get_query_url(
+            Some(&search_queries),
+            Some(40),
+            Some(20),
+            Some(SortBy::LastUpdatedDate),
+            Some(SortOrder::Ascending),
+        );
+        app.query = Some(ArxivQuery {
+            search_queries,
+            start_index: 40,
+            max_results: 20,
+            sort_by: SortBy::LastUpdatedDate,
+            sort_order: SortOrder::Ascending,
+            id_list: Vec::new(),
+        });
+        app.yank_query_url();
+        assert_eq!(*texts.borrow(), [url]);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Copied the url of the query")
+        );
+    }
+
+    #[test]
+    fn test_footer_context_hints() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        assert!(app.footer(200).text().contains("open all: O"));
+        assert!(!app.footer(60).text().contains("open all: O"));
+
+        app.set_context(Context::Search);
+        assert!(app.footer(200).text().contains("title only: Ctrl-t"));
+        app.search_toggle_title_only();
+        assert!(app.footer(200).text().starts_with("   Title-only"));
+        app.search_push_char('3');
+        app.search_submit();
+        assert!(app
+            .footer(200)
+            .text()
+            .starts_with("   clear search: Esc | quit: q"));
+        assert!(app.footer_counts().contains("(filtered from 5 by \"3\")"));
+
+        let config = Config {
+            ui: crate::config::UiConfig {
+                footer_hints: Some(false),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let app = App::new(&feed, &config, Theme::default());
+        assert!(!app.footer(200).text().contains("open all: O"));
+    }
+
+    #[test]
+    fn test_footer_of_each_context() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        config.pinned.authors = Some(vec!["Author 1".to_string()]);
+        let mut app = App::new(&feed, &config, Theme::default());
+        let footer = |app: &mut App, width| render_lines_sized(app, width, 30).pop().unwrap();
+
+        assert_eq!(
+            footer(&mut app, 80),
+            "   quit: q | move: j/k | search: / | yank url: y | export: e     1/5 · 1 pinned "
+        );
+        assert_eq!(footer(&mut app, 35), "   quit: q          1/5 · 1 pinned ");
+
+        app.set_context(Context::Search);
+        assert_eq!(
+            footer(&mut app, 80),
+            "   cancel: Esc | apply: Enter | move: ↑/↓ | cursor: ←/→          1/5 · 1 pinned "
+        );
+        assert_eq!(footer(&mut app, 35), "   cancel: Esc      1/5 · 1 pinned ");
+
+        app.set_context(Context::ArticleList);
+        app.focus_next_feed();
+        assert_eq!(app.context, Context::Pinned);
+        assert_eq!(
+            footer(&mut app, 80),
+            "   Pinned | move: j/k | yank url: y | feed: Tab | back: Esc      1/5 · 1 pinned "
+        );
+        assert_eq!(footer(&mut app, 35), "   Pinned           1/5 · 1 pinned ");
+    }
+
+    #[test]
+    fn test_footer_counts_wide() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        config.pinned.authors = Some(vec!["Author 1".to_string(), "Author 3".to_string()]);
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.select_next();
+
+        let lines = render_lines(&mut app);
+        let footer = lines.last().unwrap();
+        assert!(footer.starts_with("   quit: q | move: j/k"));
+        assert!(footer.ends_with("2/5 · 2 pinned "));
+    }
+
+    #[test]
+    fn test_footer_counts_filtered() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.set_context(Context::Search);
+        "Title 3".chars().for_each(|c| app.search_push_char(c));
+
+        let lines = render_lines(&mut app);
+        let footer = lines.last().unwrap();
+        assert!(footer.starts_with("   cancel: Esc"));
+        assert!(footer.ends_with("1/1 (filtered from 5 by \"Title 3\") "));
+    }
+
+    #[test]
+    fn test_footer_counts_narrow() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        let lines = render_lines_sized(&mut app, 30, 20);
+        assert_eq!(lines.last().unwrap(), "   quit: q | move: j/k    1/5 ");
+    }
+
+    #[test]
+    fn test_search_highlights_computed_once() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.set_context(Context::Search);
+        "Title".chars().for_each(|c| app.search_push_char(c));
+        render_lines(&mut app);
+        let computations = app.search_state.highlight_computations();
+
+        // The next frame reuses the highlights of the feed and the details.
+        render_lines(&mut app);
+        assert_eq!(app.search_state.highlight_computations(), computations);
+        // Only the abstract of the newly selected article is matched, the titles were
+        // with the feed.
+        app.select_next();
+        render_lines(&mut app);
+        assert_eq!(app.search_state.highlight_computations(), computations + 1);
+    }
+
+    #[test]
+    fn test_render_dates_in_format() {
+        let feed = fixture_feed();
+        let config = Config {
+            ui: crate::config::UiConfig {
+                date_format: Some("%d/%m/%Y".parse().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut app = App::new(&feed, &config, Theme::default());
+        let date = crate::date::format_date(
+            "2024-01-01T00:00:00Z",
+            config.ui.date_format.as_ref().unwrap(),
+            chrono::Utc::now(),
+        );
+
+        let lines = render_lines(&mut app);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains(&format!("Title 0  {date}"))));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains(&format!("Published     {date}"))));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains(&format!("Last updated  {date}"))));
+    }
+
+    #[test]
+    fn test_render_wide_characters_and_long_author_lists() {
+        let mut feed = fixture_feed();
+        feed.articles[0] = ArxivEntry::new(
+            format!("量子誤り訂正 🚀 Re\u{301}sume\u{301} {}", "長".repeat(60)),
+            (0..300).map(|i| format!("Author {i}")).collect(),
+            "Summary".to_string(),
+            "http://arxiv.org/abs/0000.00000".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+        );
+        let mut config = Config::default();
+        config.pinned.authors = Some(vec!["Author 1".to_string()]);
+        config.ui.max_title_len = Some(30);
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("量")));
+        assert!(lines.iter().any(|line| line.contains("… et al.")));
+        assert!(!lines.iter().any(|line| line.contains("Author 299")));
+    }
+
+    #[test]
+    fn test_page_label_in_feed_title() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+        assert!(!render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("arXiv Feed · page")));
+
+        app.finish_refresh(Ok(ArxivQueryResult {
+            total_results: Some(4321),
+            ..fixture_feed()
+        }));
+        assert!(render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("arXiv Feed · page 1 of 22")));
+    }
+
+    #[test]
+    fn test_prefetch_near_end() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        config.query.prefetch_margin = Some(2);
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 5,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+        app.select_next();
+        app.select_next();
+        assert_eq!(app.take_prefetch_query(), None);
+
+        // Nearing the end asks for the next articles once.
+        app.select_next();
+        let (start, url) = app.take_prefetch_query().unwrap();
+        assert_eq!(start, 5);
+        assert!(url.contains("start=5&max_results=5"));
+        app.select_next();
+        app.select_last();
+        assert_eq!(app.take_prefetch_query(), None);
+
+        let mut next = fixture_feed_of(10);
+        next.articles.drain(..5);
+        app.finish_prefetch(start, Ok(next));
+        assert_eq!(app.query_result.articles.len(), 10);
+        assert_eq!(app.selected_article_index(), Some(4));
+        assert_eq!(app.article_feed.state.selected(), Some(4));
+
+        // The same articles are not appended twice.
+        app.finish_prefetch(start, Ok(fixture_feed()));
+        assert_eq!(app.query_result.articles.len(), 10);
+    }
+
+    #[test]
+    fn test_header_updates_after_refresh() {
+        let no_articles = ArxivQueryResult::default();
+        let config = Config::default();
+        let mut app = App::new(&no_articles, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+        let header = &render_lines(&mut app)[0];
+        assert!(header
+            .starts_with("   cat:quant-ph · sorted by submittedDate desc · Quantum Physics   "));
+
+        app.finish_refresh(Ok(fixture_feed()));
+        let header = &render_lines(&mut app)[0];
+        let updated = format_local("2024-01-01T00:00:00Z").unwrap();
+        assert!(header.contains(&format!(" · updated {updated} · refreshed ")));
+
+        let header = &render_lines_sized(&mut app, 30, 20)[0];
+        assert_eq!(header, "   cat:quant-ph · sorted by s…");
+    }
+
+    #[test]
+    fn test_initial_load_error_then_retry() {
+        let no_articles = ArxivQueryResult::default();
+        let config = Config::default();
+        let mut app = App::new(&no_articles, &config, Theme::default());
+        app.start_loading();
+        assert!(render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("Fetching the papers…")));
+
+        app.finish_refresh(Err(ArxivQueryError::Request("timed out".to_string())));
+        assert_eq!(
+            app.feed_status,
+            FeedStatus::Error("Could not query arXiv: timed out".to_string())
+        );
+        assert!(render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("retry: r | quit: q")));
+
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+        app.request_refresh();
+        assert_eq!(app.feed_status, FeedStatus::Loading);
+        assert!(app.take_refresh_query().is_some());
+
+        app.finish_refresh(Ok(fixture_feed()));
+        assert_eq!(app.feed_status, FeedStatus::Ready);
+        assert_eq!(app.search_state.filtered_count(), 5);
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn test_render_empty_feed() {
+        let feed = fixture_feed_of(0);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.select_next();
+        app.select_last();
+
+        let lines = render_lines_sized(&mut app, 200, 40);
+        // In the list, and with what to do about it in the preview.
+        assert!(lines.iter().any(|line| line.contains("The feed is empty")));
+        assert!(lines.iter().any(|line| line
+            .contains("No articles for this query: press r to refresh or c to change the query.")));
+    }
+
+    #[test]
+    fn test_empty_feed_actions() {
+        let feed = fixture_feed_of(0);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+        let texts = Rc::new(RefCell::new(Vec::new()));
+        app.clipboard = Box::new(RecordingClipboard {
+            texts: texts.clone(),
+        });
+        let empty = "No articles for cat:quant-ph: press r to refresh or c to change the query.";
+
+        let lines = render_lines_sized(&mut app, 200, 40);
+        assert!(lines.iter().any(|line| line.contains(empty)));
+
+        for action in [
+            App::yank_id,
+            App::yank_citation,
+            App::search_selected_author,
+            App::search_selected_category,
+            App::start_watch_author,
+        ] {
+            app.status_message = None;
+            action(&mut app);
+            assert_eq!(app.status_message.as_deref(), Some(empty));
+            assert_eq!(app.context, Context::ArticleList);
+        }
+        assert!(texts.borrow().is_empty());
+        assert!(!app.refresh_pending);
+
+        app.select_next();
+        app.select_previous();
+        app.select_last();
+        app.focus_next_feed();
+        app.set_context(Context::Search);
+        "qubit".chars().for_each(|c| app.search_push_char(c));
+        app.select_next();
+        render_lines(&mut app);
+        app.set_context(Context::ArticleList);
+        assert_eq!(app.selected_article_index(), None);
+        assert_eq!(app.footer_counts(), "-/0");
+    }
+
+    #[test]
+    fn test_render_no_search_match() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.set_context(Context::Search);
+        "zzzz".chars().for_each(|c| app.search_push_char(c));
+
+        let lines = render_lines(&mut app);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("No article matches the search")));
+    }
+
+    #[test]
+    fn test_new_badge_since_last_run() {
+        let mut feed = fixture_feed_of(2);
+        feed.articles[1].published = "2024-01-03T08:00:00Z".to_string();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.mark_new_since(Some("2024-01-02T00:00:00Z".to_string()));
+
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("NEW Title 1")));
+        assert!(!lines.iter().any(|line| line.contains("NEW Title 0")));
+    }
+
+    #[test]
+    fn test_new_articles_count_jump_and_filter() {
+        let mut feed = fixture_feed();
+        feed.articles[2].published = "2024-01-03T08:00:00Z".to_string();
+        feed.articles[4].published = "2024-01-04T08:00:00Z".to_string();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        // Nothing is new without a previous run.
+        app.toggle_new_only();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No previous run to compare with")
+        );
+        app.select_first_new();
+        assert_eq!(app.status_message.as_deref(), Some("No new articles"));
+
+        app.mark_new_since(Some("2024-01-02T00:00:00Z".to_string()));
+        assert_eq!(app.footer_counts(), "1/5 · 2 new");
+        app.select_first_new();
+        assert_eq!(app.get_actual_article_index(), Some(2));
+
+        app.select_next();
+        app.toggle_new_only();
+        assert_eq!(app.search_state.filtered_indices, [2, 4]);
+        assert_eq!(app.get_actual_article_index(), Some(2));
+        assert_eq!(app.footer_counts(), "1/2 · 2 new");
+
+        // The search goes through the new articles only.
+        app.set_context(Context::Search);
+        app.search_push_char('4');
+        assert_eq!(app.search_state.filtered_indices, [4]);
+        app.set_context(Context::ArticleList);
+
+        app.toggle_new_only();
+        assert_eq!(app.status_message.as_deref(), Some("All the articles"));
+        assert_eq!(app.search_state.filtered_count(), 5);
+    }
+
+    #[test]
+    fn test_refresh_reruns_same_query() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let search_queries = vec![SearchQuery::Category("quant-ph".to_string())];
+        let url = get_query_url(
+            Some(&search_queries),
+            Some(0),
+            Some(200),
+            Some(SortBy::SubmittedDate),
+            Some(SortOrder::Descending),
+        );
+        app.query = Some(ArxivQuery {
+            search_queries,
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+
+        assert_eq!(app.take_refresh_query(), None);
+        app.request_refresh();
+        assert_eq!(app.take_refresh_query(), Some(url));
+        assert_eq!(app.take_refresh_query(), None);
+    }
+
+    #[test]
+    fn test_refresh_replaces_feed_and_keeps_selection() {
+        let feed = fixture_feed_of(3);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.article_feed.state.select(Some(1));
+
+        // A new article is published on top of the feed.
+        let mut refreshed = fixture_feed_of(4);
+        refreshed.articles.rotate_right(1);
+        app.finish_refresh(Ok(refreshed));
+
+        assert_eq!(app.query_result.articles.len(), 4);
+        assert_eq!(app.search_state.filtered_count(), 4);
+        let selected = app.get_actual_article_index().unwrap();
+        assert_eq!(
+            app.query_result.articles[selected].id,
+            "http://arxiv.org/abs/0000.00001"
+        );
+    }
+
+    #[test]
+    fn test_refresh_searches_changed_articles() {
+        let feed = fixture_feed_of(3);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        "Title 1".chars().for_each(|c| app.search_push_char(c));
+        assert_eq!(app.search_state.filtered_count(), 1);
+
+        // Same number of articles with the same first and last ones, another in between.
+        let mut refreshed = fixture_feed_of(3);
+        refreshed.articles[1].title = "Fluxonium".to_string();
+        app.finish_refresh(Ok(refreshed));
+        assert_eq!(app.search_state.filtered_count(), 0);
+    }
+
+    #[test]
+    fn test_query_editor_swaps_feed_and_goes_back() {
+        let feed = fixture_feed_of(3);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let query = ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+        app.query = Some(query.clone());
+        app.article_feed.state.select(Some(2));
+
+        app.open_query_editor();
+        assert_eq!(app.context, Context::QueryEditor);
+        "x".chars().for_each(|c| app.query_editor.push_char(c));
+        app.query_editor_submit();
+        // The typo is reported in the popup, which stays open.
+        assert_eq!(app.context, Context::QueryEditor);
+        assert!(app.query_editor.message.is_some());
+        app.query_editor.pop_char();
+        ", cs.LG"
+            .chars()
+            .for_each(|c| app.query_editor.push_char(c));
+        app.query_editor_submit();
+        assert_eq!(app.context, Context::ArticleList);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Fetching 200 latest quant-ph, cs.LG papers…")
+        );
+        let url = app.take_refresh_query().unwrap();
+        assert!(url.contains("cat:quant-ph+OR+cat:cs.LG"), "{url}");
+
+        // The feed is swapped when the articles arrive, the previous one is kept.
+        app.finish_refresh(Ok(fixture_feed_of(5)));
+        assert_eq!(app.query_result.articles.len(), 5);
+        assert_eq!(app.search_state.filtered_count(), 5);
+        assert_eq!(app.feed_stack.len(), 1);
+        assert!(app.query.as_ref().unwrap().terms().contains("cs.LG"));
+
+        app.go_back();
+        assert_eq!(app.query, Some(query.clone()));
+        assert_eq!(app.query_result.articles.len(), 3);
+        assert_eq!(app.search_state.filtered_count(), 3);
+        assert_eq!(app.get_actual_article_index(), Some(2));
+        assert!(app.feed_stack.is_empty());
+        app.go_back();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No previous query to go back to")
+        );
+
+        // A query failing keeps the previous query with its feed.
+        app.search_selected_author();
+        assert!(app.take_refresh_query().unwrap().contains("au:"));
+        app.finish_refresh(Err(ArxivQueryError::Request("network is down".to_string())));
+        assert_eq!(app.query, Some(query));
+        assert!(app.feed_stack.is_empty());
+    }
+
+    #[test]
+    fn test_config_popup_lists_warnings() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.config_warnings = vec![ConfigWarning::UnknownKey("hilight".to_string())];
+
+        app.set_context(Context::Config);
+        let lines = render_lines(&mut app);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Warning         unknown config key `hilight` is ignored")));
+    }
+
+    #[test]
+    fn test_refresh_error_keeps_feed() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.set_context(Context::Config);
+        app.finish_refresh(Err(ArxivQueryError::Request("network is down".to_string())));
+
+        assert_eq!(app.query_result.articles.len(), 5);
+        assert_eq!(app.context, Context::Error);
+        assert_eq!(
+            app.error_message.as_deref(),
+            Some("Refresh failed: network is down")
+        );
+        let lines = render_lines(&mut app);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Refresh failed: network is down")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("press any key to dismiss")));
+
+        app.dismiss_error();
+        assert_eq!(app.context, Context::Config);
+        assert_eq!(app.error_message, None);
+    }
+
+    #[test]
+    fn test_render_no_color_without_rgb() {
+        use crate::config::{ColorsConfig, UiConfig};
+        use ratatui::{backend::TestBackend, style::Color, Terminal};
+
+        let feed = fixture_feed();
+        let config = Config {
+            ui: UiConfig {
+                colors: ColorsConfig {
+                    accent: Some("#ff9e64".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let theme = Theme::from_config_with_no_color(&config.ui, true).unwrap();
+        let mut app = App::new(&feed, &config, theme);
+        app.set_context(Context::Search);
+        app.search_push_char('T');
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+
+        let is_rgb = |color: Color| matches!(color, Color::Rgb(..));
+        assert!(terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .all(|cell| !is_rgb(cell.fg) && !is_rgb(cell.bg)));
+    }
+
+    #[test]
+    fn test_config_edit_saves_file() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let dir = std::env::temp_dir().join(format!("arxivlens-edit-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        app.config_path = Some(path.clone());
+
+        app.set_context(Context::Config);
+        app.config_editor.select_next();
+        app.config_start_edit();
+        "Author 2, Author 4"
+            .chars()
+            .for_each(|c| app.config_editor.push_char(c));
+        app.config_commit_edit();
+
+        let authors = Some(vec!["Author 2".to_string(), "Author 4".to_string()]);
+        assert_eq!(app.config.pinned.authors, authors);
+        assert_eq!(app.pinned_height(0), 4);
+        assert_eq!(app.context, Context::Config);
+        let (saved, _) = Config::load_from_file(&path, None).unwrap();
+        assert_eq!(saved.pinned.authors, authors);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_edit_category_validated_and_saved() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let dir = std::env::temp_dir().join(format!("arxivlens-category-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        app.config_path = Some(path.clone());
+
+        app.set_context(Context::Config);
+        app.config_start_edit();
+        app.config_editor.input = Some("hep-thh".to_string());
+        app.config_commit_edit();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Unknown arXiv category `hep-thh`, did you mean `hep-th`?")
+        );
+        assert!(app.config_editor.is_editing());
+        assert_eq!(app.config.query.categories, ["quant-ph"]);
+        assert!(!path.exists());
+
+        app.config_editor.pop_char();
+        app.config_commit_edit();
+        assert!(!app.config_editor.is_editing());
+        assert_eq!(app.config.query.categories, ["hep-th"]);
+        let (saved, _) = Config::load_from_file(&path, None).unwrap();
+        assert_eq!(saved.query.categories, ["hep-th"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_toggle_pin_first_author() {
+        let mut feed = fixture_feed();
+        feed.articles[2] = ArxivEntry::new(
+            "Title 2".to_string(),
+            vec!["Marie Curie".to_string(), "Pierre Curie".to_string()],
+            "Summary 2".to_string(),
+            "http://arxiv.org/abs/0000.00002".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+        );
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let dir = std::env::temp_dir().join(format!("arxivlens-pin-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        app.config_path = Some(path.clone());
+        app.article_feed.state.select(Some(2));
+        assert!(app.pinned_feeds[0].is_empty());
+
+        app.toggle_pin_first_author();
+        let authors = Some(vec!["Marie Curie".to_string()]);
+        assert_eq!(app.config.pinned.authors, authors);
+        assert_eq!(app.pinned_feeds[0].len(), 1);
+        let (saved, _) = Config::load_from_file(&path, None).unwrap();
+        assert_eq!(saved.pinned.authors, authors);
+
+        // From the pinned feed, the author is unpinned and their feed empties.
+        app.focus_next_feed();
+        assert_eq!(app.context, Context::Pinned);
+        app.toggle_pin_first_author();
+        assert_eq!(app.config.pinned.authors, None);
+        assert!(app.pinned_feeds[0].is_empty());
+        let (saved, _) = Config::load_from_file(&path, None).unwrap();
+        assert!(saved.pinned.authors.unwrap_or_default().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_watch_author_creates_config_file() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let dir = std::env::temp_dir().join(format!("arxivlens-watch-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        app.config_path = Some(path.clone());
+        assert_eq!(app.pinned_height(0), 0);
+
+        app.start_watch_author();
+        assert_eq!(app.context, Context::AuthorPicker);
+        app.watch_chosen_author();
+
+        let authors = Some(vec!["Author 0".to_string()]);
+        assert_eq!(app.config.pinned.authors, authors);
+        assert_eq!(app.pinned_height(0), 3);
+        assert_eq!(app.context, Context::ArticleList);
+        let (saved, _) = Config::load_from_file(&path, None).unwrap();
+        assert_eq!(saved.pinned.authors, authors);
+
+        app.start_watch_author();
+        app.watch_chosen_author();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Author 0 is already in Pinned authors")
+        );
+        assert_eq!(app.config.pinned.authors, authors);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// App over 5 articles, the ones of "Author 1" and "Author 3" pinned.
+    fn pinned_app<'a>(feed: &'a ArxivQueryResult, config: &'a mut Config) -> App<'a> {
+        config.pinned.authors = Some(vec!["Author 1".to_string(), "Author 3".to_string()]);
+        App::new(feed, config, Theme::default())
+    }
+
+    #[test]
+    fn test_pinned_focus_navigation() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        let mut app = pinned_app(&feed, &mut config);
+        app.select_next();
+        assert_eq!(app.article_feed.state.selected(), Some(1));
+
+        app.focus_next_feed();
+        assert_eq!(app.context, Context::Pinned);
+        assert_eq!(app.pinned_feeds[0].state.selected(), Some(0));
+        app.select_next();
+        assert_eq!(app.pinned_feeds[0].state.selected(), Some(1));
+        app.select_previous();
+        app.select_next();
+        assert_eq!(app.pinned_feeds[0].state.selected(), Some(1));
+        // The main feed keeps its selection meanwhile.
+        assert_eq!(app.article_feed.state.selected(), Some(1));
+
+        // Both pinned articles have the same date, they keep the order of the feed.
+        assert_eq!(app.selected_article_index(), Some(3));
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("Summary 3")));
+
+        app.focus_next_feed();
+        assert_eq!(app.context, Context::ArticleList);
+        assert_eq!(app.selected_article_index(), Some(1));
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("Summary 1")));
+
+        // Back to the pinned feed, where it was left.
+        app.focus_next_feed();
+        assert_eq!(app.pinned_feeds[0].state.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_pinned_expand_collapse_all() {
+        let feed = fixture_feed_of(10);
+        let mut config = Config::default();
+        config.pinned.authors = Some((0..10).map(|i| format!("Author {i}")).collect());
+        config.pinned.keywords = Some(vec!["Title".to_string()]);
+        config.ui.pinned_max_height = Some(10);
+        let dir = std::env::temp_dir().join(format!("arxivlens-pinned-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.config_path = Some(path.clone());
+
+        // From the main feed, without focusing a pinned feed.
+        app.toggle_all_pinned_expanded();
+        assert_eq!(app.pinned_height(0), PINNED_COLLAPSED_HEIGHT);
+        assert_eq!(app.pinned_height(1), PINNED_COLLAPSED_HEIGHT);
+        let saved = Config::load_from_file(&path, None).unwrap().0;
+        assert_eq!(saved.ui.pinned_expanded, Some(false));
+        // The next run starts with the saved choice.
+        let mut next_config = config.clone();
+        next_config.ui.pinned_expanded = saved.ui.pinned_expanded;
+        let next_run = App::new(&feed, &next_config, Theme::default());
+        assert_eq!(next_run.pinned_height(0), PINNED_COLLAPSED_HEIGHT);
+
+        // A feed expanded on its own is collapsed with the others.
+        app.focus_next_feed();
+        app.toggle_pinned_expanded();
+        assert_eq!(app.pinned_height(0), 10);
+        app.toggle_all_pinned_expanded();
+        assert_eq!(app.pinned_height(0), PINNED_COLLAPSED_HEIGHT);
+        app.toggle_all_pinned_expanded();
+        assert_eq!(app.pinned_height(0), 10);
+        assert_eq!(app.pinned_height(1), 10);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pinned_expand_collapse() {
+        let feed = fixture_feed_of(10);
+        let mut config = Config::default();
+        config.pinned.authors = Some((0..10).map(|i| format!("Author {i}")).collect());
+        config.ui.pinned_max_height = Some(8);
+        let mut app = App::new(&feed, &config, Theme::default());
+        assert_eq!(app.pinned_height(0), 8);
+
+        app.focus_next_feed();
+        app.toggle_pinned_expanded();
+        assert_eq!(app.pinned_height(0), PINNED_COLLAPSED_HEIGHT);
+        // The collapsed feed scrolls to the selected article.
+        for _ in 0..5 {
+            app.select_next();
+        }
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("Title 5")));
+        assert_eq!(app.pinned_feeds[0].state.offset(), 4);
+
+        // The pinned feed stays collapsed when the focus moves.
+        app.focus_next_feed();
+        assert_eq!(app.pinned_height(0), PINNED_COLLAPSED_HEIGHT);
+        app.toggle_pinned_expanded();
+        assert_eq!(app.pinned_height(0), PINNED_COLLAPSED_HEIGHT);
+        app.focus_next_feed();
+        app.toggle_pinned_expanded();
+        assert_eq!(app.pinned_height(0), 8);
+    }
+
+    #[test]
+    fn test_pinned_feed_follows_search() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        let mut app = pinned_app(&feed, &mut config);
+        app.focus_next_feed();
+        app.select_next();
+        assert_eq!(app.pinned_feeds[0].selected_index(), Some(3));
+
+        app.set_context(Context::Search);
+        "Title 1".chars().for_each(|c| app.search_push_char(c));
+
+        assert_eq!(app.pinned_feeds[0].len(), 1);
+        // The selected article was filtered out, the selection is clamped.
+        assert_eq!(app.pinned_feeds[0].state.selected(), Some(0));
+        assert_eq!(app.pinned_feeds[0].selected_index(), Some(1));
+        let lines = render_lines(&mut app);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Pinned Authors (1 of 2)")));
+
+        app.search_push_char('2');
+        assert!(app.pinned_feeds[0].is_empty());
+        assert_eq!(app.pinned_height(0), 3);
+        let lines = render_lines(&mut app);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Pinned Authors (0 of 2)")));
+
+        // Leaving the search shows all the pinned articles again.
+        app.set_context(Context::ArticleList);
+        assert_eq!(app.pinned_feeds[0].len(), 2);
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("Pinned Authors─")));
+    }
+
+    #[test]
+    fn test_pinned_feeds_layout() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        assert_eq!((app.pinned_height(0), app.pinned_height(1)), (0, 0));
+        let lines = render_lines(&mut app);
+        assert!(!lines.iter().any(|line| line.contains("Pinned")));
+
+        config.pinned.keywords = Some(vec!["summary 2".to_string()]);
+        let mut app = App::new(&feed, &config, Theme::default());
+        assert_eq!((app.pinned_height(0), app.pinned_height(1)), (0, 3));
+        let lines = render_lines(&mut app);
+        assert!(!lines.iter().any(|line| line.contains("Pinned Authors")));
+        assert!(lines[1].contains("Pinned Keywords"));
+        assert!(lines[2].contains("Title 2"));
+
+        let mut app = pinned_app(&feed, &mut config);
+        assert_eq!((app.pinned_height(0), app.pinned_height(1)), (4, 3));
+        let lines = render_lines(&mut app);
+        assert!(lines[1].contains("Pinned Authors"));
+        assert!(lines[5].contains("Pinned Keywords"));
+        assert!(lines[6].contains("Title 2"));
+    }
+
+    #[test]
+    fn test_pinned_feeds_focus_cycle() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        config.pinned.keywords = Some(vec!["Title 4".to_string()]);
+        let mut app = pinned_app(&feed, &mut config);
+
+        app.focus_next_feed();
+        assert_eq!((app.context, app.pinned_focus), (Context::Pinned, 0));
+        app.focus_next_feed();
+        assert_eq!((app.context, app.pinned_focus), (Context::Pinned, 1));
+        assert_eq!(app.selected_article_index(), Some(4));
+        app.toggle_pinned_expanded();
+        assert!(app.pinned_feeds[0].expanded);
+        assert!(!app.pinned_feeds[1].expanded);
+        app.focus_next_feed();
+        assert_eq!(app.context, Context::ArticleList);
+
+        // The empty feeds are skipped.
+        config.pinned.authors = None;
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.focus_next_feed();
+        assert_eq!((app.context, app.pinned_focus), (Context::Pinned, 1));
+    }
+
+    #[test]
+    fn test_pinned_focus_needs_articles() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.focus_next_feed();
+        assert_eq!(app.context, Context::ArticleList);
+    }
+
+    #[test]
+    fn test_pinned_selection_kept_on_refresh() {
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        let mut app = pinned_app(&feed, &mut config);
+        app.focus_next_feed();
+        app.select_next();
+
+        let mut refreshed = fixture_feed_of(5);
+        refreshed.articles.remove(1);
+        app.finish_refresh(Ok(refreshed));
+
+        assert_eq!(app.context, Context::Pinned);
+        assert_eq!(app.pinned_feeds[0].state.selected(), Some(0));
+        assert_eq!(app.selected_article_index(), Some(2));
+    }
+
+    #[test]
+    fn test_category_config_applied() {
+        use crate::config::CategoryConfig;
+
+        let feed = fixture_feed();
+        let mut config = Config::default();
+        config.highlight.keywords = Some(vec!["qubit".to_string()]);
+        let mut category = CategoryConfig::default();
+        category.highlight.keywords = Some(vec!["photon".to_string()]);
+        category.pinned.authors = Some(vec!["Author 1".to_string()]);
+        config.category.insert("quant-ph".to_string(), category);
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        assert_eq!(app.pinned_height(0), 3);
+        assert_eq!(
+            app.effective_config.highlight.keywords,
+            Some(vec!["qubit".to_string(), "photon".to_string()])
+        );
+
+        // The popup shows the merged values of the current category.
+        app.set_context(Context::Config);
+        let lines = render_lines(&mut app);
+        assert!(lines.iter().any(|line| line.contains("qubit, photon")));
+    }
+
+    #[test]
+    fn test_watch_keyword() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.start_watch_keyword();
+        app.keyword_input.push_str(" qubit ");
+        app.watch_typed_keyword();
+
+        assert_eq!(
+            app.config.highlight.keywords,
+            Some(vec!["qubit".to_string()])
+        );
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Changed Keywords for this session")
+        );
+        assert_eq!(app.context, Context::ArticleList);
+    }
+
+    #[test]
+    fn test_config_edit_category_offers_requery() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+
+        app.set_context(Context::Config);
+        app.config_start_edit();
+        app.config_editor.input = Some("hep-th".to_string());
+        app.config_commit_edit();
+
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Changed Category for this session")
+        );
+        assert_eq!(app.context, Context::Confirm);
+        assert_eq!(app.confirm_message(), "Run the query again in hep-th?");
+        app.confirm();
+
+        assert_eq!(
+            app.query.as_ref().unwrap().search_queries,
+            vec![SearchQuery::Category("hep-th".to_string())]
+        );
+        assert!(app.take_refresh_query().unwrap().contains("cat:hep-th"));
+
+        app.set_context(Context::Config);
+        app.config_start_edit();
+        app.config_editor.input = Some(" , ".to_string());
+        app.config_commit_edit();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("The category can not be empty")
+        );
+        assert_eq!(app.config.query.categories, ["hep-th"]);
+    }
+
+    #[test]
+    fn test_search_selected_author() {
+        let mut feed = fixture_feed();
+        feed.articles[2].authors = vec!["Marie Curie".to_string(), "Pierre Curie".to_string()];
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 200,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+        app.select_next();
+        app.select_next();
+
+        app.search_selected_author();
+
+        let url = app.take_refresh_query().unwrap();
+        assert!(url.contains("search_query=au:Curie_M&start=0"));
+        assert!(!url.contains("cat:quant-ph"));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Fetching the articles of Marie Curie…")
+        );
+    }
+
+    #[test]
+    fn test_search_submit_arxiv_id() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 200,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+
+        // Any other search is applied to the feed.
+        app.set_context(Context::Search);
+        "Title".chars().for_each(|c| app.search_push_char(c));
+        app.search_submit();
+        assert_eq!(app.take_refresh_query(), None);
+        assert_eq!(app.context, Context::ArticleList);
+        assert!(app.clear_search());
+
+        app.set_context(Context::Search);
+        "arXiv:2401.01234"
+            .chars()
+            .for_each(|c| app.search_push_char(c));
+        app.search_submit();
+        let url = app.take_refresh_query().unwrap();
+        assert!(url.contains("search_query=&start=0"));
+        assert!(url.ends_with("&id_list=2401.01234"));
+        assert!(!app.search_state.is_active());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Fetching arXiv:2401.01234…")
+        );
+    }
+
+    #[test]
+    fn test_search_selected_category() {
+        let mut feed = fixture_feed();
+        feed.articles[1] = feed.articles[1]
+            .clone()
+            .with_categories(Some("cs.LG".to_string()), vec!["cs.LG".to_string()]);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.query = Some(ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        });
+
+        // No primary category for the first article: nothing to run.
+        app.search_selected_category();
+        assert_eq!(app.take_refresh_query(), None);
+
+        app.set_context(Context::Search);
+        "Title 1".chars().for_each(|c| app.search_push_char(c));
+        app.search_selected_category();
+
+        assert_eq!(
+            app.query.as_ref().unwrap().search_queries,
+            vec![SearchQuery::Category("cs.LG".to_string())]
+        );
+        assert!(app.take_refresh_query().unwrap().contains("cat:cs.LG"));
+        assert!(!app.search_state.is_active());
+    }
+
+    #[test]
+    fn test_export_writes_file() {
+        let feed = fixture_feed();
+        let dir = std::env::temp_dir().join(format!("arxivlens-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = Config {
+            export: ExportConfig {
+                dir: Some(dir.to_string_lossy().to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.set_context(Context::Export);
+        app.export(ExportFormat::Csv);
+
+        assert_eq!(app.context, Context::ArticleList);
+        let message = app.status_message.clone().unwrap();
+        assert!(message.starts_with("Exported 5 articles"));
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_command_prompt() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.start_command();
+        for c in "sortt title".chars() {
+            app.command_push_char(c);
+        }
+        app.command_submit();
+        // The error is shown in the prompt, which stays open to fix the command.
+        assert_eq!(app.context, Context::Command);
+        assert_eq!(
+            app.footer(120).text(),
+            "   :sortt title   unknown command `sortt`, did you mean `sort`?"
+        );
+
+        app.start_command();
+        for c in "so".chars() {
+            app.command_push_char(c);
+        }
+        app.command_complete();
+        assert_eq!(app.command_input, "sort ");
+        app.command_push_char('t');
+        app.command_complete();
+        app.command_submit();
+        assert_eq!(app.context, Context::ArticleList);
+        assert_eq!(app.search_state.sort_mode, SortMode::Title);
+        assert_eq!(app.status_message.as_deref(), Some("Sorted by title"));
+
+        let path =
+            std::env::temp_dir().join(format!("arxivlens-command-{}.bib", std::process::id()));
+        app.start_command();
+        for c in format!("export bibtex \"{}\"", path.display()).chars() {
+            app.command_push_char(c);
+        }
+        app.command_submit();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("@misc{"));
+        std::fs::remove_file(&path).unwrap();
+
+        // Erasing the whole command closes the prompt.
+        app.start_command();
+        app.command_push_char('q');
+        app.command_pop_char();
+        app.command_pop_char();
+        assert_eq!(app.context, Context::ArticleList);
+    }
+
+    #[test]
+    fn test_export_only_the_searched_articles() {
+        let feed = fixture_feed();
+        let path = std::env::temp_dir().join(format!(
+            "arxivlens-export-search-{}.csv",
+            std::process::id()
+        ));
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        app.set_context(Context::Search);
+        "Title 3".chars().for_each(|c| app.search_push_char(c));
+        app.search_submit();
+
+        app.export_to(ExportFormat::Csv, path.clone());
+
+        let message = app.status_message.clone().unwrap();
+        assert!(message.starts_with("Exported 1 articles"));
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(csv.contains("Title 3"));
+        assert!(!csv.contains("Title 2"));
+        assert!(app.search_state.is_active());
+    }
+
+    #[test]
+    fn test_export_reports_io_error() {
+        let feed = fixture_feed();
+        let config = Config {
+            export: ExportConfig {
+                dir: Some("/nonexistent/arxivlens/dir".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.export(ExportFormat::Json);
+
+        let message = app.status_message.clone().unwrap();
+        assert!(message.starts_with("Export failed"));
+    }
+
+    #[test]
+    fn test_search_maps_selection_to_article() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        app.set_context(Context::Search);
+        for c in "Title 3".chars() {
+            app.search_push_char(c);
+        }
+        assert_eq!(app.search_state.filtered_indices[0], 3);
+        assert_eq!(app.get_actual_article_index(), Some(3));
+
+        // The cursor is drawn where the next character goes.
+        app.search_cursor_left();
+        assert!(render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("/Title ▏3")));
+        // A query wider than the bar scrolls to keep the cursor in view.
+        app.search_cursor_end();
+        app.search_insert_str(" of a query too long for the bar");
+        assert!(render_lines_sized(&mut app, 30, 20)
+            .iter()
+            .any(|line| line.contains("│…r the bar▏│")));
+
+        app.set_context(Context::ArticleList);
+        assert_eq!(app.search_state.filtered_count(), 5);
+    }
+
+    #[test]
+    fn test_open_all_visible_without_confirmation() {
+        let feed = fixture_feed_of(3);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let urls = Rc::new(RefCell::new(Vec::new()));
+        app.opener = Box::new(RecordingOpener { urls: urls.clone() });
+
+        app.open_all_visible();
+
+        assert_eq!(app.context, Context::ArticleList);
+        assert_eq!(urls.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_open_all_visible_confirmed() {
+        let feed = fixture_feed_of(8);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let urls = Rc::new(RefCell::new(Vec::new()));
+        app.opener = Box::new(RecordingOpener { urls: urls.clone() });
+
+        app.open_all_visible();
+        assert_eq!(app.context, Context::Confirm);
+        assert!(urls.borrow().is_empty());
+
+        app.open_all_visible_confirmed();
+        assert_eq!(app.context, Context::ArticleList);
+        assert_eq!(urls.borrow().len(), 8);
+        assert_eq!(urls.borrow()[0], "http://arxiv.org/abs/0000.00000");
+    }
+
+    #[test]
+    fn test_open_all_visible_declined() {
+        let feed = fixture_feed_of(8);
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        let urls = Rc::new(RefCell::new(Vec::new()));
+        app.opener = Box::new(RecordingOpener { urls: urls.clone() });
+
+        app.open_all_visible();
+        app.set_context(Context::ArticleList);
+
+        assert!(urls.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_auto_select_first_after_load() {
+        let no_articles = ArxivQueryResult::default();
+        let config = Config::default();
+        let mut app = App::new(&no_articles, &config, Theme::default());
+        app.start_loading();
+        app.finish_refresh(Ok(fixture_feed()));
+
+        assert_eq!(app.article_feed.state.selected(), Some(0));
+        assert!(render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("Summary 0")));
+    }
+
+    #[test]
+    fn test_no_auto_select_shows_placeholder() {
+        let no_articles = ArxivQueryResult::default();
+        let mut config = Config::default();
+        config.ui.auto_select_first = Some(false);
+        let mut app = App::new(&no_articles, &config, Theme::default());
+        app.start_loading();
+        app.finish_refresh(Ok(fixture_feed()));
+
+        assert_eq!(app.article_feed.state.selected(), None);
+        assert!(render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("No article selected")));
+
+        app.select_next();
+        assert_eq!(app.article_feed.state.selected(), Some(0));
+        assert!(render_lines(&mut app)
+            .iter()
+            .any(|line| line.contains("Summary 0")));
+    }
+
+    #[test]
+    fn test_restore_session_missing_article() {
+        let feed = fixture_feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+
+        let session = Session::new("query", Some("http://arxiv.org/abs/9999.99999".into()), 2);
+        app.restore_session(&session);
 
-        let article_view = ArticleDetails::new(current_entry, self.highlight_config, &self.theme);
-        article_view.render(frame, layout[1], &self.theme)
+        assert_eq!(app.article_feed.state.selected(), Some(0));
+        assert_eq!(app.article_feed.state.offset(), 0);
     }
 }
@@ -0,0 +1,420 @@
+//! Commands typed in the prompt opened with `:`, e.g. `:category cs.LG` or
+//! `:export bibtex ~/out.bib`: their parsing and the completion of their names and
+//! arguments.
+
+use clap::ValueEnum;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::arxiv::categories::{suggest_category, ARXIV_CATEGORIES};
+use crate::editor::shared_prefix;
+use crate::export::ExportFormat;
+use crate::search::{CrossListMode, SearchEngine, SortMode};
+use crate::tools::expand_tilde;
+
+/// Names of the commands, as typed after `:`.
+const COMMANDS: &[&str] = &[
+    "author", "category", "export", "help", "quit", "refresh", "set", "sort", "stats",
+];
+/// Values of `:sort`.
+const SORT_MODES: &[(&str, SortMode)] = &[
+    ("feed", SortMode::Feed),
+    ("published", SortMode::Published),
+    ("updated", SortMode::Updated),
+    ("title", SortMode::Title),
+];
+/// Settings changed with `:set`.
+const SETTINGS: &[&str] = &["cross_lists", "new_only"];
+const CROSS_LIST_MODES: &[(&str, CrossListMode)] = &[
+    ("show", CrossListMode::Show),
+    ("dim", CrossListMode::Dim),
+    ("hide", CrossListMode::Hide),
+];
+const SWITCHES: &[(&str, bool)] = &[("on", true), ("off", false)];
+
+/// Command parsed from the prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Run the query again in these categories, `:category cs.LG math.OC`.
+    Category(Vec<String>),
+    /// Run the query again for the articles of an author, `:author "J Doe"`.
+    Author(String),
+    /// Sort the feed, `:sort updated`.
+    Sort(SortMode),
+    /// Export the feed, to the given file or else to the export directory,
+    /// `:export bibtex ~/out.bib`.
+    Export(ExportFormat, Option<PathBuf>),
+    /// Change a setting for the session, `:set cross_lists dim`.
+    Set(Setting),
+    /// Run the query again, `:refresh`.
+    Refresh,
+    /// Show the statistics of the feed, `:stats`.
+    Stats,
+    /// Show the keys, `:help`.
+    Help,
+    /// Quit the application, `:quit`.
+    Quit,
+}
+
+/// Setting changed with `:set`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    /// How the cross-lists are shown, `:set cross_lists hide`.
+    CrossLists(CrossListMode),
+    /// Whether only the new articles are shown, `:set new_only on`.
+    NewOnly(bool),
+}
+
+/// Why a command could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// Nothing was typed.
+    Empty,
+    /// A `"` is not closed.
+    UnterminatedQuote,
+    /// The command does not exist, with the closest one if any.
+    UnknownCommand {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    /// The arguments are missing or too many, with the expected ones.
+    Usage {
+        command: &'static str,
+        usage: &'static str,
+    },
+    /// An argument is not one of its allowed values, with a suggestion if any.
+    InvalidArgument {
+        what: &'static str,
+        value: String,
+        suggestion: Option<String>,
+    },
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Empty => write!(f, "no command"),
+            CommandError::UnterminatedQuote => write!(f, "missing closing quote"),
+            CommandError::UnknownCommand { name, suggestion } => {
+                write!(f, "unknown command `{name}`")?;
+                match suggestion {
+                    Some(suggestion) => write!(f, ", did you mean `{suggestion}`?"),
+                    None => Ok(()),
+                }
+            }
+            CommandError::Usage { command, usage: "" } => {
+                write!(f, "usage: :{command}, without arguments")
+            }
+            CommandError::Usage { command, usage } => write!(f, "usage: :{command} {usage}"),
+            CommandError::InvalidArgument {
+                what,
+                value,
+                suggestion,
+            } => {
+                write!(f, "unknown {what} `{value}`")?;
+                match suggestion {
+                    Some(suggestion) => write!(f, ", did you mean `{suggestion}`?"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Split the input in words, the words between double quotes staying together.
+fn split_words(input: &str) -> Result<Vec<String>, CommandError> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return Err(CommandError::UnterminatedQuote);
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Value named by the argument, or an error suggesting the closest name.
+fn lookup<T: Copy>(
+    what: &'static str,
+    value: &str,
+    values: &[(&'static str, T)],
+) -> Result<T, CommandError> {
+    if let Some((_, value)) = values.iter().find(|(name, _)| *name == value) {
+        return Ok(*value);
+    }
+    let names: Vec<&str> = values.iter().map(|(name, _)| *name).collect();
+    Err(CommandError::InvalidArgument {
+        what,
+        value: value.to_string(),
+        suggestion: SearchEngine::new()
+            .closest(value, &names)
+            .map(str::to_string),
+    })
+}
+
+/// Names of the values of an argument.
+fn names<T>(values: &[(&str, T)]) -> Vec<String> {
+    values.iter().map(|(name, _)| name.to_string()).collect()
+}
+
+/// Names of the export formats, e.g. `bibtex`.
+fn export_formats() -> Vec<String> {
+    ExportFormat::value_variants()
+        .iter()
+        .filter_map(ValueEnum::to_possible_value)
+        .map(|value| value.get_name().to_string())
+        .collect()
+}
+
+/// Parse the text typed after `:`.
+pub fn parse(input: &str) -> Result<Command, CommandError> {
+    let words = split_words(input)?;
+    let Some((name, args)) = words.split_first() else {
+        return Err(CommandError::Empty);
+    };
+    let Some(&command) = COMMANDS.iter().find(|command| *command == name) else {
+        return Err(CommandError::UnknownCommand {
+            name: name.clone(),
+            suggestion: SearchEngine::new().closest(name, COMMANDS),
+        });
+    };
+    let usage = |usage| CommandError::Usage { command, usage };
+
+    match (command, args) {
+        ("category", []) => Err(usage("<category>...")),
+        ("category", categories) => {
+            for category in categories {
+                if !ARXIV_CATEGORIES.iter().any(|(code, _)| code == category) {
+                    return Err(CommandError::InvalidArgument {
+                        what: "category",
+                        value: category.clone(),
+                        suggestion: suggest_category(category).map(str::to_string),
+                    });
+                }
+            }
+            Ok(Command::Category(categories.to_vec()))
+        }
+        ("author", names) if !names.concat().trim().is_empty() => {
+            Ok(Command::Author(names.join(" ")))
+        }
+        ("author", _) => Err(usage("\"<name>\"")),
+        ("sort", [mode]) => lookup("sort order", mode, SORT_MODES).map(Command::Sort),
+        ("sort", _) => Err(usage("feed|published|updated|title")),
+        ("export", [format, path @ ..]) if path.len() <= 1 => {
+            let format = ExportFormat::from_str(format, true).map_err(|_| {
+                let formats = export_formats();
+                let formats: Vec<&str> = formats.iter().map(String::as_str).collect();
+                CommandError::InvalidArgument {
+                    what: "export format",
+                    value: format.clone(),
+                    suggestion: SearchEngine::new()
+                        .closest(format, &formats)
+                        .map(str::to_string),
+                }
+            })?;
+            let path = path.first().map(|path| expand_tilde(path));
+            Ok(Command::Export(format, path))
+        }
+        ("export", _) => Err(usage("<format> [path]")),
+        ("set", [setting, value]) => match setting.as_str() {
+            "cross_lists" => lookup("cross-list mode", value, CROSS_LIST_MODES)
+                .map(|mode| Command::Set(Setting::CrossLists(mode))),
+            "new_only" => lookup("value", value, SWITCHES)
+                .map(|new_only| Command::Set(Setting::NewOnly(new_only))),
+            _ => Err(CommandError::InvalidArgument {
+                what: "setting",
+                value: setting.clone(),
+                suggestion: SearchEngine::new()
+                    .closest(setting, SETTINGS)
+                    .map(str::to_string),
+            }),
+        },
+        ("set", _) => Err(usage("cross_lists show|dim|hide, or new_only on|off")),
+        ("refresh", []) => Ok(Command::Refresh),
+        ("stats", []) => Ok(Command::Stats),
+        ("help", []) => Ok(Command::Help),
+        ("quit", []) => Ok(Command::Quit),
+        (command, _) => Err(CommandError::Usage { command, usage: "" }),
+    }
+}
+
+/// Candidates completing the last word of the input: the names of the commands for the
+/// first word, then the values of their arguments, e.g. the arXiv categories.
+pub fn completions(input: &str) -> Vec<String> {
+    let mut words: Vec<&str> = input.split_whitespace().collect();
+    let partial = if input.ends_with(char::is_whitespace) {
+        ""
+    } else {
+        words.pop().unwrap_or_default()
+    };
+    let candidates: Vec<String> = match words.as_slice() {
+        [] => COMMANDS.iter().map(|name| name.to_string()).collect(),
+        ["category", ..] => ARXIV_CATEGORIES
+            .iter()
+            .map(|(code, _)| code.to_string())
+            .collect(),
+        ["sort"] => names(SORT_MODES),
+        ["export"] => export_formats(),
+        ["set"] => SETTINGS.iter().map(|name| name.to_string()).collect(),
+        ["set", "cross_lists"] => names(CROSS_LIST_MODES),
+        ["set", "new_only"] => names(SWITCHES),
+        _ => Vec::new(),
+    };
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(partial))
+        .collect()
+}
+
+/// The input with its last word completed, by the only candidate followed by a space, or
+/// else by the start shared by the candidates, which are returned to be listed.
+pub fn complete(input: &str) -> (String, Vec<String>) {
+    let candidates = completions(input);
+    let partial = input.rsplit(char::is_whitespace).next().unwrap_or_default();
+    let start = &input[..input.len() - partial.len()];
+    match candidates.as_slice() {
+        [] => (input.to_string(), candidates),
+        [only] => (format!("{start}{only} "), Vec::new()),
+        _ => (format!("{start}{}", shared_prefix(&candidates)), candidates),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            parse("category cs.LG  math.OC"),
+            Ok(Command::Category(vec![
+                "cs.LG".to_string(),
+                "math.OC".to_string()
+            ]))
+        );
+        assert_eq!(
+            parse("author \"J Doe\""),
+            Ok(Command::Author("J Doe".to_string()))
+        );
+        assert_eq!(
+            parse("author J Doe"),
+            Ok(Command::Author("J Doe".to_string()))
+        );
+        assert_eq!(
+            parse(" sort updated "),
+            Ok(Command::Sort(SortMode::Updated))
+        );
+        assert_eq!(
+            parse("export bibtex \"/tmp/my papers.bib\""),
+            Ok(Command::Export(
+                ExportFormat::Bibtex,
+                Some(PathBuf::from("/tmp/my papers.bib"))
+            ))
+        );
+        assert_eq!(
+            parse("export md"),
+            Err(CommandError::InvalidArgument {
+                what: "export format",
+                value: "md".to_string(),
+                suggestion: Some("markdown".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("export markdown"),
+            Ok(Command::Export(ExportFormat::Markdown, None))
+        );
+        assert_eq!(
+            parse("set cross_lists hide"),
+            Ok(Command::Set(Setting::CrossLists(CrossListMode::Hide)))
+        );
+        assert_eq!(
+            parse("set new_only off"),
+            Ok(Command::Set(Setting::NewOnly(false)))
+        );
+        assert_eq!(parse("quit"), Ok(Command::Quit));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("  "), Err(CommandError::Empty));
+        assert_eq!(
+            parse("author \"J Doe"),
+            Err(CommandError::UnterminatedQuote)
+        );
+        assert_eq!(
+            parse("sortt title").unwrap_err().to_string(),
+            "unknown command `sortt`, did you mean `sort`?"
+        );
+        assert_eq!(
+            parse("category quant-phh").unwrap_err().to_string(),
+            "unknown category `quant-phh`, did you mean `quant-ph`?"
+        );
+        assert_eq!(
+            parse("sort").unwrap_err().to_string(),
+            "usage: :sort feed|published|updated|title"
+        );
+        assert_eq!(
+            parse("author \"\"").unwrap_err().to_string(),
+            "usage: :author \"<name>\""
+        );
+        assert_eq!(
+            parse("export bibtex a.bib b.bib").unwrap_err().to_string(),
+            "usage: :export <format> [path]"
+        );
+        assert_eq!(
+            parse("set hide_read on").unwrap_err().to_string(),
+            "unknown setting `hide_read`"
+        );
+        assert_eq!(
+            parse("set new_only maybe").unwrap_err().to_string(),
+            "unknown value `maybe`"
+        );
+    }
+
+    #[test]
+    fn test_completions() {
+        assert_eq!(completions("s"), ["set", "sort", "stats"]);
+        assert_eq!(completions(""), COMMANDS);
+        assert_eq!(completions("sort u"), ["updated"]);
+        assert_eq!(completions("category cs.LG quant"), ["quant-ph"]);
+        assert_eq!(completions("export "), export_formats());
+        assert_eq!(completions("set cross_lists "), ["show", "dim", "hide"]);
+        assert_eq!(completions("set new_only o"), ["on", "off"]);
+        assert!(completions("author J").is_empty());
+        assert!(completions("quit ").is_empty());
+    }
+
+    #[test]
+    fn test_complete() {
+        assert_eq!(complete("so"), ("sort ".to_string(), Vec::new()));
+        assert_eq!(
+            complete("category cs.L"),
+            (
+                "category cs.L".to_string(),
+                vec!["cs.LG".to_string(), "cs.LO".to_string()]
+            )
+        );
+        assert_eq!(
+            complete("set cross"),
+            ("set cross_lists ".to_string(), Vec::new())
+        );
+        assert_eq!(
+            complete("set new_only o"),
+            (
+                "set new_only o".to_string(),
+                vec!["on".to_string(), "off".to_string()]
+            )
+        );
+        assert_eq!(complete("sort x"), ("sort x".to_string(), Vec::new()));
+    }
+}
@@ -1,5 +1,8 @@
+pub mod categories;
+mod client;
 mod parsing;
 mod query;
 
+pub use client::*;
 pub use parsing::*;
 pub use query::*;
@@ -0,0 +1,267 @@
+//! Taxonomy of the arXiv categories.
+//!
+//! Static table of the category codes used in the `cat:` queries with their human readable
+//! names. See the [`arXiv category taxonomy`].
+//!
+//! [`arXiv category taxonomy`]: https://arxiv.org/category_taxonomy
+
+use crate::search::SearchEngine;
+
+/// The arXiv categories, as `(code, name)` pairs.
+pub const ARXIV_CATEGORIES: &[(&str, &str)] = &[
+    // Computer Science
+    ("cs.AI", "Artificial Intelligence"),
+    ("cs.AR", "Hardware Architecture"),
+    ("cs.CC", "Computational Complexity"),
+    ("cs.CE", "Computational Engineering, Finance, and Science"),
+    ("cs.CG", "Computational Geometry"),
+    ("cs.CL", "Computation and Language"),
+    ("cs.CR", "Cryptography and Security"),
+    ("cs.CV", "Computer Vision and Pattern Recognition"),
+    ("cs.CY", "Computers and Society"),
+    ("cs.DB", "Databases"),
+    ("cs.DC", "Distributed, Parallel, and Cluster Computing"),
+    ("cs.DL", "Digital Libraries"),
+    ("cs.DM", "Discrete Mathematics"),
+    ("cs.DS", "Data Structures and Algorithms"),
+    ("cs.ET", "Emerging Technologies"),
+    ("cs.FL", "Formal Languages and Automata Theory"),
+    ("cs.GL", "General Literature"),
+    ("cs.GR", "Graphics"),
+    ("cs.GT", "Computer Science and Game Theory"),
+    ("cs.HC", "Human-Computer Interaction"),
+    ("cs.IR", "Information Retrieval"),
+    ("cs.IT", "Information Theory"),
+    ("cs.LG", "Machine Learning"),
+    ("cs.LO", "Logic in Computer Science"),
+    ("cs.MA", "Multiagent Systems"),
+    ("cs.MM", "Multimedia"),
+    ("cs.MS", "Mathematical Software"),
+    ("cs.NA", "Numerical Analysis"),
+    ("cs.NE", "Neural and Evolutionary Computing"),
+    ("cs.NI", "Networking and Internet Architecture"),
+    ("cs.OH", "Other Computer Science"),
+    ("cs.OS", "Operating Systems"),
+    ("cs.PF", "Performance"),
+    ("cs.PL", "Programming Languages"),
+    ("cs.RO", "Robotics"),
+    ("cs.SC", "Symbolic Computation"),
+    ("cs.SD", "Sound"),
+    ("cs.SE", "Software Engineering"),
+    ("cs.SI", "Social and Information Networks"),
+    ("cs.SY", "Systems and Control"),
+    // Economics
+    ("econ.EM", "Econometrics"),
+    ("econ.GN", "General Economics"),
+    ("econ.TH", "Theoretical Economics"),
+    // Electrical Engineering and Systems Science
+    ("eess.AS", "Audio and Speech Processing"),
+    ("eess.IV", "Image and Video Processing"),
+    ("eess.SP", "Signal Processing"),
+    ("eess.SY", "Systems and Control"),
+    // Mathematics
+    ("math.AC", "Commutative Algebra"),
+    ("math.AG", "Algebraic Geometry"),
+    ("math.AP", "Analysis of PDEs"),
+    ("math.AT", "Algebraic Topology"),
+    ("math.CA", "Classical Analysis and ODEs"),
+    ("math.CO", "Combinatorics"),
+    ("math.CT", "Category Theory"),
+    ("math.CV", "Complex Variables"),
+    ("math.DG", "Differential Geometry"),
+    ("math.DS", "Dynamical Systems"),
+    ("math.FA", "Functional Analysis"),
+    ("math.GM", "General Mathematics"),
+    ("math.GN", "General Topology"),
+    ("math.GR", "Group Theory"),
+    ("math.GT", "Geometric Topology"),
+    ("math.HO", "History and Overview"),
+    ("math.IT", "Information Theory"),
+    ("math.KT", "K-Theory and Homology"),
+    ("math.LO", "Logic"),
+    ("math.MG", "Metric Geometry"),
+    ("math.MP", "Mathematical Physics"),
+    ("math.NA", "Numerical Analysis"),
+    ("math.NT", "Number Theory"),
+    ("math.OA", "Operator Algebras"),
+    ("math.OC", "Optimization and Control"),
+    ("math.PR", "Probability"),
+    ("math.QA", "Quantum Algebra"),
+    ("math.RA", "Rings and Algebras"),
+    ("math.RT", "Representation Theory"),
+    ("math.SG", "Symplectic Geometry"),
+    ("math.SP", "Spectral Theory"),
+    ("math.ST", "Statistics Theory"),
+    // Physics
+    ("astro-ph", "Astrophysics"),
+    ("astro-ph.CO", "Cosmology and Nongalactic Astrophysics"),
+    ("astro-ph.EP", "Earth and Planetary Astrophysics"),
+    ("astro-ph.GA", "Astrophysics of Galaxies"),
+    ("astro-ph.HE", "High Energy Astrophysical Phenomena"),
+    (
+        "astro-ph.IM",
+        "Instrumentation and Methods for Astrophysics",
+    ),
+    ("astro-ph.SR", "Solar and Stellar Astrophysics"),
+    ("cond-mat", "Condensed Matter"),
+    ("cond-mat.dis-nn", "Disordered Systems and Neural Networks"),
+    ("cond-mat.mes-hall", "Mesoscale and Nanoscale Physics"),
+    ("cond-mat.mtrl-sci", "Materials Science"),
+    ("cond-mat.other", "Other Condensed Matter"),
+    ("cond-mat.quant-gas", "Quantum Gases"),
+    ("cond-mat.soft", "Soft Condensed Matter"),
+    ("cond-mat.stat-mech", "Statistical Mechanics"),
+    ("cond-mat.str-el", "Strongly Correlated Electrons"),
+    ("cond-mat.supr-con", "Superconductivity"),
+    ("gr-qc", "General Relativity and Quantum Cosmology"),
+    ("hep-ex", "High Energy Physics - Experiment"),
+    ("hep-lat", "High Energy Physics - Lattice"),
+    ("hep-ph", "High Energy Physics - Phenomenology"),
+    ("hep-th", "High Energy Physics - Theory"),
+    ("math-ph", "Mathematical Physics"),
+    ("nlin", "Nonlinear Sciences"),
+    ("nlin.AO", "Adaptation and Self-Organizing Systems"),
+    ("nlin.CD", "Chaotic Dynamics"),
+    ("nlin.CG", "Cellular Automata and Lattice Gases"),
+    ("nlin.PS", "Pattern Formation and Solitons"),
+    ("nlin.SI", "Exactly Solvable and Integrable Systems"),
+    ("nucl-ex", "Nuclear Experiment"),
+    ("nucl-th", "Nuclear Theory"),
+    ("physics", "Physics"),
+    ("physics.acc-ph", "Accelerator Physics"),
+    ("physics.ao-ph", "Atmospheric and Oceanic Physics"),
+    ("physics.app-ph", "Applied Physics"),
+    ("physics.atm-clus", "Atomic and Molecular Clusters"),
+    ("physics.atom-ph", "Atomic Physics"),
+    ("physics.bio-ph", "Biological Physics"),
+    ("physics.chem-ph", "Chemical Physics"),
+    ("physics.class-ph", "Classical Physics"),
+    ("physics.comp-ph", "Computational Physics"),
+    (
+        "physics.data-an",
+        "Data Analysis, Statistics and Probability",
+    ),
+    ("physics.ed-ph", "Physics Education"),
+    ("physics.flu-dyn", "Fluid Dynamics"),
+    ("physics.gen-ph", "General Physics"),
+    ("physics.geo-ph", "Geophysics"),
+    ("physics.hist-ph", "History and Philosophy of Physics"),
+    ("physics.ins-det", "Instrumentation and Detectors"),
+    ("physics.med-ph", "Medical Physics"),
+    ("physics.optics", "Optics"),
+    ("physics.plasm-ph", "Plasma Physics"),
+    ("physics.pop-ph", "Popular Physics"),
+    ("physics.soc-ph", "Physics and Society"),
+    ("physics.space-ph", "Space Physics"),
+    ("quant-ph", "Quantum Physics"),
+    // Quantitative Biology
+    ("q-bio.BM", "Biomolecules"),
+    ("q-bio.CB", "Cell Behavior"),
+    ("q-bio.GN", "Genomics"),
+    ("q-bio.MN", "Molecular Networks"),
+    ("q-bio.NC", "Neurons and Cognition"),
+    ("q-bio.OT", "Other Quantitative Biology"),
+    ("q-bio.PE", "Populations and Evolution"),
+    ("q-bio.QM", "Quantitative Methods"),
+    ("q-bio.SC", "Subcellular Processes"),
+    ("q-bio.TO", "Tissues and Organs"),
+    // Quantitative Finance
+    ("q-fin.CP", "Computational Finance"),
+    ("q-fin.EC", "Economics"),
+    ("q-fin.GN", "General Finance"),
+    ("q-fin.MF", "Mathematical Finance"),
+    ("q-fin.PM", "Portfolio Management"),
+    ("q-fin.PR", "Pricing of Securities"),
+    ("q-fin.RM", "Risk Management"),
+    ("q-fin.ST", "Statistical Finance"),
+    ("q-fin.TR", "Trading and Market Microstructure"),
+    // Statistics
+    ("stat.AP", "Applications"),
+    ("stat.CO", "Computation"),
+    ("stat.ME", "Methodology"),
+    ("stat.ML", "Machine Learning"),
+    ("stat.OT", "Other Statistics"),
+    ("stat.TH", "Statistics Theory"),
+];
+
+/// Human readable name of a category code, e.g. "Quantum Physics" for "quant-ph".
+pub fn category_name(code: &str) -> Option<&'static str> {
+    ARXIV_CATEGORIES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+/// Known category closest to an unknown code, e.g. "quant-ph" for "quant-phh".
+pub fn suggest_category(code: &str) -> Option<&'static str> {
+    let codes: Vec<&'static str> = ARXIV_CATEGORIES.iter().map(|(code, _)| *code).collect();
+    SearchEngine::new().closest(code, &codes)
+}
+
+/// Check that the code is in the taxonomy, or else tell the closest known one, e.g.
+/// "unknown arXiv category `quantph`, did you mean `quant-ph`?".
+pub fn check_category(code: &str) -> Result<(), String> {
+    if category_name(code).is_some() {
+        return Ok(());
+    }
+    Err(match suggest_category(code) {
+        Some(suggestion) => {
+            format!("unknown arXiv category `{code}`, did you mean `{suggestion}`?")
+        }
+        None => format!("unknown arXiv category `{code}`, see --list-categories"),
+    })
+}
+
+/// The table of categories formatted with aligned columns, one category per line.
+pub fn format_category_list() -> String {
+    let width = ARXIV_CATEGORIES
+        .iter()
+        .map(|(code, _)| code.len())
+        .max()
+        .unwrap_or_default();
+    ARXIV_CATEGORIES
+        .iter()
+        .map(|(code, name)| format!("{code:<width$}  {name}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_name() {
+        assert_eq!(category_name("quant-ph"), Some("Quantum Physics"));
+        assert_eq!(category_name("cs.AI"), Some("Artificial Intelligence"));
+        assert_eq!(category_name("quant-phh"), None);
+    }
+
+    #[test]
+    fn test_check_category() {
+        assert_eq!(check_category("cs.AI"), Ok(()));
+        assert_eq!(
+            check_category("quantph"),
+            Err("unknown arXiv category `quantph`, did you mean `quant-ph`?".to_string())
+        );
+        assert_eq!(
+            check_category("zzzz"),
+            Err("unknown arXiv category `zzzz`, see --list-categories".to_string())
+        );
+    }
+
+    #[test]
+    fn test_suggest_category() {
+        assert_eq!(suggest_category("quant-phh"), Some("quant-ph"));
+        assert_eq!(suggest_category("hep-t"), Some("hep-th"));
+        assert_eq!(suggest_category("zzzz"), None);
+    }
+
+    #[test]
+    fn test_format_category_list() {
+        let list = format_category_list();
+        assert_eq!(list.lines().count(), ARXIV_CATEGORIES.len());
+        assert!(list
+            .lines()
+            .any(|line| line.starts_with("math.AG ") && line.ends_with("  Algebraic Geometry")));
+    }
+}
@@ -0,0 +1,110 @@
+//! Clients fetching the responses of the [`arXiv API`]
+//!
+//! The queries are sent through the [`ArxivClient`] trait, so that the rest of the
+//! application does not depend on the network: [`ReqwestClient`] queries arXiv, while
+//! [`FixtureClient`] answers with canned XML.
+//!
+//! [`arXiv API`] : https://info.arxiv.org/help/api/user-manual.html
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::{fetch_query_url, ArxivQueryError, DEFAULT_USER_AGENT};
+
+/// Source of the XML content returned by arXiv for a query url.
+pub trait ArxivClient: Send + Sync {
+    /// Fetch the XML content returned for the query url.
+    fn fetch(&self, query_url: &str) -> Result<String, ArxivQueryError>;
+}
+
+/// Client querying arXiv over HTTP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReqwestClient {
+    /// User agent identifying the client, as arXiv asks.
+    user_agent: String,
+}
+
+impl ReqwestClient {
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+        }
+    }
+}
+
+impl Default for ReqwestClient {
+    fn default() -> Self {
+        Self::new(DEFAULT_USER_AGENT)
+    }
+}
+
+impl ArxivClient for ReqwestClient {
+    fn fetch(&self, query_url: &str) -> Result<String, ArxivQueryError> {
+        fetch_query_url(query_url, &self.user_agent)
+    }
+}
+
+/// Client answering with the XML content given for each query url, without any network.
+///
+/// The query urls it was asked for are recorded, in order.
+#[derive(Debug, Default)]
+pub struct FixtureClient {
+    responses: HashMap<String, String>,
+    requests: Mutex<Vec<String>>,
+}
+
+impl FixtureClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the query url with the XML content.
+    pub fn with_response(
+        mut self,
+        query_url: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        self.responses.insert(query_url.into(), content.into());
+        self
+    }
+
+    /// Query urls fetched so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl ArxivClient for FixtureClient {
+    fn fetch(&self, query_url: &str) -> Result<String, ArxivQueryError> {
+        self.requests.lock().unwrap().push(query_url.to_string());
+        self.responses
+            .get(query_url)
+            .cloned()
+            .ok_or_else(|| ArxivQueryError::Request(format!("no fixture for {query_url}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_client() {
+        let client = FixtureClient::new().with_response("http://example.org/a", "<feed/>");
+
+        assert_eq!(
+            client.fetch("http://example.org/a"),
+            Ok("<feed/>".to_string())
+        );
+        assert_eq!(
+            client.fetch("http://example.org/b"),
+            Err(ArxivQueryError::Request(
+                "no fixture for http://example.org/b".to_string()
+            ))
+        );
+        assert_eq!(
+            client.requests(),
+            ["http://example.org/a", "http://example.org/b"]
+        );
+    }
+}
@@ -5,12 +5,21 @@
 
 use minidom::Element;
 use std::error::Error;
+use std::fmt::Display;
 
+use super::ArxivClient;
 use crate::search_highlight::search_patterns;
 
 const ENTRY_NS: &str = "http://www.w3.org/2005/Atom";
+const ARXIV_NS: &str = "http://arxiv.org/schemas/atom";
+const OPENSEARCH_NS: &str = "http://a9.com/-/spec/opensearch/1.1/";
+/// Part of the id of the entry arXiv sends instead of the articles when it rejects a
+/// query, e.g. `http://arxiv.org/api/errors#incorrect_id_format_for_1234.12345`.
+const API_ERROR_ID: &str = "api/errors";
+/// Number of characters of a malformed response kept in the error, to debug it.
+const MALFORMED_EXCERPT_LEN: usize = 200;
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct ArxivEntry {
     pub title: String,
     pub authors: Vec<String>,
@@ -18,6 +27,10 @@ pub struct ArxivEntry {
     pub id: String,
     pub updated: String,
     pub published: String,
+    /// Category the article was submitted to, e.g. "quant-ph".
+    pub primary_category: Option<String>,
+    /// All the categories of the article, the primary one and the cross-lists.
+    pub categories: Vec<String>,
     all_authors: String,
 }
 
@@ -38,14 +51,49 @@ impl ArxivEntry {
             id,
             updated,
             published,
+            primary_category: None,
+            categories: Vec::new(),
             all_authors,
         }
     }
 
+    /// The same entry with its categories.
+    pub fn with_categories(
+        mut self,
+        primary_category: Option<String>,
+        categories: Vec<String>,
+    ) -> Self {
+        self.primary_category = primary_category;
+        self.categories = categories;
+        self
+    }
+
+    /// Whether the article was revised since its first version.
+    pub fn is_revised(&self) -> bool {
+        self.updated != self.published
+    }
+
+    /// Whether the article is listed in one of the queried categories but was submitted
+    /// to another one. Unknown without the queried categories or the primary category.
+    pub fn is_cross_list(&self, queried_categories: &[&str]) -> bool {
+        match &self.primary_category {
+            Some(primary) if !queried_categories.is_empty() => {
+                !queried_categories.contains(&primary.as_str())
+            }
+            _ => false,
+        }
+    }
+
     pub fn get_all_authors(&self) -> &str {
         &self.all_authors
     }
 
+    /// Whether the article was published after the given date, in the format of the
+    /// arXiv dates.
+    pub fn is_published_after(&self, timestamp: &str) -> bool {
+        self.published.as_str() > timestamp
+    }
+
     pub fn contains_author(&self, author_patterns: Option<&[&str]>) -> bool {
         if let Some(patterns) = author_patterns {
             let matches = search_patterns(&self.all_authors, patterns);
@@ -54,47 +102,162 @@ impl ArxivEntry {
             false
         }
     }
+
+    /// Whether the title or the abstract contains one of the keywords, ignoring the case.
+    pub fn contains_keyword(&self, keywords: Option<&[&str]>) -> bool {
+        match keywords {
+            Some(keywords) if !keywords.is_empty() => {
+                !search_patterns(&self.title, keywords).is_empty()
+                    || !search_patterns(&self.summary, keywords).is_empty()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Error while querying arXiv or reading its response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArxivQueryError {
+    /// The request failed, e.g. arXiv could not be reached.
+    Request(String),
+    /// The response is not a feed of arXiv entries.
+    Parse(String),
+    /// arXiv rejected the query, e.g. a malformed id, with its explanation.
+    ApiError(String),
+    /// The response is not an Atom feed, e.g. an HTML page during a maintenance, with
+    /// its beginning.
+    MalformedResponse(String),
+}
+
+impl Display for ArxivQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArxivQueryError::Request(e) => write!(f, "{e}"),
+            ArxivQueryError::Parse(e) => write!(f, "invalid response from arXiv: {e}"),
+            ArxivQueryError::ApiError(e) => write!(f, "arXiv rejected the query: {e}"),
+            ArxivQueryError::MalformedResponse(excerpt) if excerpt.is_empty() => {
+                write!(
+                    f,
+                    "arXiv sent an empty response, it may be under maintenance"
+                )
+            }
+            ArxivQueryError::MalformedResponse(excerpt) => write!(
+                f,
+                "arXiv did not send a feed, it may be under maintenance: {excerpt}"
+            ),
+        }
+    }
+}
+
+impl Error for ArxivQueryError {}
+
+impl From<reqwest::Error> for ArxivQueryError {
+    fn from(e: reqwest::Error) -> Self {
+        ArxivQueryError::Request(e.to_string())
+    }
+}
+
+impl From<minidom::Error> for ArxivQueryError {
+    fn from(e: minidom::Error) -> Self {
+        ArxivQueryError::Parse(e.to_string())
+    }
+}
+
+impl ArxivQueryError {
+    /// Error of a response which is not an Atom feed, keeping its beginning on a line.
+    fn malformed(content: &str) -> Self {
+        let excerpt: String = content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(MALFORMED_EXCERPT_LEN)
+            .collect();
+        ArxivQueryError::MalformedResponse(excerpt)
+    }
+}
+
+/// Helper function to get the text of a required child
+fn child_text(element: &Element, name: &str) -> Result<String, ArxivQueryError> {
+    element
+        .get_child(name, ENTRY_NS)
+        .map(Element::text)
+        .ok_or_else(|| ArxivQueryError::Parse(format!("missing <{name}> element")))
 }
 
 /// Helper function to extract the authors
-fn extract_authors(entry: &Element) -> Result<Vec<String>, Box<dyn Error>> {
+fn extract_authors(entry: &Element) -> Result<Vec<String>, ArxivQueryError> {
     let mut names: Vec<String> = Vec::new();
 
     // Since there are several child with the same name, we iterate over all of them:
     for child in entry.children() {
         if child.is("author", ENTRY_NS) {
-            let name = child.get_child("name", ENTRY_NS).unwrap().text();
-            names.push(name)
+            names.push(child_text(child, "name")?)
         }
     }
 
     Ok(names)
 }
 
+/// Helper function to extract the primary category and all the categories
+fn extract_categories(entry: &Element) -> (Option<String>, Vec<String>) {
+    let primary_category = entry
+        .get_child("primary_category", ARXIV_NS)
+        .and_then(|child| child.attr("term"))
+        .map(str::to_string);
+    let categories = entry
+        .children()
+        .filter(|child| child.is("category", ENTRY_NS))
+        .filter_map(|child| child.attr("term"))
+        .map(str::to_string)
+        .collect();
+    (primary_category, categories)
+}
+
 /// Storing the result of the arxiv query
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct ArxivQueryResult {
     pub updated: String,
+    /// Number of articles matching the query, over all the pages. Unknown if arXiv did
+    /// not give it.
+    pub total_results: Option<usize>,
     pub articles: Vec<ArxivEntry>,
 }
 
 impl ArxivQueryResult {
-    pub fn from_xml_content(content: &str) -> Self {
-        let root: Element = content.parse().unwrap();
+    /// Parse the XML content returned by arXiv.
+    ///
+    /// A feed holding the error entry of arXiv gives an [`ArxivQueryError::ApiError`].
+    pub fn from_xml_content(content: &str) -> Result<Self, ArxivQueryError> {
+        let root: Element = match content.parse() {
+            Ok(root) if Element::is(&root, "feed", ENTRY_NS) => root,
+            _ => return Err(ArxivQueryError::malformed(content)),
+        };
 
         // Find the updated
-        let query_update = root.get_child("updated", ENTRY_NS).unwrap().text();
+        let query_update = child_text(&root, "updated")?;
+        let total_results = root
+            .get_child("totalResults", OPENSEARCH_NS)
+            .and_then(|child| child.text().trim().parse().ok());
 
         let mut articles: Vec<ArxivEntry> = Vec::new();
 
         for child in root.children() {
             if child.is("entry", ENTRY_NS) {
                 // Extract the main information
-                let title = child.get_child("title", ENTRY_NS).unwrap().text();
-                let id = child.get_child("id", ENTRY_NS).unwrap().text();
-                let summary = child.get_child("summary", ENTRY_NS).unwrap().text();
-                let updated = child.get_child("updated", ENTRY_NS).unwrap().text();
-                let published = child.get_child("published", ENTRY_NS).unwrap().text();
+                let title = child_text(child, "title")?;
+                let id = child_text(child, "id")?;
+                let summary = child_text(child, "summary")?;
+                // The explanation of a rejected query comes as an entry titled "Error",
+                // which is not an article and has no publication date.
+                let is_error = id.contains(API_ERROR_ID)
+                    || (title.trim() == "Error"
+                        && child.get_child("published", ENTRY_NS).is_none());
+                if is_error {
+                    return Err(ArxivQueryError::ApiError(summary.trim().to_string()));
+                }
+                let updated = child_text(child, "updated")?;
+                let published = child_text(child, "published")?;
 
                 // Extract the authors which have one more depth.
                 let authors = match extract_authors(child) {
@@ -102,35 +265,35 @@ impl ArxivQueryResult {
                     Err(_) => vec!["Error while parsing authors names".to_string()],
                 };
 
+                let (primary_category, categories) = extract_categories(child);
+
                 // Only add the new entry, ie published == updated
-                match updated.as_str() == published.as_str() {
-                    true => articles.push(ArxivEntry::new(
-                        title.replace("\n ", "").to_owned(), // arxiv has this formatting
-                        authors.to_owned(),
-                        summary.replace('\n', " ").to_owned(),
-                        id.to_owned(),
-                        updated.to_owned(),
-                        published.to_owned(),
-                    )),
-                    _ => (),
+                if updated.as_str() == published.as_str() {
+                    articles.push(
+                        ArxivEntry::new(
+                            title.replace("\n ", "").to_owned(), // arxiv has this formatting
+                            authors.to_owned(),
+                            summary.replace('\n', " ").to_owned(),
+                            id.to_owned(),
+                            updated.to_owned(),
+                            published.to_owned(),
+                        )
+                        .with_categories(primary_category, categories),
+                    )
                 }
             }
         }
         let articles = articles;
-        Self {
+        Ok(Self {
             updated: query_update,
+            total_results,
             articles,
-        }
+        })
     }
-    pub fn from_query(query: String) -> Self {
-        let query_response = match reqwest::blocking::get(query) {
-            Ok(content) => content,
-            Err(error) => panic!("Problem while querying arXiv: {error:?}"),
-        };
-        let xml_content = query_response.text().unwrap_or_else(|e| {
-            eprintln!("Request failed: {}", e);
-            std::process::exit(1);
-        });
+
+    /// Fetch the articles of the query url through the client.
+    pub fn from_query(client: &dyn ArxivClient, query: &str) -> Result<Self, ArxivQueryError> {
+        let xml_content = client.fetch(query)?;
         ArxivQueryResult::from_xml_content(&xml_content)
     }
 }
@@ -140,6 +303,7 @@ mod tests {
     use std::str::FromStr;
 
     use super::*;
+    use crate::arxiv::FixtureClient;
 
     #[test]
     fn test_extract_authors() -> Result<(), Box<dyn Error>> {
@@ -167,6 +331,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_api_error() {
+        let xml_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <link href="http://arxiv.org/api/query?search_query=&amp;id_list=1234.12345&amp;start=0&amp;max_results=10" rel="self" type="application/atom+xml"/>
+              <title type="html">ArXiv Query: search_query=&amp;id_list=1234.12345&amp;start=0&amp;max_results=10</title>
+              <id>http://arxiv.org/api/kvuntZ8c9a4Eq5CF7KY03nMug+Q</id>
+              <updated>2007-10-12T00:00:00-04:00</updated>
+              <opensearch:totalResults xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">1</opensearch:totalResults>
+              <opensearch:startIndex xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">0</opensearch:startIndex>
+              <opensearch:itemsPerPage xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">1</opensearch:itemsPerPage>
+              <entry>
+                <id>http://arxiv.org/api/errors#incorrect_id_format_for_1234.12345</id>
+                <title>Error</title>
+                <summary>incorrect id format for 1234.12345</summary>
+                <updated>2007-10-12T00:00:00-04:00</updated>
+                <link href="http://arxiv.org/api/errors#incorrect_id_format_for_1234.12345" rel="alternate" type="text/html"/>
+                <author>
+                  <name>arXiv api core</name>
+                </author>
+              </entry>
+            </feed>
+        "#;
+        let query = "http://export.arxiv.org/api/query?id_list=1234.12345";
+        let client = FixtureClient::new().with_response(query, xml_content);
+        let error = ArxivQueryResult::from_query(&client, query).unwrap_err();
+        assert_eq!(
+            error,
+            ArxivQueryError::ApiError("incorrect id format for 1234.12345".to_string())
+        );
+        assert_eq!(
+            error.to_string(),
+            "arXiv rejected the query: incorrect id format for 1234.12345"
+        );
+
+        // The error entry is recognized by its shape, whatever its id.
+        let xml_content = xml_content.replace("api/errors#", "api/");
+        assert_eq!(
+            ArxivQueryResult::from_xml_content(&xml_content),
+            Err(ArxivQueryError::ApiError(
+                "incorrect id format for 1234.12345".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn test_parse_arxiv_entries() -> Result<(), Box<dyn Error>> {
         let xml_content = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -190,6 +399,9 @@ mod tests {
                 <author>
                   <name>Author Two</name>
                 </author>
+                <arxiv:primary_category xmlns:arxiv="http://arxiv.org/schemas/atom" term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+                <category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+                <category term="quant-ph" scheme="http://arxiv.org/schemas/atom"/>
               </entry>
               <entry>
                 <id>http://arxiv.org/abs/1212.34567</id>
@@ -205,6 +417,7 @@ mod tests {
         .to_string();
         let expected_result = ArxivQueryResult {
             updated: "2024-07-09T20:00:00Z".to_string(),
+            total_results: Some(10),
             articles: vec![
                 ArxivEntry {
                     title: String::from("Sample Title 1"),
@@ -215,6 +428,8 @@ mod tests {
                     id: String::from("http://arxiv.org/abs/9876.54321"),
                     updated: String::from("2023-12-31T23:59:59Z"),
                     published: String::from("2023-12-31T23:59:59Z"),
+                    primary_category: Some(String::from("cs.LG")),
+                    categories: vec![String::from("cs.LG"), String::from("quant-ph")],
                     all_authors: String::from("Author One, Author Two"),
                 },
                 ArxivEntry {
@@ -224,15 +439,127 @@ mod tests {
                     id: String::from("http://arxiv.org/abs/1212.34567"),
                     updated: String::from("2024-01-01T00:00:00Z"),
                     published: String::from("2024-01-01T00:00:00Z"),
+                    primary_category: None,
+                    categories: Vec::new(),
                     all_authors: String::from("Author Three"),
                 },
             ],
         };
 
-        let actual_result = ArxivQueryResult::from_xml_content(&xml_content);
+        let query = "http://export.arxiv.org/api/query?search_query=fake:topic";
+        let client = FixtureClient::new().with_response(query, xml_content);
+        let actual_result = ArxivQueryResult::from_query(&client, query)?;
+
+        assert_eq!(client.requests(), [query]);
 
         assert_eq!(expected_result, actual_result);
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_invalid_content() {
+        assert_eq!(
+            ArxivQueryResult::from_xml_content("Rate exceeded."),
+            Err(ArxivQueryError::MalformedResponse(
+                "Rate exceeded.".to_string()
+            ))
+        );
+
+        let missing_title = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <updated>2024-01-01T00:00:00Z</updated>
+              <entry>
+                <id>http://arxiv.org/abs/1212.34567</id>
+              </entry>
+            </feed>"#;
+        assert_eq!(
+            ArxivQueryResult::from_xml_content(missing_title),
+            Err(ArxivQueryError::Parse(
+                "missing <title> element".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_maintenance_page() {
+        let page = format!(
+            r#"<!DOCTYPE html>
+            <html>
+              <head><title>arXiv is down for maintenance</title></head>
+              <body>
+                <p>{}</p>
+              </body>
+            </html>"#,
+            "We are performing scheduled maintenance. ".repeat(10)
+        );
+        let Err(ArxivQueryError::MalformedResponse(excerpt)) =
+            ArxivQueryResult::from_xml_content(&page)
+        else {
+            panic!("expected a malformed response");
+        };
+        assert!(excerpt.starts_with(
+            "<!DOCTYPE html> <html> <head><title>arXiv is down for maintenance</title></head>"
+        ));
+        assert_eq!(excerpt.chars().count(), MALFORMED_EXCERPT_LEN);
+
+        // Well-formed XML, but not a feed.
+        let xhtml = r#"<html xmlns="http://www.w3.org/1999/xhtml"><body>Down</body></html>"#;
+        assert!(matches!(
+            ArxivQueryResult::from_xml_content(xhtml),
+            Err(ArxivQueryError::MalformedResponse(_))
+        ));
+
+        let error = ArxivQueryResult::from_xml_content(" \n").unwrap_err();
+        assert_eq!(error, ArxivQueryError::MalformedResponse(String::new()));
+        assert_eq!(
+            error.to_string(),
+            "arXiv sent an empty response, it may be under maintenance"
+        );
+    }
+
+    #[test]
+    fn test_is_cross_list() {
+        let entry = ArxivEntry::default()
+            .with_categories(Some("cs.LG".to_string()), vec!["cs.LG".to_string()]);
+        assert!(entry.is_cross_list(&["quant-ph"]));
+        assert!(!entry.is_cross_list(&["quant-ph", "cs.LG"]));
+        assert!(!entry.is_cross_list(&[]));
+        assert!(!ArxivEntry::default().is_cross_list(&["quant-ph"]));
+    }
+
+    #[test]
+    fn test_is_published_after() {
+        let entry = |published: &str| {
+            ArxivEntry::new(
+                String::new(),
+                Vec::new(),
+                String::new(),
+                String::new(),
+                published.to_string(),
+                published.to_string(),
+            )
+        };
+        let last_run = "2024-01-01T00:00:00Z";
+
+        assert!(!entry("2023-12-31T23:59:59Z").is_published_after(last_run));
+        assert!(entry("2024-01-01T00:00:01Z").is_published_after(last_run));
+    }
+
+    #[test]
+    fn test_contains_keyword() {
+        let entry = ArxivEntry::new(
+            "Decoding the surface code".to_string(),
+            Vec::new(),
+            "We study a transmon architecture.".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+        );
+        assert!(entry.contains_keyword(Some(&["Surface Code"])));
+        assert!(entry.contains_keyword(Some(&["fluxonium", "transmon"])));
+        assert!(!entry.contains_keyword(Some(&["error correction"])));
+        assert!(!entry.contains_keyword(Some(&[])));
+        assert!(!entry.contains_keyword(None));
+    }
 }
@@ -12,21 +12,36 @@
 //! - `sortOrder`: Defines the sorting order of the entries ("ascending" or "descending").
 //!
 //! Here is an example of a search query:
-//! http://export.arxiv.org/api/query?search_query=ti:"electron thermal conductivity"&sortBy=lastUpdatedDate&sortOrder=ascending
+//! http://export.arxiv.org/api/query?search_query=ti:%22electron+thermal+conductivity%22&sortBy=lastUpdatedDate&sortOrder=ascending
 //!
 //! For more in-depth documentation, look at the [`arXiv API`] user manual.
 //!
 //! [`arXiv API`] : https://info.arxiv.org/help/api/user-manual.html
 
 use std::collections::BTreeMap;
-use std::error::Error;
 use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::USER_AGENT;
+use serde::{Deserialize, Serialize, Serializer};
+
+use super::{ArxivClient, ArxivQueryError};
 
 const ARXIV_QUERY_BASE_URL: &str = "http://export.arxiv.org/api/query?";
 
+/// Maximum number of results arXiv returns for a single request.
+pub const ARXIV_MAX_RESULTS: i32 = 2000;
+
+/// User agent identifying the requests to arXiv, unless the config gives another one.
+pub const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
 // --- Construct the search query ---
 
 /// Specifies different query options for searching the arXiv archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SearchQuery {
     /// Search for articles by title.
     Title(String),
@@ -45,6 +60,8 @@ pub enum SearchQuery {
     /// Search for articles using a general query string across all fields.
     /// Use with caution as it might lead to unexpected results due to potential broad matches.
     All(String),
+    /// Search for articles matching any of the queries (e.g. several categories).
+    AnyOf(Vec<SearchQuery>),
 }
 
 impl SearchQuery {
@@ -64,6 +81,27 @@ impl SearchQuery {
             SearchQuery::Category(_) => "cat",
             SearchQuery::ReportNumber(_) => "rn",
             SearchQuery::All(_) => "all",
+            SearchQuery::AnyOf(queries) => queries.first().map_or("all", |q| q.category()),
+        }
+    }
+
+    /// Build a query matching any of the given ones.
+    ///
+    /// A single query is returned as is, so that the url stays the same as before.
+    pub fn any_of(mut queries: Vec<SearchQuery>) -> Option<SearchQuery> {
+        match queries.len() {
+            0 => None,
+            1 => queries.pop(),
+            _ => Some(SearchQuery::AnyOf(queries)),
+        }
+    }
+
+    /// The term with its field prefix, e.g. `au:Doe`, the alternatives of an `AnyOf`
+    /// group being prefixed each.
+    fn prefixed(&self) -> String {
+        match self {
+            SearchQuery::AnyOf(_) => self.to_string(),
+            query => format!("{}:{query}", query.category()),
         }
     }
 }
@@ -79,51 +117,151 @@ impl Display for SearchQuery {
             SearchQuery::Category(term) => write!(f, "{}", term),
             SearchQuery::ReportNumber(term) => write!(f, "{}", term),
             SearchQuery::All(term) => write!(f, "{}", term),
+            SearchQuery::AnyOf(queries) => {
+                let alternatives: Vec<String> = queries.iter().map(SearchQuery::prefixed).collect();
+                write!(f, "({})", alternatives.join(" OR "))
+            }
         }
     }
 }
 
+/// Encode the text of a term for the `search_query` parameter: the spaces become `+`
+/// and the other characters outside of the unreserved ones are percent-encoded. A text
+/// of several words is quoted, to be searched as a phrase.
+fn encode_term(term: &str) -> String {
+    let term = term.split_whitespace().collect::<Vec<_>>().join(" ");
+    let quoted = term.contains(' ');
+    let mut encoded = String::new();
+    for byte in term.bytes() {
+        match byte {
+            b' ' => encoded.push('+'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(char::from(byte))
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    if quoted {
+        format!("%22{encoded}%22")
+    } else {
+        encoded
+    }
+}
+
+/// The query as a term of the `search_query` parameter, e.g. `ti:%22surface+code%22`, or
+/// `%28cat:quant-ph+OR+cat:cs.LG%29` for an `AnyOf` query.
+fn url_term(query: &SearchQuery) -> String {
+    match query {
+        SearchQuery::AnyOf(queries) => {
+            let alternatives: Vec<String> = queries.iter().map(url_term).collect();
+            format!("%28{}%29", alternatives.join("+OR+"))
+        }
+        query => format!("{}:{}", query.category(), encode_term(&query.to_string())),
+    }
+}
+
 /// Groups and joins search queries for constructing a well-formatted arXiv API query string.
 ///
-/// This function takes a slice of `SeqrchQuery` structs and groups them by their
-/// category. It then joins the queries within each category using `+AND+` and
-/// combines the category groups with `&` to create a single, valid query string
-/// suitable for the arXiv API.
+/// This function takes a slice of `SearchQuery` structs, groups them by their category
+/// and joins every term with `+AND+`, each with its own field prefix since arXiv only
+/// applies a prefix to the term right after it. The whole string is the value of the
+/// single `search_query` parameter.
+///
+/// `AnyOf` queries are not grouped: each one is an `+OR+` group in parentheses, ordered
+/// with the category of its alternatives.
 ///
 /// The function utilizes a `BTreeMap` to ensure a deterministic output order
 /// for the categories and their joined queries.
 fn group_and_join_queries(search_queries: &[SearchQuery]) -> String {
     let mut grouped_queries: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
-
     for query in search_queries {
         grouped_queries
             .entry(query.category())
             .or_default()
-            .push(format!("{}", query));
-    }
-
-    let mut joined_query: Vec<String> = Vec::new();
-    for (category, category_queries) in grouped_queries.iter_mut() {
-        let mut category_query = format!("{}:", category);
-        category_query.push_str(&category_queries.join("+AND+"));
-        joined_query.push(category_query);
+            .push(url_term(query));
     }
-    joined_query.join("&")
+    grouped_queries
+        .into_values()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("+AND+")
 }
 
 // --- Option for the query ---
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
 pub enum SortBy {
     Relevance,
     LastUpdatedDate,
     SubmittedDate,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
 pub enum SortOrder {
     Ascending,
     Descending,
 }
 
+impl FromStr for SortBy {
+    type Err = String;
+
+    /// Parse the short names used on the command line, as well as the arXiv API names.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "relevance" => Ok(SortBy::Relevance),
+            "updated" | "lastUpdatedDate" => Ok(SortBy::LastUpdatedDate),
+            "submitted" | "submittedDate" => Ok(SortBy::SubmittedDate),
+            _ => Err(format!(
+                "unknown sort `{s}`, expected one of: relevance, submitted, updated"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for SortBy {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" | "ascending" => Ok(SortOrder::Ascending),
+            "desc" | "descending" => Ok(SortOrder::Descending),
+            _ => Err(format!(
+                "unknown sort order `{s}`, expected one of: asc, desc"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for SortOrder {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Serialize for SortBy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for SortOrder {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl Display for SortBy {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -242,14 +380,15 @@ pub fn get_query_url(
     format!("{}{}", ARXIV_QUERY_BASE_URL, search_query)
 }
 
-/// Query arXiv with the query url.
+/// Query arXiv with the query url, through the client.
 pub fn query_arxiv(
+    client: &dyn ArxivClient,
     search_queries: Option<&[SearchQuery]>,
     start_index: Option<i32>,
     max_results: Option<i32>,
     sort_by: Option<SortBy>,
     sort_order: Option<SortOrder>,
-) -> Result<String, Box<dyn Error>> {
+) -> Result<String, ArxivQueryError> {
     let query_str = get_query_url(
         search_queries,
         start_index,
@@ -257,13 +396,333 @@ pub fn query_arxiv(
         sort_by,
         sort_order,
     );
-    Ok(reqwest::blocking::get(query_str)?.text()?)
+    client.fetch(&query_str)
+}
+
+/// Time arXiv asks clients to leave between two requests.
+pub const DEFAULT_REQUEST_DELAY: Duration = Duration::from_secs(3);
+
+/// Spacing of the requests to arXiv by a minimum delay, so that paging quickly through
+/// the results does not hammer the API.
+#[derive(Debug, Clone)]
+pub struct RequestScheduler {
+    delay: Duration,
+    /// When the last request was scheduled to start.
+    last: Option<Instant>,
+}
+
+impl RequestScheduler {
+    pub fn new(delay: Duration) -> Self {
+        Self { delay, last: None }
+    }
+
+    /// Schedule a request asked for at `now`, returning how long to wait before sending
+    /// it: until `delay` after the previous request, or none if that is already past.
+    pub fn reserve(&mut self, now: Instant) -> Duration {
+        let start = match self.last {
+            Some(last) => (last + self.delay).max(now),
+            None => now,
+        };
+        self.last = Some(start);
+        start - now
+    }
+}
+
+/// Request of a query url, identifying the client with the user agent as arXiv asks.
+pub fn query_request(query_url: &str, user_agent: &str) -> RequestBuilder {
+    Client::new().get(query_url).header(USER_AGENT, user_agent)
+}
+
+/// Fetch the XML content returned by arXiv for a query url.
+pub fn fetch_query_url(query_url: &str, user_agent: &str) -> Result<String, ArxivQueryError> {
+    Ok(query_request(query_url, user_agent).send()?.text()?)
+}
+
+/// Complete query of the arXiv API, kept to run it again with some changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArxivQuery {
+    pub search_queries: Vec<SearchQuery>,
+    pub start_index: i32,
+    pub max_results: i32,
+    pub sort_by: SortBy,
+    pub sort_order: SortOrder,
+    /// Identifiers of the articles to fetch, whatever the search queries.
+    pub id_list: Vec<String>,
+}
+
+impl ArxivQuery {
+    /// Url of the query, see [`get_query_url`], followed by the `id_list` if any.
+    pub fn url(&self) -> String {
+        let url = get_query_url(
+            Some(&self.search_queries),
+            Some(self.start_index),
+            Some(self.max_results),
+            Some(self.sort_by),
+            Some(self.sort_order),
+        );
+        if self.id_list.is_empty() {
+            url
+        } else {
+            format!("{url}&id_list={}", self.id_list.join(","))
+        }
+    }
+
+    /// Page of the results fetched by the query, e.g. "page 2 of 22", or "page 2" if the
+    /// total number of results is unknown.
+    pub fn page_label(&self, total_results: Option<usize>) -> String {
+        let per_page = self.max_results.max(1) as usize;
+        let page = self.start_index.max(0) as usize / per_page + 1;
+        match total_results {
+            Some(total) => format!("page {page} of {}", total.div_ceil(per_page).max(page)),
+            None => format!("page {page}"),
+        }
+    }
+
+    /// Categories the query looks in, e.g. `["quant-ph"]`.
+    pub fn categories(&self) -> Vec<&str> {
+        fn categories<'q>(queries: &'q [SearchQuery], found: &mut Vec<&'q str>) {
+            for query in queries {
+                match query {
+                    SearchQuery::Category(category) => found.push(category),
+                    SearchQuery::AnyOf(queries) => categories(queries, found),
+                    _ => {}
+                }
+            }
+        }
+        let mut found = Vec::new();
+        categories(&self.search_queries, &mut found);
+        found
+    }
+
+    /// Short description of the query, shown while it runs, e.g. "Fetching 200 latest
+    /// quant-ph papers…".
+    pub fn description(&self) -> String {
+        if !self.id_list.is_empty() {
+            return format!("Fetching arXiv:{}…", self.id_list.join(", arXiv:"));
+        }
+        let found = self.categories();
+        let latest =
+            if self.sort_by == SortBy::SubmittedDate && self.sort_order == SortOrder::Descending {
+                " latest"
+            } else {
+                ""
+            };
+        let categories = if found.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", found.join(", "))
+        };
+        format!("Fetching {}{latest}{categories} papers…", self.max_results)
+    }
+
+    /// Terms of the query, e.g. "cat:quant-ph au:Curie".
+    pub fn terms(&self) -> String {
+        let ids = self.id_list.iter().map(|id| format!("id:{id}"));
+        let terms: Vec<String> = self
+            .search_queries
+            .iter()
+            .map(SearchQuery::prefixed)
+            .chain(ids)
+            .collect();
+        terms.join(" ")
+    }
+
+    /// Summary of the query for the header, e.g. "cat:quant-ph · sorted by submittedDate
+    /// desc".
+    pub fn summary(&self) -> String {
+        let order = match self.sort_order {
+            SortOrder::Ascending => "asc",
+            SortOrder::Descending => "desc",
+        };
+        format!("{} · sorted by {} {order}", self.terms(), self.sort_by)
+    }
+
+    /// The same query looking in other categories.
+    pub fn with_categories(&self, categories: &[String]) -> Self {
+        let is_category = |query: &SearchQuery| match query {
+            SearchQuery::Category(_) => true,
+            SearchQuery::AnyOf(queries) => queries
+                .iter()
+                .all(|query| matches!(query, SearchQuery::Category(_))),
+            _ => false,
+        };
+        let mut search_queries: Vec<SearchQuery> = self
+            .search_queries
+            .iter()
+            .filter(|query| !is_category(query))
+            .cloned()
+            .collect();
+        let categories = categories.iter().cloned().map(SearchQuery::Category);
+        search_queries.extend(SearchQuery::any_of(categories.collect()));
+        Self {
+            search_queries,
+            ..self.clone()
+        }
+    }
+
+    /// Query of the articles of an author, from the first page, with the same number of
+    /// results and sorting.
+    pub fn for_author(&self, name: &str) -> Self {
+        self.only(SearchQuery::Author(author_term(name)))
+    }
+
+    /// Query of the articles of a category, from the first page, with the same number of
+    /// results and sorting.
+    pub fn for_category(&self, category: &str) -> Self {
+        self.only(SearchQuery::Category(category.to_string()))
+    }
+
+    /// Query of the article of an arXiv identifier, with the same sorting.
+    pub fn for_id(&self, id: &str) -> Self {
+        Self {
+            search_queries: Vec::new(),
+            start_index: 0,
+            id_list: vec![id.to_string()],
+            ..self.clone()
+        }
+    }
+
+    /// The same query from the first page, searching for `search_query` only.
+    fn only(&self, search_query: SearchQuery) -> Self {
+        Self {
+            search_queries: vec![search_query],
+            start_index: 0,
+            id_list: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// The arXiv identifier the text consists of, e.g. "2401.01234", "2401.01234v2" or
+/// "arXiv:2401.01234", without the "arXiv:" prefix.
+pub fn parse_arxiv_id(text: &str) -> Option<&str> {
+    let text = text.trim();
+    let id = text
+        .strip_prefix("arXiv:")
+        .or_else(|| text.strip_prefix("arxiv:"))
+        .unwrap_or(text);
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let (number, version) = match id.split_once('v') {
+        Some((number, version)) => (number, Some(version)),
+        None => (id, None),
+    };
+    let (month, sequence) = number.split_once('.')?;
+    let valid = month.len() == 4
+        && digits(month)
+        && (4..=5).contains(&sequence.len())
+        && digits(sequence)
+        && version.is_none_or(digits);
+    valid.then_some(id)
+}
+
+/// Author name in the form searched by arXiv, the surname followed by the first
+/// initial, e.g. "Einstein_A" for "Albert Einstein".
+pub fn author_term(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    match words.as_slice() {
+        [] => String::new(),
+        [surname] => surname.to_string(),
+        [first, .., surname] => {
+            let initial: String = first.chars().take(1).collect();
+            format!("{surname}_{initial}")
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_query_request_user_agent() {
+        let url = format!("{ARXIV_QUERY_BASE_URL}search_query=cat:quant-ph");
+        let user_agent = |user_agent| {
+            query_request(&url, user_agent).build().unwrap().headers()[USER_AGENT]
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            user_agent(DEFAULT_USER_AGENT),
+            format!("arxivlens/{}", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(
+            user_agent("lab-digest/1.0 (mailto:me@example.org)"),
+            "lab-digest/1.0 (mailto:me@example.org)"
+        );
+    }
+
+    #[test]
+    fn test_query_arxiv_through_client() {
+        let search_queries = [SearchQuery::Category("quant-ph".to_string())];
+        let url = get_query_url(Some(&search_queries), Some(0), Some(10), None, None);
+        let client = crate::arxiv::FixtureClient::new().with_response(&url, "<feed/>");
+
+        let content = query_arxiv(
+            &client,
+            Some(&search_queries),
+            Some(0),
+            Some(10),
+            None,
+            None,
+        );
+
+        assert_eq!(content, Ok("<feed/>".to_string()));
+        assert_eq!(client.requests(), [url]);
+    }
+
+    #[test]
+    fn test_parse_arxiv_id() {
+        assert_eq!(parse_arxiv_id("2401.01234"), Some("2401.01234"));
+        assert_eq!(parse_arxiv_id(" arXiv:2401.0123v2 "), Some("2401.0123v2"));
+        assert_eq!(parse_arxiv_id("2401.01234v"), None);
+        assert_eq!(parse_arxiv_id("2401.012345"), None);
+        assert_eq!(parse_arxiv_id("240.01234"), None);
+        assert_eq!(parse_arxiv_id("surface code"), None);
+    }
+
+    #[test]
+    fn test_arxiv_query_for_id() {
+        let query = ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 200,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+        let by_id = query.for_id("2401.01234");
+        assert_eq!(
+            by_id.url(),
+            format!(
+                "{ARXIV_QUERY_BASE_URL}search_query=&start=0&max_results=200\
+                 &sortBy=submittedDate&sortOrder=descending&id_list=2401.01234"
+            )
+        );
+        assert_eq!(by_id.terms(), "id:2401.01234");
+        assert_eq!(by_id.description(), "Fetching arXiv:2401.01234…");
+        assert_eq!(by_id.for_category("quant-ph").id_list, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_request_scheduler_spacing() {
+        let delay = Duration::from_secs(3);
+        let mut scheduler = RequestScheduler::new(delay);
+        let t0 = Instant::now();
+
+        assert_eq!(scheduler.reserve(t0), Duration::ZERO);
+        // Asked one second later, the request waits for the end of the delay.
+        assert_eq!(
+            scheduler.reserve(t0 + Duration::from_secs(1)),
+            Duration::from_secs(2)
+        );
+        // Requests asked at once are each spaced by the delay.
+        let now = t0 + Duration::from_secs(20);
+        let starts: Vec<Instant> = (0..3).map(|_| now + scheduler.reserve(now)).collect();
+        assert_eq!(starts[0], now);
+        assert!(starts.windows(2).all(|pair| pair[1] - pair[0] >= delay));
+    }
+
     // ----- Testing the construction of the query url -----
     #[test]
     fn test_get_search_query_basic() {
@@ -297,7 +756,10 @@ mod tests {
         );
         assert_eq!(
             url,
-            format!("{}search_query=au:Albert Einstein", ARXIV_QUERY_BASE_URL)
+            format!(
+                "{}search_query=au:%22Albert+Einstein%22",
+                ARXIV_QUERY_BASE_URL
+            )
         );
     }
 
@@ -316,7 +778,7 @@ mod tests {
         assert_eq!(
         url,
         format!(
-          "{}search_query=au:Jane Doe&cat:stat.ML&start=10&max_results=50&sortBy=lastUpdatedDate&sortOrder=descending",
+          "{}search_query=au:%22Jane+Doe%22+AND+cat:stat.ML&start=10&max_results=50&sortBy=lastUpdatedDate&sortOrder=descending",
           ARXIV_QUERY_BASE_URL
         )
       );
@@ -333,7 +795,7 @@ mod tests {
         ];
 
         // Expected encoded query string
-        let expected_query = "abs:Entanglement&au:Doe&cat:quant-ph&ti:Holes";
+        let expected_query = "abs:Entanglement+AND+au:Doe+AND+cat:quant-ph+AND+ti:Holes";
 
         // Test the function and compare with expected result
         let encoded_query = group_and_join_queries(&queries);
@@ -352,8 +814,8 @@ mod tests {
         ];
 
         // Expected encoded query string
-        let expected_query =
-            "abs:Entanglement&au:John Doe&ti:Quantum Mechanics+AND+Black Holes+AND+Relativity";
+        let expected_query = "abs:Entanglement+AND+au:%22John+Doe%22\
+             +AND+ti:%22Quantum+Mechanics%22+AND+ti:%22Black+Holes%22+AND+ti:Relativity";
 
         // Test the function
         let encoded_query = group_and_join_queries(&queries);
@@ -361,4 +823,246 @@ mod tests {
         // Assert encoded query matches expectation
         assert_eq!(encoded_query, expected_query);
     }
+
+    #[test]
+    fn test_encode_term() {
+        assert_eq!(encode_term("quant-ph"), "quant-ph");
+        assert_eq!(encode_term("Phys. Rev.  Lett."), "%22Phys.+Rev.+Lett.%22");
+        assert_eq!(encode_term("50%&up"), "50%25%26up");
+        assert_eq!(encode_term("Schrödinger"), "Schr%C3%B6dinger");
+    }
+
+    #[test]
+    fn test_group_and_join_queries_any_of() {
+        let queries = vec![
+            SearchQuery::AnyOf(vec![
+                SearchQuery::Category("quant-ph".to_string()),
+                SearchQuery::Category("cond-mat.mes-hall".to_string()),
+            ]),
+            SearchQuery::Author("Doe".to_string()),
+            SearchQuery::Title("Qubit".to_string()),
+        ];
+
+        let expected_query = "au:Doe+AND+%28cat:quant-ph+OR+cat:cond-mat.mes-hall%29+AND+ti:Qubit";
+        assert_eq!(group_and_join_queries(&queries), expected_query);
+    }
+
+    #[test]
+    fn test_any_of() {
+        assert_eq!(SearchQuery::any_of(vec![]), None);
+        assert_eq!(
+            SearchQuery::any_of(vec![SearchQuery::Author("Doe".to_string())]),
+            Some(SearchQuery::Author("Doe".to_string()))
+        );
+        assert_eq!(
+            SearchQuery::any_of(vec![
+                SearchQuery::Author("Doe".to_string()),
+                SearchQuery::Author("Smith".to_string()),
+            ])
+            .map(|q| q.to_string()),
+            Some("(au:Doe OR au:Smith)".to_string())
+        );
+
+        // The url has the encoded form.
+        let query = ArxivQuery {
+            search_queries: vec![SearchQuery::AnyOf(vec![
+                SearchQuery::Author("Doe".to_string()),
+                SearchQuery::Author("van der Berg".to_string()),
+            ])],
+            start_index: 0,
+            max_results: 50,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+        assert!(query
+            .url()
+            .contains("search_query=%28au:Doe+OR+au:%22van+der+Berg%22%29&"));
+    }
+
+    #[test]
+    fn test_sort_by_from_str() {
+        assert_eq!("relevance".parse(), Ok(SortBy::Relevance));
+        assert_eq!("submitted".parse(), Ok(SortBy::SubmittedDate));
+        assert_eq!("lastUpdatedDate".parse(), Ok(SortBy::LastUpdatedDate));
+        let error = "submited".parse::<SortBy>().unwrap_err();
+        assert!(error.contains("submited"));
+        assert!(error.contains("relevance, submitted, updated"));
+    }
+
+    #[test]
+    fn test_sort_order_from_str() {
+        assert_eq!("asc".parse(), Ok(SortOrder::Ascending));
+        assert_eq!("descending".parse(), Ok(SortOrder::Descending));
+        assert!("down".parse::<SortOrder>().is_err());
+    }
+
+    #[test]
+    fn test_arxiv_query_description() {
+        let query = ArxivQuery {
+            search_queries: vec![
+                SearchQuery::Author("Curie".to_string()),
+                SearchQuery::Category("quant-ph".to_string()),
+            ],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+        assert_eq!(query.description(), "Fetching 200 latest quant-ph papers…");
+
+        let query = ArxivQuery {
+            search_queries: vec![SearchQuery::Author("Curie".to_string())],
+            sort_by: SortBy::Relevance,
+            ..query
+        };
+        assert_eq!(query.description(), "Fetching 200 papers…");
+    }
+
+    #[test]
+    fn test_arxiv_query_page_label() {
+        let query = ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+        assert_eq!(query.page_label(Some(4321)), "page 1 of 22");
+        assert_eq!(query.page_label(Some(200)), "page 1 of 1");
+        assert_eq!(query.page_label(Some(0)), "page 1 of 1");
+        assert_eq!(query.page_label(None), "page 1");
+
+        let query = ArxivQuery {
+            start_index: 400,
+            ..query
+        };
+        assert_eq!(query.page_label(Some(4321)), "page 3 of 22");
+        assert_eq!(query.page_label(None), "page 3");
+    }
+
+    #[test]
+    fn test_arxiv_query_summary() {
+        let query = ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 200,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+        assert_eq!(
+            query.summary(),
+            "cat:quant-ph · sorted by submittedDate desc"
+        );
+
+        let query = ArxivQuery {
+            search_queries: vec![
+                SearchQuery::Title("qubit".to_string()),
+                SearchQuery::AnyOf(vec![
+                    SearchQuery::Author("Curie".to_string()),
+                    SearchQuery::Author("Bohr".to_string()),
+                ]),
+            ],
+            sort_order: SortOrder::Ascending,
+            ..query
+        };
+        assert_eq!(
+            query.summary(),
+            "ti:qubit (au:Curie OR au:Bohr) · sorted by submittedDate asc"
+        );
+    }
+
+    #[test]
+    fn test_arxiv_query_with_categories() {
+        let query = ArxivQuery {
+            search_queries: vec![
+                SearchQuery::Author("Curie".to_string()),
+                SearchQuery::AnyOf(vec![
+                    SearchQuery::Category("quant-ph".to_string()),
+                    SearchQuery::Category("cs.LG".to_string()),
+                ]),
+            ],
+            start_index: 0,
+            max_results: 100,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+
+        let changed = query.with_categories(&["hep-th".to_string()]);
+
+        assert_eq!(
+            changed.search_queries,
+            vec![
+                SearchQuery::Author("Curie".to_string()),
+                SearchQuery::Category("hep-th".to_string()),
+            ]
+        );
+        assert_eq!(changed.max_results, 100);
+        assert_eq!(
+            changed.url(),
+            get_query_url(
+                Some(&changed.search_queries),
+                Some(0),
+                Some(100),
+                Some(SortBy::SubmittedDate),
+                Some(SortOrder::Descending),
+            )
+        );
+    }
+
+    #[test]
+    fn test_arxiv_query_for_author() {
+        let query = ArxivQuery {
+            search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+            start_index: 400,
+            max_results: 100,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+
+        let by_author = query.for_author("Albert Einstein");
+
+        assert_eq!(
+            by_author.search_queries,
+            vec![SearchQuery::Author("Einstein_A".to_string())]
+        );
+        assert_eq!(by_author.start_index, 0);
+        assert_eq!(by_author.max_results, 100);
+        assert!(by_author
+            .url()
+            .contains("search_query=au:Einstein_A&start=0"));
+
+        assert_eq!(author_term("M. Curie"), "Curie_M");
+        assert_eq!(author_term("Becquerel"), "Becquerel");
+        assert_eq!(author_term("  "), "");
+    }
+
+    #[test]
+    fn test_arxiv_query_for_category() {
+        let query = ArxivQuery {
+            search_queries: vec![
+                SearchQuery::Author("Curie".to_string()),
+                SearchQuery::Category("quant-ph".to_string()),
+            ],
+            start_index: 200,
+            max_results: 100,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+
+        let by_category = query.for_category("cs.LG");
+
+        assert_eq!(
+            by_category.search_queries,
+            vec![SearchQuery::Category("cs.LG".to_string())]
+        );
+        assert_eq!(by_category.start_index, 0);
+        assert!(by_category.url().contains("search_query=cat:cs.LG&start=0"));
+    }
 }
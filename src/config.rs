@@ -1,25 +1,201 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::arxiv::categories::{category_name, suggest_category};
+use crate::arxiv::{
+    SortBy, SortOrder, ARXIV_MAX_RESULTS, DEFAULT_REQUEST_DELAY, DEFAULT_USER_AGENT,
+};
+use crate::date::DateFormat;
+use crate::export::{CitationStyle, DigestOptions};
+use crate::search::{CrossListMode, SearchEngine};
+use crate::tools::{
+    expand_tilde, validate_template, TemplateError, DEFAULT_BROWSER_COMMAND, PATH_PLACEHOLDER,
+    URL_PLACEHOLDER,
+};
+use crate::ui::ScrollMode;
 
 const APP_DIR_NAME: &str = "arxivlens";
 const CONFIG_FILE_NAME: &str = "config.toml";
 
 const DEFAULT_ARXIV_CATEGORY: &str = "quant-ph";
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+/// Table of the config file holding the named profiles, e.g. `[profile.ml]`.
+const PROFILE_TABLE: &str = "profile";
+
+/// Errors while loading the config.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file could not be read.
+    IoError(std::io::Error),
+    /// The config file is not valid TOML or does not match the expected schema.
+    ParseError(toml::de::Error),
+    /// The config file to save a value to is not valid TOML.
+    EditError(toml_edit::TomlError),
+    /// The config file given explicitly does not exist.
+    MissingFile(PathBuf),
+    /// The requested profile is not defined in the config file.
+    UnknownProfile(String),
+    /// The config file to initialize already exists.
+    AlreadyExists(PathBuf),
+    /// The base theme is not one of the built-in themes.
+    UnknownTheme(String),
+    /// A color of the config can not be parsed.
+    InvalidColor { field: String, value: String },
+    /// The theme file could not be read or parsed.
+    ThemeFile(PathBuf, String),
+    /// A command template of the `[tools]` section can not be run.
+    InvalidCommand { field: String, error: TemplateError },
+    /// A value of the config is out of its allowed values, with how to fix it.
+    InvalidValue { field: String, message: String },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::IoError(e) => write!(f, "could not read the config file: {e}"),
+            ConfigError::ParseError(e) => write!(f, "could not parse the config file: {e}"),
+            ConfigError::EditError(e) => write!(f, "could not parse the config file: {e}"),
+            ConfigError::MissingFile(path) => {
+                write!(f, "the config file {} does not exist", path.display())
+            }
+            ConfigError::UnknownProfile(name) => write!(
+                f,
+                "unknown profile `{name}`, define it in a [{PROFILE_TABLE}.{name}] table"
+            ),
+            ConfigError::AlreadyExists(path) => write!(
+                f,
+                "the config file {} already exists, use --force to overwrite it",
+                path.display()
+            ),
+            ConfigError::UnknownTheme(name) => {
+                write!(f, "unknown theme `{name}`, expected one of: default, light, mono")
+            }
+            ConfigError::InvalidColor { field, value } => write!(
+                f,
+                "invalid color `{value}` for {field}, expected a name like \"red\" or a hex code like \"#ff9e64\""
+            ),
+            ConfigError::ThemeFile(path, e) => {
+                write!(f, "could not load the theme file {}: {e}", path.display())
+            }
+            ConfigError::InvalidCommand { field, error } => {
+                write!(f, "invalid command for {field}: {error}")
+            }
+            ConfigError::InvalidValue { field, message } => {
+                write!(f, "invalid value for {field}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Problems of the config which do not prevent loading it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigWarning {
+    /// A key is not part of the config, e.g. a typo like `[hilight]`. It is ignored.
+    UnknownKey(String),
+    /// A category is not in the arXiv taxonomy, with the closest known one if any.
+    UnknownCategory {
+        category: String,
+        suggestion: Option<String>,
+    },
+}
+
+impl Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigWarning::UnknownKey(key) => write!(f, "unknown config key `{key}` is ignored"),
+            ConfigWarning::UnknownCategory {
+                category,
+                suggestion: Some(suggestion),
+            } => write!(
+                f,
+                "unknown arXiv category `{category}`, did you mean `{suggestion}`?"
+            ),
+            ConfigWarning::UnknownCategory {
+                category,
+                suggestion: None,
+            } => write!(f, "unknown arXiv category `{category}`"),
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::IoError(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::ParseError(e)
+    }
+}
+
+impl From<toml_edit::TomlError> for ConfigError {
+    fn from(e: toml_edit::TomlError) -> Self {
+        ConfigError::EditError(e)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Config {
     #[serde(default)]
     pub query: QueryConfig,
     #[serde(default)]
     pub highlight: HighlightConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub pinned: PinnedConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
+    #[serde(default)]
+    pub tools: ToolsConfig,
+    /// Highlights and pinned authors of some categories, by category code.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub category: BTreeMap<String, CategoryConfig>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct QueryConfig {
-    #[serde(default = "query_default_category")]
-    pub category: String,
+    /// Categories to look, either a single one or a list.
+    #[serde(
+        rename = "category",
+        alias = "categories",
+        default = "query_default_categories",
+        deserialize_with = "string_or_vec"
+    )]
+    pub categories: Vec<String>,
+    /// Number of articles fetched.
+    #[serde(default)]
+    pub max_results: Option<i32>,
+    /// Sorting of the articles: "relevance", "submitted" or "updated".
+    #[serde(default)]
+    pub sort_by: Option<SortBy>,
+    /// Order of the sorting: "asc" or "desc".
+    #[serde(default)]
+    pub sort_order: Option<SortOrder>,
+    /// Number of articles left below the selection when the next `max_results` articles
+    /// are fetched in the background, 0 to never fetch them.
+    #[serde(default)]
+    pub prefetch_margin: Option<usize>,
+    /// User agent of the requests to arXiv, e.g. with a contact address as its API asks
+    /// of heavy users. The name and version of arxivlens by default.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Seconds left between two requests to arXiv, e.g. when fetching the next articles,
+    /// 3 by default as arXiv asks.
+    #[serde(default)]
+    pub request_delay: Option<u64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct HighlightConfig {
     #[serde(default = "query_default_keywords")]
     pub keywords: Option<Vec<String>>,
@@ -27,10 +203,267 @@ pub struct HighlightConfig {
     pub authors: Option<Vec<String>>,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UiConfig {
+    /// Maximum width of the titles in the feed, in columns. Longer titles are
+    /// truncated with an ellipsis.
+    #[serde(default)]
+    pub max_title_len: Option<usize>,
+    /// Show the start of the abstract on a line below each title of the feed, off by
+    /// default.
+    #[serde(default)]
+    pub show_abstract_snippet: Option<bool>,
+    /// Maximum width of the lines of the abstract in the preview, in columns, for an
+    /// easier reading in a wide pane. The abstract fills the pane by default.
+    #[serde(default)]
+    pub summary_wrap_width: Option<usize>,
+    /// How the feed scrolls to follow the selection: "edge" (the default) or
+    /// "centered".
+    #[serde(default)]
+    pub scroll_mode: Option<ScrollMode>,
+    /// How the articles submitted to another category than the queried ones are shown:
+    /// "show" (the default), "dim" to dim them after the others, or "hide".
+    #[serde(default)]
+    pub cross_lists: Option<CrossListMode>,
+    /// Base theme: "default", "light" or "mono".
+    #[serde(default)]
+    pub theme_name: Option<String>,
+    /// File holding a whole theme, used instead of the base theme. A relative path is
+    /// relative to the directory of the config file.
+    #[serde(default)]
+    pub theme_file: Option<PathBuf>,
+    /// Mark the articles published since the previous run with a "NEW" badge, on by
+    /// default.
+    #[serde(default)]
+    pub new_badge: Option<bool>,
+    /// Mark every article as new the first time a query is run, off by default.
+    #[serde(default)]
+    pub new_on_first_run: Option<bool>,
+    /// Show only the articles published since the previous run on startup, off by
+    /// default.
+    #[serde(default)]
+    pub new_only: Option<bool>,
+    /// Show hints for the secondary actions of the context in the footer, on by default.
+    #[serde(default)]
+    pub footer_hints: Option<bool>,
+    /// Format of the dates of the articles: "relative" (the default), "short" or a
+    /// strftime format.
+    #[serde(default)]
+    pub date_format: Option<DateFormat>,
+    /// Select the first article when the feed is loaded or refreshed, on by default.
+    /// Otherwise no article is shown until one is selected.
+    #[serde(default)]
+    pub auto_select_first: Option<bool>,
+    /// Maximum height of the expanded pinned feed, borders included, 12 by default.
+    #[serde(default)]
+    pub pinned_max_height: Option<u16>,
+    /// Start with the pinned feeds expanded, on by default. Saved when they are all
+    /// expanded or collapsed at once.
+    #[serde(default)]
+    pub pinned_expanded: Option<bool>,
+    /// Smallest terminal the interface is drawn in, 30 columns by default. Below, a
+    /// message asks to resize the terminal.
+    #[serde(default)]
+    pub min_width: Option<u16>,
+    /// Smallest terminal the interface is drawn in, 10 rows by default.
+    #[serde(default)]
+    pub min_height: Option<u16>,
+    /// Milliseconds between two ticks of the animations, 100 by default.
+    #[serde(default)]
+    pub tick_rate: Option<u64>,
+    /// Colors overriding the ones of the theme.
+    #[serde(default)]
+    pub colors: ColorsConfig,
+}
+
+/// Colors overriding the ones of the theme, either named ANSI colors ("red",
+/// "lightblue", ...) or hex codes ("#ff9e64").
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ColorsConfig {
+    /// Text of the interface.
+    #[serde(default)]
+    pub foreground: Option<String>,
+    /// Background of the interface.
+    #[serde(default)]
+    pub background: Option<String>,
+    /// Titles of the panes.
+    #[serde(default)]
+    pub accent: Option<String>,
+    /// Keywords found in the titles and abstracts.
+    #[serde(default)]
+    pub highlight: Option<String>,
+    /// Highlighted authors.
+    #[serde(default)]
+    pub author_highlight: Option<String>,
+    /// Characters matched by the search.
+    #[serde(default)]
+    pub search_highlight: Option<String>,
+    /// Background of the selected article.
+    #[serde(default)]
+    pub selection: Option<String>,
+    /// Shortcuts of the footer.
+    #[serde(default)]
+    pub shortcut: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PinnedConfig {
+    /// Authors whose articles are shown in a dedicated feed.
+    #[serde(default)]
+    pub authors: Option<Vec<String>>,
+    /// Keywords whose articles, by title or abstract, are shown in another feed.
+    #[serde(default)]
+    pub keywords: Option<Vec<String>>,
+    /// Maximum number of articles in each pinned feed, the newest are kept.
+    #[serde(default)]
+    pub max_articles: Option<usize>,
+    /// Number of articles shown by a collapsed pinned feed, followed by how many more
+    /// there are. By default, the collapsed feed scrolls through all of them.
+    #[serde(default)]
+    pub max_collapsed: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ExportConfig {
+    /// Directory where the exported feeds are written. Defaults to the current directory.
+    #[serde(default)]
+    pub dir: Option<String>,
+    /// Style of the citations copied with `Y`: "apa" (the default) or "mla".
+    #[serde(default)]
+    pub citation_style: Option<CitationStyle>,
+    /// Show the abstracts in the Markdown and HTML digests, on by default.
+    #[serde(default)]
+    pub digest_abstracts: Option<bool>,
+    /// Maximum number of articles in the digests, all of them by default.
+    #[serde(default)]
+    pub digest_max_entries: Option<usize>,
+}
+
+impl ExportConfig {
+    /// What the Markdown and HTML digests hold.
+    pub fn digest_options(&self) -> DigestOptions {
+        DigestOptions {
+            abstracts: self.digest_abstracts.unwrap_or(true),
+            max_entries: self.digest_max_entries,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SearchConfig {
+    /// Match the search against the titles only, instead of the titles and abstracts.
+    #[serde(default)]
+    pub title_only: bool,
+    /// Match the search against the authors too, on by default.
+    pub authors: Option<bool>,
+}
+
+/// Highlights and pinned authors of a category, e.g. `[category."cs.LG".highlight]`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CategoryConfig {
+    /// Append the lists of the category to the global ones, instead of replacing them.
+    #[serde(default = "category_default_merge")]
+    pub merge: bool,
+    #[serde(default)]
+    pub highlight: CategoryHighlightConfig,
+    #[serde(default)]
+    pub pinned: PinnedConfig,
+}
+
+impl Default for CategoryConfig {
+    fn default() -> Self {
+        Self {
+            merge: category_default_merge(),
+            highlight: CategoryHighlightConfig::default(),
+            pinned: PinnedConfig::default(),
+        }
+    }
+}
+
+/// Highlights of a category, the global ones are used for the missing lists.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CategoryHighlightConfig {
+    #[serde(default)]
+    pub keywords: Option<Vec<String>>,
+    #[serde(default)]
+    pub authors: Option<Vec<String>>,
+}
+
+/// Combine the list of a category with the global one.
+fn resolve_list(global: &mut Option<Vec<String>>, category: &Option<Vec<String>>, merge: bool) {
+    let Some(values) = category else {
+        return;
+    };
+    match global {
+        Some(global) if merge => {
+            for value in values {
+                if !global.contains(value) {
+                    global.push(value.clone());
+                }
+            }
+        }
+        _ => *global = Some(values.clone()),
+    }
+}
+
+/// External tools, the commands are templates where `{url}` and `{path}` are replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ToolsConfig {
+    /// Directory where the articles are downloaded, `~` is the home directory.
+    #[serde(default = "tools_default_download_dir")]
+    pub download_dir: String,
+    /// Command opening a url in the browser.
+    #[serde(default = "tools_default_browser")]
+    pub browser: String,
+    /// Command opening a downloaded PDF.
+    #[serde(default = "tools_default_pdf_viewer")]
+    pub pdf_viewer: String,
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            download_dir: tools_default_download_dir(),
+            browser: tools_default_browser(),
+            pdf_viewer: tools_default_pdf_viewer(),
+        }
+    }
+}
+
+impl ToolsConfig {
+    /// The download directory, with `~` expanded.
+    pub fn download_dir(&self) -> PathBuf {
+        expand_tilde(&self.download_dir)
+    }
+
+    /// Check that the command templates can be run.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let commands = [
+            ("tools.browser", &self.browser, URL_PLACEHOLDER),
+            ("tools.pdf_viewer", &self.pdf_viewer, PATH_PLACEHOLDER),
+        ];
+        for (field, template, placeholder) in commands {
+            validate_template(template, placeholder).map_err(|error| {
+                ConfigError::InvalidCommand {
+                    field: field.to_string(),
+                    error,
+                }
+            })?;
+        }
+        Ok(())
+    }
+}
+
 impl Default for QueryConfig {
     fn default() -> Self {
         Self {
-            category: query_default_category(),
+            categories: query_default_categories(),
+            max_results: None,
+            sort_by: None,
+            sort_order: None,
+            prefetch_margin: None,
+            user_agent: None,
+            request_delay: None,
         }
     }
 }
@@ -44,9 +477,307 @@ impl Default for HighlightConfig {
     }
 }
 
-fn query_default_category() -> String {
-    DEFAULT_ARXIV_CATEGORY.to_string()
+/// Key of the config file template, as `(key, description, example)`.
+type TemplateKey = (&'static str, &'static str, &'static str);
+
+/// Documentation of the config file written by `--init-config`, as
+/// `(section, description, keys)`.
+///
+/// The keys set in the default config are written with their default value, the other
+/// ones are commented out with the example value.
+const TEMPLATE_SECTIONS: &[(&str, &str, &[TemplateKey])] = &[
+    (
+        "query",
+        "Query sent to arXiv.",
+        &[
+            (
+                "category",
+                "Categories to look, either a single one or a list (see --list-categories).",
+                r#"["quant-ph", "cond-mat.mes-hall"]"#,
+            ),
+            (
+                "max_results",
+                "Number of articles fetched, at most 2000.",
+                "200",
+            ),
+            (
+                "sort_by",
+                r#"Sorting of the articles: "relevance", "submitted" or "updated"."#,
+                r#""submitted""#,
+            ),
+            (
+                "sort_order",
+                r#"Order of the sorting: "asc" or "desc"."#,
+                r#""desc""#,
+            ),
+            (
+                "prefetch_margin",
+                "Articles left below the selection when the next max_results articles are fetched, 0 to never fetch them.",
+                "20",
+            ),
+            (
+                "user_agent",
+                "User agent of the requests to arXiv, e.g. to give a contact address.",
+                r#""arxivlens (mailto:me@example.org)""#,
+            ),
+            (
+                "request_delay",
+                "Seconds left between two requests to arXiv, 0 to not wait.",
+                "5",
+            ),
+        ],
+    ),
+    (
+        "highlight",
+        "Highlighting of the feed.",
+        &[
+            (
+                "keywords",
+                "Keywords highlighted in the titles and abstracts.",
+                r#"["quantum", "error correction"]"#,
+            ),
+            (
+                "authors",
+                "Authors highlighted in the feed.",
+                r#"["Schrodinger", "Becquerel"]"#,
+            ),
+        ],
+    ),
+    (
+        "pinned",
+        "Feed of the articles of some authors, shown above the main feed.",
+        &[
+            ("authors", "Pinned authors.", r#"["Curie"]"#),
+            (
+                "keywords",
+                "Pinned keywords, looked for in the titles and abstracts.",
+                r#"["error correction", "transmon"]"#,
+            ),
+            (
+                "max_articles",
+                "Maximum number of articles in each pinned feed, the newest are kept.",
+                "5",
+            ),
+            (
+                "max_collapsed",
+                "Articles shown by a collapsed pinned feed, followed by how many more there are.",
+                "2",
+            ),
+        ],
+    ),
+    (
+        "ui",
+        "Layout of the interface.",
+        &[
+            (
+                "max_title_len",
+                "Maximum width of the titles in the feed, in columns.",
+                "80",
+            ),
+            (
+                "show_abstract_snippet",
+                "Show the start of the abstract on a line below each title of the feed.",
+                "true",
+            ),
+            (
+                "summary_wrap_width",
+                "Maximum width of the lines of the abstract in the preview, in columns.",
+                "72",
+            ),
+            (
+                "scroll_mode",
+                r#"How the feed follows the selection: "edge" or "centered" to keep it in the middle."#,
+                r#""centered""#,
+            ),
+            (
+                "cross_lists",
+                r#"Articles of other categories: "show", "dim" to dim them after the others, or "hide"."#,
+                r#""dim""#,
+            ),
+            (
+                "theme_name",
+                r#"Base theme: "default", "light" or "mono" (no colors, also forced by NO_COLOR)."#,
+                r#""light""#,
+            ),
+            (
+                "theme_file",
+                "File holding a whole theme, relative to this file, used instead of the base theme.",
+                r#""mytheme.toml""#,
+            ),
+            (
+                "new_badge",
+                r#"Mark the articles published since the previous run with a "NEW" badge."#,
+                "false",
+            ),
+            (
+                "new_on_first_run",
+                "Mark every article as new the first time a query is run.",
+                "true",
+            ),
+            (
+                "new_only",
+                "Show only the articles published since the previous run on startup.",
+                "true",
+            ),
+            (
+                "footer_hints",
+                "Show hints for the secondary actions in the footer.",
+                "false",
+            ),
+            (
+                "date_format",
+                r#"Dates of the articles: "relative" ("3 days ago"), "short" ("Mon Jul 8") or a strftime format."#,
+                r#""%Y-%m-%d""#,
+            ),
+            (
+                "auto_select_first",
+                "Select the first article when the feed is loaded or refreshed.",
+                "false",
+            ),
+            (
+                "pinned_max_height",
+                "Maximum height of the expanded pinned feed, borders included.",
+                "12",
+            ),
+            (
+                "pinned_expanded",
+                "Start with the pinned feeds expanded, saved by the Z key.",
+                "false",
+            ),
+            (
+                "min_width",
+                "Columns of the smallest terminal the interface is drawn in.",
+                "30",
+            ),
+            (
+                "min_height",
+                "Rows of the smallest terminal the interface is drawn in.",
+                "10",
+            ),
+            (
+                "tick_rate",
+                "Milliseconds between two ticks of the animations, like the loading spinner.",
+                "250",
+            ),
+        ],
+    ),
+    (
+        "ui.colors",
+        r##"Colors overriding the theme: names like "red" or hex codes like "#ff9e64"."##,
+        &[
+            ("foreground", "Text of the interface.", r#""white""#),
+            ("background", "Background of the interface.", r#""black""#),
+            ("accent", "Titles of the panes.", r##""#ff9e64""##),
+            ("highlight", "Keywords found in the titles and abstracts.", r#""yellow""#),
+            ("author_highlight", "Highlighted authors.", r#""magenta""#),
+            ("search_highlight", "Characters matched by the search.", r#""lightyellow""#),
+            ("selection", "Background of the selected article.", r#""gray""#),
+            ("shortcut", "Shortcuts of the footer.", r#""blue""#),
+        ],
+    ),
+    (
+        "export",
+        "Export of the feed.",
+        &[
+            (
+                "dir",
+                "Directory of the exported files, the current directory by default.",
+                r#""/home/me/papers""#,
+            ),
+            (
+                "citation_style",
+                r#"Style of the citations copied with Y: "apa" or "mla", cycled with S."#,
+                r#""mla""#,
+            ),
+            (
+                "digest_abstracts",
+                "Show the abstracts in the Markdown and HTML digests.",
+                "false",
+            ),
+            (
+                "digest_max_entries",
+                "Maximum number of articles in the digests.",
+                "20",
+            ),
+        ],
+    ),
+    (
+        "search",
+        "Fuzzy search through the feed.",
+        &[
+            (
+                "title_only",
+                "Match the titles only instead of the titles and abstracts, toggled with Ctrl-t.",
+                "true",
+            ),
+            (
+                "authors",
+                "Match the authors too, besides the titles and abstracts.",
+                "false",
+            ),
+        ],
+    ),
+    (
+        "tools",
+        "External tools, {url} and {path} are replaced in the commands.",
+        &[
+            (
+                "download_dir",
+                "Directory where the articles are downloaded.",
+                r#""~/papers""#,
+            ),
+            (
+                "browser",
+                "Command opening a url in the browser.",
+                r#""firefox --new-tab {url}""#,
+            ),
+            (
+                "pdf_viewer",
+                "Command opening a downloaded article.",
+                r#""evince {path}""#,
+            ),
+        ],
+    ),
+];
+
+fn category_default_merge() -> bool {
+    true
 }
+
+fn tools_default_download_dir() -> String {
+    "~/Downloads/arxiv".to_string()
+}
+
+fn tools_default_browser() -> String {
+    format!("{DEFAULT_BROWSER_COMMAND} {URL_PLACEHOLDER}")
+}
+
+fn tools_default_pdf_viewer() -> String {
+    format!("zathura {PATH_PLACEHOLDER}")
+}
+
+fn query_default_categories() -> Vec<String> {
+    vec![DEFAULT_ARXIV_CATEGORY.to_string()]
+}
+
+/// Accept either a single string or a list of strings.
+fn string_or_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrVec {
+        String(String),
+        Vec(Vec<String>),
+    }
+
+    Ok(match StringOrVec::deserialize(deserializer)? {
+        StringOrVec::String(value) => vec![value],
+        StringOrVec::Vec(values) => values,
+    })
+}
+
 fn query_default_keywords() -> Option<Vec<String>> {
     None
 }
@@ -54,17 +785,399 @@ fn query_default_authors() -> Option<Vec<String>> {
     None
 }
 
+impl QueryConfig {
+    /// Check the values which can not be enforced by the parsing.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |field: &str, message: String| ConfigError::InvalidValue {
+            field: field.to_string(),
+            message,
+        };
+        if self.categories.is_empty() {
+            return Err(invalid(
+                "query.category",
+                format!("no category to query, set one like \"{DEFAULT_ARXIV_CATEGORY}\" (see --list-categories)"),
+            ));
+        }
+        if self
+            .categories
+            .iter()
+            .any(|category| category.trim().is_empty())
+        {
+            return Err(invalid(
+                "query.category",
+                "a category is empty, remove it or write its code like \"cs.LG\"".to_string(),
+            ));
+        }
+        if let Some(max_results) = self.max_results {
+            if !(1..=ARXIV_MAX_RESULTS).contains(&max_results) {
+                return Err(invalid(
+                    "query.max_results",
+                    format!("must be between 1 and {ARXIV_MAX_RESULTS}, got {max_results}"),
+                ));
+            }
+        }
+        if self
+            .user_agent
+            .as_ref()
+            .is_some_and(|user_agent| user_agent.trim().is_empty())
+        {
+            return Err(invalid(
+                "query.user_agent",
+                "must not be empty, remove it to use the default one".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Time left between two requests to arXiv.
+    pub fn request_delay(&self) -> Duration {
+        self.request_delay
+            .map_or(DEFAULT_REQUEST_DELAY, Duration::from_secs)
+    }
+
+    /// User agent of the requests to arXiv.
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT)
+    }
+
+    /// Warnings about the categories missing from the arXiv taxonomy, which are likely
+    /// typos. They are not fatal since the table may lag behind arXiv.
+    pub fn category_warnings(&self) -> Vec<ConfigWarning> {
+        self.categories
+            .iter()
+            .filter(|category| category_name(category).is_none())
+            .map(|category| ConfigWarning::UnknownCategory {
+                category: category.clone(),
+                suggestion: suggest_category(category).map(str::to_string),
+            })
+            .collect()
+    }
+}
+
+/// Values accepted for `query.sort_by` and `query.sort_order`.
+const SORT_BY_VALUES: &[&str] = &["relevance", "submitted", "updated"];
+const SORT_ORDER_VALUES: &[&str] = &["asc", "desc"];
+
+/// Check the sort values of the `[query]` table before deserializing it, so that a typo
+/// names the field and suggests the closest value instead of a bare parsing error.
+fn validate_sort_values(table: &toml::Table) -> Result<(), ConfigError> {
+    let Some(query) = section_table(table, "query") else {
+        return Ok(());
+    };
+    let fields = [
+        ("sort_by", SORT_BY_VALUES),
+        ("sort_order", SORT_ORDER_VALUES),
+    ];
+    for (key, values) in fields {
+        let Some(value) = query.get(key).and_then(toml::Value::as_str) else {
+            continue;
+        };
+        let is_valid = match key {
+            "sort_by" => value.parse::<SortBy>().is_ok(),
+            _ => value.parse::<SortOrder>().is_ok(),
+        };
+        if is_valid {
+            continue;
+        }
+        let mut message = format!(
+            "unknown value `{value}`, expected one of: {}",
+            values.join(", ")
+        );
+        if let Some(suggestion) = SearchEngine::new().closest(value, values) {
+            message.push_str(&format!("; did you mean `{suggestion}`?"));
+        }
+        return Err(ConfigError::InvalidValue {
+            field: format!("query.{key}"),
+            message,
+        });
+    }
+    Ok(())
+}
+
+/// Config file as written, empty if it does not exist yet.
+fn read_document(path: &Path) -> Result<toml_edit::DocumentMut, ConfigError> {
+    let content = if path.exists() {
+        std::fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    Ok(content.parse()?)
+}
+
+/// Write the config file, creating its directory.
+fn write_document(path: &Path, document: &toml_edit::DocumentMut) -> Result<(), ConfigError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, document.to_string())?;
+    Ok(())
+}
+
+/// Set the values in the table at `tables` of the document, each nested table in its own
+/// table.
+fn set_document_values<'a>(
+    document: &mut toml_edit::DocumentMut,
+    tables: &mut Vec<&'a str>,
+    values: &'a toml::Table,
+) {
+    for (key, value) in values {
+        match value {
+            toml::Value::Table(values) => {
+                tables.push(key);
+                set_document_values(document, tables, values);
+                tables.pop();
+            }
+            value => set_document_value(document, tables, key, value.clone()),
+        }
+    }
+}
+
+/// Set `key` in the table at `tables` of the document, creating the tables if needed. A
+/// value replacing another keeps its comment.
+fn set_document_value(
+    document: &mut toml_edit::DocumentMut,
+    tables: &[&str],
+    key: &str,
+    value: toml::Value,
+) {
+    let mut value: toml_edit::Value = value
+        .to_string()
+        .parse()
+        .expect("a toml value is written as valid toml");
+    let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+    for &name in tables {
+        let item = table.entry(name).or_insert_with(|| {
+            // Only the innermost table gets a header, e.g. `[profile.ml.query]`.
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            toml_edit::Item::Table(table)
+        });
+        if !item.is_table_like() {
+            *item = toml_edit::table();
+        }
+        table = item.as_table_like_mut().expect("the item is a table");
+    }
+    match table.get_mut(key) {
+        Some(toml_edit::Item::Value(previous)) => {
+            // The comment after the previous value stays.
+            *value.decor_mut() = previous.decor().clone();
+            *previous = value;
+        }
+        _ => {
+            table.insert(key, toml_edit::Item::Value(value));
+        }
+    }
+}
+
+/// Table of a possibly nested section, e.g. `ui.colors`.
+fn section_table<'t>(table: &'t toml::Table, section: &str) -> Option<&'t toml::Table> {
+    section
+        .split('.')
+        .try_fold(table, |table, key| table.get(key)?.as_table())
+}
+
+/// Merge the `overlay` table into `base`: the values of the overlay win, and the nested
+/// tables are merged recursively so the base fills the gaps.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 impl Config {
-    pub fn load() -> Config {
-        let path = xdg::BaseDirectories::with_prefix(APP_DIR_NAME)
-            .unwrap()
-            .get_config_file(CONFIG_FILE_NAME);
-        if path.exists() {
-            let content = std::fs::read_to_string(path).unwrap();
-            toml::from_str(&content).unwrap()
-        } else {
-            Config::default()
+    /// Config with the highlights and pinned authors of the queried categories applied.
+    ///
+    /// The lists of a category are appended to the global ones, or replace them if the
+    /// category sets `merge = false`. The maximum number of pinned articles of a category
+    /// wins over the global one.
+    pub fn for_query_categories(&self) -> Config {
+        let mut config = self.clone();
+        for category in self
+            .query
+            .categories
+            .iter()
+            .filter_map(|code| self.category.get(code))
+        {
+            let merge = category.merge;
+            let highlight = &mut config.highlight;
+            resolve_list(&mut highlight.keywords, &category.highlight.keywords, merge);
+            resolve_list(&mut highlight.authors, &category.highlight.authors, merge);
+            resolve_list(&mut config.pinned.authors, &category.pinned.authors, merge);
+            resolve_list(
+                &mut config.pinned.keywords,
+                &category.pinned.keywords,
+                merge,
+            );
+            if category.pinned.max_articles.is_some() {
+                config.pinned.max_articles = category.pinned.max_articles;
+            }
+        }
+        config
+    }
+
+    /// Path of the config file in the XDG directory.
+    pub fn xdg_path() -> Result<PathBuf, ConfigError> {
+        Ok(xdg::BaseDirectories::with_prefix(APP_DIR_NAME)
+            .map_err(|e| ConfigError::IoError(e.into()))?
+            .get_config_file(CONFIG_FILE_NAME))
+    }
+
+    /// Commented config file holding the default values.
+    ///
+    /// The values come from serializing the default config, so the template follows the
+    /// code.
+    pub fn template() -> String {
+        let defaults =
+            toml::Table::try_from(Config::default()).expect("the default config is serializable");
+        let mut template = String::new();
+        for (section, description, keys) in TEMPLATE_SECTIONS {
+            let values = section_table(&defaults, section);
+            template.push_str(&format!("# {description}\n[{section}]\n"));
+            for (key, description, example) in *keys {
+                template.push_str(&format!("# {description}\n"));
+                match values.and_then(|values| values.get(*key)) {
+                    Some(value) => template.push_str(&format!("{key} = {value}\n")),
+                    None => template.push_str(&format!("# {key} = {example}\n")),
+                }
+            }
+            template.push('\n');
         }
+        template
+    }
+
+    /// Path of the config file: the one given explicitly, or the one of the XDG directory.
+    pub fn path(path: Option<&Path>) -> Result<PathBuf, ConfigError> {
+        path.map_or_else(Self::xdg_path, |path| Ok(path.to_path_buf()))
+    }
+
+    /// Write the config to the config file of the XDG directory, see [`Self::save_to`].
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&Self::xdg_path()?)
+    }
+
+    /// Write the config to `path`, creating the file and its directory if needed.
+    ///
+    /// The values are set one by one in the file, as with [`Self::set_file_value`], so
+    /// the comments, the profiles and the keys the config leaves unset are kept.
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        let values = toml::Table::try_from(self).expect("the config is serializable");
+        let mut document = read_document(path)?;
+        set_document_values(&mut document, &mut Vec::new(), &values);
+        write_document(path, &document)
+    }
+
+    /// Set the value of `section.key` in the config file, creating the file if needed.
+    ///
+    /// With a profile, the value goes to its `[profile.<name>.<section>]` table, so that
+    /// the base config and the other profiles are left as they are. The rest of the file
+    /// is kept as written, comments included.
+    pub fn set_file_value(
+        path: &Path,
+        profile: Option<&str>,
+        section: &str,
+        key: &str,
+        value: toml::Value,
+    ) -> Result<(), ConfigError> {
+        let mut document = read_document(path)?;
+        let tables = match profile {
+            Some(name) => vec![PROFILE_TABLE, name, section],
+            None => vec![section],
+        };
+        set_document_value(&mut document, &tables, key, value);
+        write_document(path, &document)
+    }
+
+    /// Write the commented default config to `path`, creating its directory.
+    ///
+    /// An existing file is only overwritten with `force`.
+    pub fn init_file(path: &Path, force: bool) -> Result<(), ConfigError> {
+        if path.exists() && !force {
+            return Err(ConfigError::AlreadyExists(path.to_path_buf()));
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, Self::template())?;
+        Ok(())
+    }
+
+    /// Load the config from the given path, or from the XDG directory if none is given.
+    ///
+    /// Unlike the XDG file, a path given explicitly must exist.
+    pub fn load_path(
+        path: Option<&Path>,
+        profile: Option<&str>,
+    ) -> Result<(Config, Vec<ConfigWarning>), ConfigError> {
+        match path {
+            Some(path) if !path.exists() => Err(ConfigError::MissingFile(path.to_path_buf())),
+            Some(path) => Self::load_from_file(path, profile),
+            None => Self::load_profile(profile),
+        }
+    }
+
+    /// Load the config from the XDG directory, with the given profile applied.
+    ///
+    /// A missing config file gives the default config, unless a profile is requested.
+    pub fn load_profile(
+        profile: Option<&str>,
+    ) -> Result<(Config, Vec<ConfigWarning>), ConfigError> {
+        let path = Self::xdg_path()?;
+        match (path.exists(), profile) {
+            (true, _) => Self::load_from_file(&path, profile),
+            (false, Some(name)) => Err(ConfigError::UnknownProfile(name.to_string())),
+            (false, None) => Ok((Config::default(), Vec::new())),
+        }
+    }
+
+    /// Load the config from a file, with the given profile applied.
+    pub fn load_from_file(
+        path: &Path,
+        profile: Option<&str>,
+    ) -> Result<(Config, Vec<ConfigWarning>), ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        let (mut config, warnings) = Self::from_toml_str(&content, profile)?;
+        if let (Some(theme_file), Some(dir)) = (&config.ui.theme_file, path.parent()) {
+            config.ui.theme_file = Some(dir.join(theme_file));
+        }
+        Ok((config, warnings))
+    }
+
+    /// Parse the config, with the given profile applied.
+    ///
+    /// The values of the `[profile.<name>]` table override the ones of the base config.
+    /// The unknown keys and the suspicious values are returned as warnings.
+    pub fn from_toml_str(
+        content: &str,
+        profile: Option<&str>,
+    ) -> Result<(Config, Vec<ConfigWarning>), ConfigError> {
+        let mut table: toml::Table = toml::from_str(content)?;
+        let profiles = table.remove(PROFILE_TABLE);
+
+        if let Some(name) = profile {
+            let overlay = profiles
+                .as_ref()
+                .and_then(|profiles| profiles.get(name))
+                .and_then(|overlay| overlay.as_table())
+                .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?;
+            merge_tables(&mut table, overlay.clone());
+        }
+
+        validate_sort_values(&table)?;
+        let mut warnings = Vec::new();
+        let config: Config = serde_ignored::deserialize(toml::Value::Table(table), |path| {
+            warnings.push(ConfigWarning::UnknownKey(path.to_string()))
+        })?;
+        config.tools.validate()?;
+        config.query.validate()?;
+        warnings.extend(config.query.category_warnings());
+        Ok((config, warnings))
     }
 }
 
@@ -72,17 +1185,122 @@ impl Config {
 mod tests {
     use super::*;
 
+    const CATEGORY_TOML: &str = r#"
+        [query]
+        category = "cs.LG"
+
+        [highlight]
+        keywords = ["transformer"]
+
+        [pinned]
+        authors = ["Curie"]
+
+        [category."cs.LG".highlight]
+        keywords = ["diffusion", "transformer"]
+
+        [category."cs.LG".pinned]
+        authors = ["Hinton"]
+        max_articles = 3
+
+        [category."quant-ph".highlight]
+        keywords = ["qubit"]
+    "#;
+
+    #[test]
+    fn test_category_config_merged() {
+        let (config, warnings) = Config::from_toml_str(CATEGORY_TOML, None).unwrap();
+        assert!(warnings.is_empty());
+        let effective = config.for_query_categories();
+
+        let strings = |values: &[&str]| Some(values.iter().map(|v| v.to_string()).collect());
+        assert_eq!(
+            effective.highlight.keywords,
+            strings(&["transformer", "diffusion"])
+        );
+        assert_eq!(effective.pinned.authors, strings(&["Curie", "Hinton"]));
+        assert_eq!(effective.pinned.max_articles, Some(3));
+    }
+
+    #[test]
+    fn test_category_config_replaced() {
+        let toml_str = CATEGORY_TOML.replace(
+            r#"[category."cs.LG".pinned]"#,
+            "[category.\"cs.LG\"]\nmerge = false\n\n[category.\"cs.LG\".pinned]",
+        );
+        let (config, _) = Config::from_toml_str(&toml_str, None).unwrap();
+        let effective = config.for_query_categories();
+
+        assert_eq!(
+            effective.highlight.keywords,
+            Some(vec!["diffusion".to_string(), "transformer".to_string()])
+        );
+        assert_eq!(effective.pinned.authors, Some(vec!["Hinton".to_string()]));
+    }
+
+    #[test]
+    fn test_category_config_fallback() {
+        let toml_str = CATEGORY_TOML.replace(r#"category = "cs.LG""#, r#"category = "hep-th""#);
+        let (config, _) = Config::from_toml_str(&toml_str, None).unwrap();
+        let effective = config.for_query_categories();
+
+        assert_eq!(effective.highlight, config.highlight);
+        assert_eq!(effective.pinned, config.pinned);
+    }
+
+    #[test]
+    fn test_invalid_tool_command() {
+        let toml_str = r#"
+            [tools]
+            browser = "firefox"
+        "#;
+        let error = Config::from_toml_str(toml_str, None).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::InvalidCommand {
+                ref field,
+                error: TemplateError::MissingPlaceholder(_)
+            } if field == "tools.browser"
+        ));
+
+        let toml_str = r#"
+            [tools]
+            pdf_viewer = ""
+        "#;
+        let error = Config::from_toml_str(toml_str, None).unwrap_err();
+        assert!(error.to_string().contains("tools.pdf_viewer"));
+    }
+
     #[test]
     fn test_config_default() {
         let actual = Config::default();
         let expected = Config {
             query: QueryConfig {
-                category: "quant-ph".into(),
+                categories: vec!["quant-ph".into()],
+                ..Default::default()
             },
             highlight: HighlightConfig {
                 keywords: None,
                 authors: None,
             },
+            ui: UiConfig {
+                max_title_len: None,
+                ..Default::default()
+            },
+            export: ExportConfig {
+                dir: None,
+                citation_style: None,
+                digest_abstracts: None,
+                digest_max_entries: None,
+            },
+            pinned: PinnedConfig {
+                authors: None,
+                keywords: None,
+                max_articles: None,
+                max_collapsed: None,
+            },
+            search: SearchConfig::default(),
+            tools: ToolsConfig::default(),
+            category: BTreeMap::new(),
         };
 
         assert_eq!(actual, expected);
@@ -96,16 +1314,46 @@ mod tests {
             [highlight]
             keywords = ["apple", "berry"]
             authors = ["Schrodinger", "Becquerel"]
+            [ui]
+            max_title_len = 80
+            [export]
+            dir = "/tmp/arxiv"
+            citation_style = "mla"
+            digest_max_entries = 10
+            [pinned]
+            authors = ["Curie"]
+            keywords = ["transmon"]
+            max_articles = 5
         "#;
         let actual: Config = toml::from_str(toml).unwrap();
         let expected = Config {
             query: QueryConfig {
-                category: "quant-ph".into(),
+                categories: vec!["quant-ph".into()],
+                ..Default::default()
             },
             highlight: HighlightConfig {
                 keywords: Some(vec!["apple".to_string(), "berry".to_string()]),
                 authors: Some(vec!["Schrodinger".to_string(), "Becquerel".to_string()]),
             },
+            ui: UiConfig {
+                max_title_len: Some(80),
+                ..Default::default()
+            },
+            export: ExportConfig {
+                dir: Some("/tmp/arxiv".to_string()),
+                citation_style: Some(CitationStyle::Mla),
+                digest_abstracts: None,
+                digest_max_entries: Some(10),
+            },
+            pinned: PinnedConfig {
+                authors: Some(vec!["Curie".to_string()]),
+                keywords: Some(vec!["transmon".to_string()]),
+                max_articles: Some(5),
+                max_collapsed: None,
+            },
+            search: SearchConfig::default(),
+            tools: ToolsConfig::default(),
+            category: BTreeMap::new(),
         };
         assert_eq!(actual, expected);
     }
@@ -119,13 +1367,520 @@ mod tests {
         let actual: Config = toml::from_str(toml).unwrap();
         let expected = Config {
             query: QueryConfig {
-                category: "quant-ph".into(),
+                categories: vec!["quant-ph".into()],
+                ..Default::default()
             },
             highlight: HighlightConfig {
                 keywords: None,
                 authors: Some(vec!["Schrodinger".to_string(), "Becquerel".to_string()]),
             },
+            ui: UiConfig::default(),
+            export: ExportConfig::default(),
+            pinned: PinnedConfig::default(),
+            search: SearchConfig::default(),
+            tools: ToolsConfig::default(),
+            category: BTreeMap::new(),
         };
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_config_category_list() {
+        let toml = r#"
+            [query]
+            category = ["quant-ph", "cond-mat.mes-hall"]
+        "#;
+        let actual: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            actual.query.categories,
+            vec!["quant-ph".to_string(), "cond-mat.mes-hall".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_config_sorting() {
+        let toml = r#"
+            [query]
+            max_results = 50
+            sort_by = "updated"
+            sort_order = "asc"
+            prefetch_margin = 10
+        "#;
+        let actual: Config = toml::from_str(toml).unwrap();
+        assert_eq!(actual.query.max_results, Some(50));
+        assert_eq!(actual.query.prefetch_margin, Some(10));
+        assert_eq!(actual.query.sort_by, Some(SortBy::LastUpdatedDate));
+        assert_eq!(actual.query.sort_order, Some(SortOrder::Ascending));
+        assert!(actual.query.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_invalid_sort() {
+        let toml = r#"
+            [query]
+            sort_by = "submited"
+        "#;
+        let error = toml::from_str::<Config>(toml).unwrap_err();
+        assert!(error.to_string().contains("unknown sort `submited`"));
+    }
+
+    #[test]
+    fn test_config_validation_sort() {
+        let toml = r#"
+            [query]
+            sort_by = "submited"
+        "#;
+        let error = Config::from_toml_str(toml, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid value for query.sort_by: unknown value `submited`, expected one of: \
+             relevance, submitted, updated; did you mean `submitted`?"
+        );
+
+        // In a profile too.
+        let toml = r#"
+            [profile.old.query]
+            sort_order = "ascending-ish"
+        "#;
+        let error = Config::from_toml_str(toml, Some("old")).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("invalid value for query.sort_order: unknown value `ascending-ish`"));
+
+        // Other type errors still come from the parsing.
+        let toml = r#"
+            [query]
+            sort_by = 3
+        "#;
+        let error = Config::from_toml_str(toml, None).unwrap_err();
+        assert!(matches!(error, ConfigError::ParseError(_)));
+    }
+
+    #[test]
+    fn test_config_validation_empty_category() {
+        let toml = r#"
+            [query]
+            category = []
+        "#;
+        let error = Config::from_toml_str(toml, None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid value for query.category: no category to query, set one like \
+             \"quant-ph\" (see --list-categories)"
+        );
+
+        let toml = r#"
+            [query]
+            category = ["quant-ph", " "]
+        "#;
+        let error = Config::from_toml_str(toml, None).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::InvalidValue { field, .. } if field == "query.category"
+        ));
+    }
+
+    #[test]
+    fn test_config_date_format() {
+        let toml = r#"
+            [ui]
+            date_format = "%d/%m/%Y"
+        "#;
+        let actual: Config = toml::from_str(toml).unwrap();
+        assert_eq!(
+            actual.ui.date_format,
+            Some(DateFormat::Strftime("%d/%m/%Y".to_string()))
+        );
+
+        let toml = r#"
+            [ui]
+            date_format = "%d/%Q"
+        "#;
+        let error = toml::from_str::<Config>(toml).unwrap_err();
+        assert!(error.to_string().contains("invalid date format `%d/%Q`"));
+    }
+
+    #[test]
+    fn test_config_max_results_bounds() {
+        let mut query = QueryConfig {
+            max_results: Some(5000),
+            ..Default::default()
+        };
+        assert!(query.validate().unwrap_err().to_string().contains("2000"));
+        query.max_results = Some(0);
+        assert!(query.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_user_agent() {
+        let mut query = QueryConfig::default();
+        assert_eq!(query.user_agent(), DEFAULT_USER_AGENT);
+        query.user_agent = Some("lab-digest/1.0 (mailto:me@example.org)".to_string());
+        assert!(query.validate().is_ok());
+        assert_eq!(query.user_agent(), "lab-digest/1.0 (mailto:me@example.org)");
+        query.user_agent = Some("  ".to_string());
+        assert_eq!(
+            query.validate().unwrap_err().to_string(),
+            "invalid value for query.user_agent: must not be empty, remove it to use the default one"
+        );
+    }
+
+    #[test]
+    fn test_config_request_delay() {
+        let mut query = QueryConfig::default();
+        assert_eq!(query.request_delay(), Duration::from_secs(3));
+        query.request_delay = Some(10);
+        assert_eq!(query.request_delay(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_query_config_category_warnings() {
+        let query = QueryConfig {
+            categories: vec!["quant-ph".into(), "quant-phh".into(), "zzzz".into()],
+            ..Default::default()
+        };
+        let warnings = query.category_warnings();
+        assert_eq!(
+            warnings,
+            vec![
+                ConfigWarning::UnknownCategory {
+                    category: "quant-phh".into(),
+                    suggestion: Some("quant-ph".into()),
+                },
+                ConfigWarning::UnknownCategory {
+                    category: "zzzz".into(),
+                    suggestion: None,
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "unknown arXiv category `quant-phh`, did you mean `quant-ph`?"
+        );
+        assert!(QueryConfig::default().category_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_config_unknown_table() {
+        let toml = r#"
+            [hilight]
+            keywords = ["qubit"]
+        "#;
+        let (config, warnings) = Config::from_toml_str(toml, None).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(warnings, vec![ConfigWarning::UnknownKey("hilight".into())]);
+    }
+
+    #[test]
+    fn test_config_unknown_field() {
+        let toml = r#"
+            [highlight]
+            keyworsd = ["qubit"]
+            authors = ["Schrodinger"]
+        "#;
+        let (config, warnings) = Config::from_toml_str(toml, None).unwrap();
+        assert_eq!(config.highlight.authors, Some(vec!["Schrodinger".into()]));
+        assert_eq!(
+            warnings,
+            vec![ConfigWarning::UnknownKey("highlight.keyworsd".into())]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "unknown config key `highlight.keyworsd` is ignored"
+        );
+    }
+
+    #[test]
+    fn test_config_category_warning_on_load() {
+        let toml = r#"
+            [query]
+            category = ["cs.LG", "quant-phh"]
+        "#;
+        let (_, warnings) = Config::from_toml_str(toml, None).unwrap();
+        assert_eq!(
+            warnings,
+            vec![ConfigWarning::UnknownCategory {
+                category: "quant-phh".into(),
+                suggestion: Some("quant-ph".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_config_known_keys_no_warning() {
+        let (_, warnings) = Config::from_toml_str(&Config::template(), None).unwrap();
+        assert!(warnings.is_empty());
+        let (_, warnings) = Config::from_toml_str(PROFILES_TOML, Some("ml")).unwrap();
+        assert!(warnings.is_empty());
+        let (_, warnings) =
+            Config::from_toml_str("[query]\ncategories = [\"cs.LG\"]\n", None).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_config_serialized_round_trip() {
+        let toml = r#"
+            [query]
+            category = ["quant-ph", "cs.LG"]
+            max_results = 50
+            sort_by = "updated"
+            sort_order = "asc"
+
+            [pinned]
+            authors = ["Curie"]
+            max_collapsed = 2
+
+            [ui]
+            date_format = "%d/%m/%Y"
+            scroll_mode = "centered"
+            cross_lists = "hide"
+
+            [export]
+            citation_style = "mla"
+        "#;
+        let (config, _) = Config::from_toml_str(toml, None).unwrap();
+        let (mut with_categories, _) = Config::from_toml_str(CATEGORY_TOML, None).unwrap();
+        with_categories.ui = config.ui.clone();
+
+        // Whatever is written back, e.g. by the edits from the interface, loads the same.
+        for config in [config, with_categories] {
+            let content = toml::to_string(&config).unwrap();
+            let (loaded, warnings) = Config::from_toml_str(&content, None).unwrap();
+            assert!(warnings.is_empty(), "{warnings:?}");
+            assert_eq!(loaded, config);
+        }
+    }
+
+    #[test]
+    fn test_config_save_round_trip() {
+        let dir = std::env::temp_dir().join(format!("arxivlens-save-{}", std::process::id()));
+        let path = dir.join("arxivlens").join(CONFIG_FILE_NAME);
+        let (mut config, _) = Config::from_toml_str(CATEGORY_TOML, None).unwrap();
+        config.query.categories = vec!["quant-ph".to_string(), "cs.LG".to_string()];
+        config.query.max_results = Some(50);
+
+        // The directory is created.
+        config.save_to(&path).unwrap();
+        let (loaded, warnings) = Config::load_from_file(&path, None).unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(loaded, config);
+
+        // The comments and the profiles of the file are kept.
+        std::fs::write(
+            &path,
+            "# My arxivlens config\n[query]\ncategory = \"cs.LG\" # ML\n\n[profile.ml.query]\ncategory = \"cs.LG\"\n",
+        )
+        .unwrap();
+        config.save_to(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let (loaded, _) = Config::load_from_file(&path, None).unwrap();
+        let (profile, _) = Config::load_from_file(&path, Some("ml")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(
+            content.starts_with(
+                "# My arxivlens config\n[query]\ncategory = [\"quant-ph\", \"cs.LG\"] # ML\n"
+            ),
+            "{content}"
+        );
+        assert_eq!(loaded, config);
+        assert_eq!(profile.query.categories, vec!["cs.LG".to_string()]);
+    }
+
+    const PROFILES_TOML: &str = r#"
+        [query]
+        category = "quant-ph"
+        max_results = 100
+        [highlight]
+        keywords = ["qubit"]
+        authors = ["Schrodinger"]
+
+        [profile.ml.query]
+        category = "cs.LG"
+        [profile.ml.highlight]
+        authors = ["Hinton"]
+    "#;
+
+    #[test]
+    fn test_config_without_profile() {
+        let actual = Config::from_toml_str(PROFILES_TOML, None).unwrap().0;
+        assert_eq!(actual.query.categories, vec!["quant-ph".to_string()]);
+        assert_eq!(
+            actual.highlight.authors,
+            Some(vec!["Schrodinger".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_config_profile_override() {
+        let actual = Config::from_toml_str(PROFILES_TOML, Some("ml")).unwrap().0;
+        // The profile values win...
+        assert_eq!(actual.query.categories, vec!["cs.LG".to_string()]);
+        assert_eq!(actual.highlight.authors, Some(vec!["Hinton".to_string()]));
+        // ...and the base config fills the gaps.
+        assert_eq!(actual.query.max_results, Some(100));
+        assert_eq!(actual.highlight.keywords, Some(vec!["qubit".to_string()]));
+    }
+
+    #[test]
+    fn test_config_unknown_profile() {
+        let error = Config::from_toml_str(PROFILES_TOML, Some("research")).unwrap_err();
+        assert!(matches!(error, ConfigError::UnknownProfile(ref name) if name == "research"));
+        assert!(error.to_string().contains("[profile.research]"));
+    }
+
+    #[test]
+    fn test_config_explicit_path() {
+        let path =
+            std::env::temp_dir().join(format!("arxivlens-config-{}.toml", std::process::id()));
+        std::fs::write(&path, "[query]\ncategory = \"cs.LG\"\n").unwrap();
+
+        let actual = Config::load_path(Some(&path), None).unwrap().0;
+        assert_eq!(actual.query.categories, vec!["cs.LG".to_string()]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_config_explicit_path_missing() {
+        let path = Path::new("/nonexistent/arxivlens/config.toml");
+        let error = Config::load_path(Some(path), None).unwrap_err();
+        assert!(matches!(error, ConfigError::MissingFile(ref p) if p == path));
+    }
+
+    #[test]
+    fn test_config_init_file() {
+        let dir = std::env::temp_dir().join(format!("arxivlens-init-{}", std::process::id()));
+        let path = dir.join(APP_DIR_NAME).join(CONFIG_FILE_NAME);
+
+        Config::init_file(&path, false).unwrap();
+        assert_eq!(
+            Config::load_from_file(&path, None).unwrap().0,
+            Config::default()
+        );
+
+        // An existing file is only overwritten with force.
+        std::fs::write(&path, "[query]\ncategory = \"cs.LG\"\n").unwrap();
+        let error = Config::init_file(&path, false).unwrap_err();
+        assert!(matches!(error, ConfigError::AlreadyExists(ref p) if p == &path));
+        assert_eq!(
+            Config::load_from_file(&path, None)
+                .unwrap()
+                .0
+                .query
+                .categories,
+            vec!["cs.LG".to_string()]
+        );
+        Config::init_file(&path, true).unwrap();
+        assert_eq!(
+            Config::load_from_file(&path, None).unwrap().0,
+            Config::default()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_template_examples() {
+        // Uncommenting the examples gives a valid config setting every documented key.
+        let uncommented: String = Config::template()
+            .lines()
+            .map(|line| match line.strip_prefix("# ") {
+                Some(setting) if setting.contains(" = ") => setting,
+                _ => line,
+            })
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let config = Config::from_toml_str(&uncommented, None).unwrap().0;
+        let table = toml::Table::try_from(config).unwrap();
+        for (section, _, keys) in TEMPLATE_SECTIONS {
+            for (key, _, _) in *keys {
+                assert!(
+                    section_table(&table, section)
+                        .and_then(|values| values.get(*key))
+                        .is_some(),
+                    "{section}.{key} not set"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_config_set_file_value() {
+        let dir = std::env::temp_dir().join(format!("arxivlens-set-{}", std::process::id()));
+        let path = dir.join(CONFIG_FILE_NAME);
+
+        // A missing file is created.
+        Config::set_file_value(&path, None, "pinned", "authors", vec!["Curie"].into()).unwrap();
+        let (config, _) = Config::load_from_file(&path, None).unwrap();
+        assert_eq!(config.pinned.authors, Some(vec!["Curie".to_string()]));
+
+        // The other keys are kept, unknown ones included.
+        std::fs::write(
+            &path,
+            "[query]\ncategory = \"cs.LG\"\nfuture_key = 1\n[profile.ml.query]\ncategory = \"cs.AI\"\n",
+        )
+        .unwrap();
+        Config::set_file_value(&path, None, "query", "category", vec!["hep-th"].into()).unwrap();
+        let (config, warnings) = Config::load_from_file(&path, None).unwrap();
+        assert_eq!(config.query.categories, vec!["hep-th".to_string()]);
+        assert_eq!(
+            warnings,
+            vec![ConfigWarning::UnknownKey("query.future_key".into())]
+        );
+        let (config, _) = Config::load_from_file(&path, Some("ml")).unwrap();
+        assert_eq!(config.query.categories, vec!["cs.AI".to_string()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_set_file_value_keeps_comments() {
+        let dir = std::env::temp_dir().join(format!("arxivlens-comments-{}", std::process::id()));
+        let path = dir.join(CONFIG_FILE_NAME);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            &path,
+            "# My arxivlens config\n[query]\n# The category of the feed\ncategory = \"cs.LG\" # ML\n",
+        )
+        .unwrap();
+
+        Config::set_file_value(&path, None, "query", "category", vec!["hep-th"].into()).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            content,
+            "# My arxivlens config\n[query]\n# The category of the feed\ncategory = [\"hep-th\"] # ML\n"
+        );
+    }
+
+    #[test]
+    fn test_config_set_file_value_in_profile() {
+        let dir = std::env::temp_dir().join(format!("arxivlens-profile-{}", std::process::id()));
+        let path = dir.join(CONFIG_FILE_NAME);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            &path,
+            "[pinned]\nauthors = [\"Curie\"]\n\n[profile.ml.query]\ncategory = \"cs.LG\"\n\n[profile.physics.query]\ncategory = \"hep-th\"\n",
+        )
+        .unwrap();
+
+        let authors = vec!["Curie", "Hinton"];
+        Config::set_file_value(&path, Some("ml"), "pinned", "authors", authors.into()).unwrap();
+
+        // The profile saved to gets the value...
+        let (config, _) = Config::load_from_file(&path, Some("ml")).unwrap();
+        assert_eq!(
+            config.pinned.authors,
+            Some(vec!["Curie".to_string(), "Hinton".to_string()])
+        );
+        assert_eq!(config.query.categories, vec!["cs.LG".to_string()]);
+        // ...while the base config and the other profiles keep theirs.
+        for profile in [None, Some("physics")] {
+            let (config, _) = Config::load_from_file(&path, profile).unwrap();
+            assert_eq!(config.pinned.authors, Some(vec!["Curie".to_string()]));
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(content.contains("[profile.ml.pinned]\nauthors = [\"Curie\", \"Hinton\"]\n"));
+        assert!(!content.contains("[profile]\n"));
+    }
 }
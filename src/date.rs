@@ -0,0 +1,212 @@
+//! Formatting the dates of the arXiv feed.
+//!
+//! arXiv gives its dates in UTC, e.g. "2024-01-01T00:00:00Z". They are shown in the
+//! local time of the user, in the format of the `ui.date_format` setting.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Format of the dates shown in the header.
+const HEADER_FORMAT: &str = "%Y-%m-%d %H:%M";
+/// Format of the short dates, e.g. "Mon Jul 8".
+const SHORT_FORMAT: &str = "%a %b %-d";
+/// Format of the relative dates older than [`RELATIVE_MAX_DAYS`].
+const ABSOLUTE_FORMAT: &str = "%Y-%m-%d";
+/// Age after which a relative date is shown as an absolute one.
+const RELATIVE_MAX_DAYS: i64 = 30;
+
+/// Format an arXiv date in local time, e.g. "2024-01-01 01:00" in Paris. `None` if the
+/// date can not be parsed.
+pub fn format_local(timestamp: &str) -> Option<String> {
+    format_in(timestamp, &Local)
+}
+
+/// Format an arXiv date in the given time zone.
+fn format_in<Tz: TimeZone>(timestamp: &str, tz: &Tz) -> Option<String>
+where
+    Tz::Offset: Display,
+{
+    let date = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some(date.with_timezone(tz).format(HEADER_FORMAT).to_string())
+}
+
+/// Format of the dates of the articles.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum DateFormat {
+    /// Age of the article, e.g. "3 days ago".
+    #[default]
+    Relative,
+    /// Day of the article, e.g. "Mon Jul 8".
+    Short,
+    /// A strftime format, e.g. "%Y-%m-%d".
+    Strftime(String),
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "relative" => Ok(DateFormat::Relative),
+            "short" => Ok(DateFormat::Short),
+            _ if StrftimeItems::new(s).any(|item| item == Item::Error) => Err(format!(
+                "invalid date format `{s}`, expected \"relative\", \"short\" or a strftime format"
+            )),
+            _ => Ok(DateFormat::Strftime(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for DateFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for DateFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateFormat::Relative => write!(f, "relative"),
+            DateFormat::Short => write!(f, "short"),
+            DateFormat::Strftime(format) => write!(f, "{format}"),
+        }
+    }
+}
+
+impl Serialize for DateFormat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Format an arXiv date in local time, relative to `now` for [`DateFormat::Relative`].
+///
+/// A date which can not be parsed is returned as is.
+pub fn format_date(timestamp: &str, format: &DateFormat, now: DateTime<Utc>) -> String {
+    format_date_in(timestamp, format, now, &Local)
+}
+
+fn format_date_in<Tz: TimeZone>(
+    timestamp: &str,
+    format: &DateFormat,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> String
+where
+    Tz::Offset: Display,
+{
+    let Ok(date) = DateTime::parse_from_rfc3339(timestamp) else {
+        return timestamp.to_string();
+    };
+    let date = date.with_timezone(tz);
+    match format {
+        DateFormat::Relative => {
+            let age = now.signed_duration_since(&date);
+            let plural = |count: i64, unit: &str| {
+                let s = if count == 1 { "" } else { "s" };
+                format!("{count} {unit}{s} ago")
+            };
+            match age.num_minutes() {
+                minutes if minutes < 1 => "just now".to_string(),
+                minutes if minutes < 60 => plural(minutes, "minute"),
+                _ if age.num_hours() < 24 => plural(age.num_hours(), "hour"),
+                _ if age.num_days() < 7 => plural(age.num_days(), "day"),
+                _ if age.num_days() <= RELATIVE_MAX_DAYS => plural(age.num_weeks(), "week"),
+                _ => date.format(ABSOLUTE_FORMAT).to_string(),
+            }
+        }
+        DateFormat::Short => date.format(SHORT_FORMAT).to_string(),
+        DateFormat::Strftime(format) => date.format(format).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn relative(timestamp: &str) -> String {
+        let now = "2024-07-09T20:00:00Z".parse().unwrap();
+        format_date_in(timestamp, &DateFormat::Relative, now, &Utc)
+    }
+
+    #[test]
+    fn test_format_in() {
+        assert_eq!(
+            format_in("2024-07-09T20:00:00Z", &Utc),
+            Some("2024-07-09 20:00".to_string())
+        );
+        let paris = FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            format_in("2024-07-09T23:30:00Z", &paris),
+            Some("2024-07-10 01:30".to_string())
+        );
+        let new_york = FixedOffset::west_opt(4 * 3600).unwrap();
+        assert_eq!(
+            format_in("2024-07-09T20:00:00-04:00", &new_york),
+            Some("2024-07-09 20:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_invalid() {
+        assert_eq!(format_local(""), None);
+        assert_eq!(format_local("yesterday"), None);
+        assert_eq!(
+            format_date("yesterday", &DateFormat::Short, Utc::now()),
+            "yesterday"
+        );
+    }
+
+    #[test]
+    fn test_format_relative() {
+        assert_eq!(relative("2024-07-09T19:59:30Z"), "just now");
+        assert_eq!(relative("2024-07-09T21:00:00Z"), "just now");
+        assert_eq!(relative("2024-07-09T19:59:00Z"), "1 minute ago");
+        assert_eq!(relative("2024-07-09T19:01:00Z"), "59 minutes ago");
+        assert_eq!(relative("2024-07-09T19:00:00Z"), "1 hour ago");
+        assert_eq!(relative("2024-07-08T20:00:01Z"), "23 hours ago");
+        assert_eq!(relative("2024-07-08T20:00:00Z"), "1 day ago");
+        assert_eq!(relative("2024-07-02T20:00:01Z"), "6 days ago");
+        assert_eq!(relative("2024-07-02T20:00:00Z"), "1 week ago");
+        assert_eq!(relative("2024-06-09T20:00:00Z"), "4 weeks ago");
+        assert_eq!(relative("2024-06-08T20:00:00Z"), "2024-06-08");
+    }
+
+    #[test]
+    fn test_format_short_and_strftime() {
+        let now = Utc::now();
+        let paris = FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            format_date_in("2024-07-08T20:00:00Z", &DateFormat::Short, now, &Utc),
+            "Mon Jul 8"
+        );
+        assert_eq!(
+            format_date_in("2024-07-08T23:00:00Z", &DateFormat::Short, now, &paris),
+            "Tue Jul 9"
+        );
+        let format = "%d/%m/%Y %H:%M".parse().unwrap();
+        assert_eq!(
+            format_date_in("2024-07-08T20:00:00Z", &format, now, &Utc),
+            "08/07/2024 20:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_date_format() {
+        assert_eq!("relative".parse(), Ok(DateFormat::Relative));
+        assert_eq!("short".parse(), Ok(DateFormat::Short));
+        assert_eq!(
+            "%Y-%m-%d".parse(),
+            Ok(DateFormat::Strftime("%Y-%m-%d".to_string()))
+        );
+        assert!("%Y-%Q".parse::<DateFormat>().is_err());
+        assert!("%".parse::<DateFormat>().is_err());
+    }
+}
@@ -0,0 +1,636 @@
+//! Editing the config from the interface.
+//!
+//! The [`ConfigEditor`] holds the field selected in the config popup and the text typed
+//! while editing it. The lists are edited as comma separated values.
+//!
+//! Authors and keywords can also be added one at a time from the article list, see
+//! [`ConfigField::with_value`].
+//!
+//! The [`QueryEditor`] holds the fields of the query popup, to run another query without
+//! restarting.
+
+use crate::arxiv::categories::{category_name, suggest_category, ARXIV_CATEGORIES};
+use crate::arxiv::{author_term, ArxivQuery, SearchQuery, SortBy, ARXIV_MAX_RESULTS};
+use crate::config::Config;
+
+/// Fields of the config editable from the interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigField {
+    Category,
+    PinnedAuthors,
+    Keywords,
+}
+
+impl ConfigField {
+    /// The fields, in the order of the popup.
+    pub const ALL: [ConfigField; 3] = [
+        ConfigField::Category,
+        ConfigField::PinnedAuthors,
+        ConfigField::Keywords,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConfigField::Category => "Category",
+            ConfigField::PinnedAuthors => "Pinned authors",
+            ConfigField::Keywords => "Keywords",
+        }
+    }
+
+    /// Section and key of the field in the config file.
+    pub fn key(self) -> (&'static str, &'static str) {
+        match self {
+            ConfigField::Category => ("query", "category"),
+            ConfigField::PinnedAuthors => ("pinned", "authors"),
+            ConfigField::Keywords => ("highlight", "keywords"),
+        }
+    }
+
+    /// Values of the field in the config.
+    pub fn values(self, config: &Config) -> Vec<String> {
+        match self {
+            ConfigField::Category => config.query.categories.clone(),
+            ConfigField::PinnedAuthors => config.pinned.authors.clone().unwrap_or_default(),
+            ConfigField::Keywords => config.highlight.keywords.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Check the values typed for the field: the categories must be in the arXiv
+    /// taxonomy, and there must be at least one, or the config would not load anymore.
+    pub fn validate(self, values: &[String]) -> Result<(), String> {
+        if self != ConfigField::Category {
+            return Ok(());
+        }
+        if values.is_empty() {
+            return Err("The category can not be empty".to_string());
+        }
+        match values.iter().find(|value| category_name(value).is_none()) {
+            Some(unknown) => Err(match suggest_category(unknown) {
+                Some(suggestion) => {
+                    format!("Unknown arXiv category `{unknown}`, did you mean `{suggestion}`?")
+                }
+                None => format!("Unknown arXiv category `{unknown}`"),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Values of the field without `value`, or `None` if it is not there, ignoring the
+    /// case.
+    pub fn without_value(self, config: &Config, value: &str) -> Option<Vec<String>> {
+        let mut values = self.values(config);
+        let position = values.iter().position(|v| v.eq_ignore_ascii_case(value))?;
+        values.remove(position);
+        Some(values)
+    }
+
+    /// Values of the field with `value` appended, or `None` if it is already there,
+    /// ignoring the case.
+    pub fn with_value(self, config: &Config, value: &str) -> Option<Vec<String>> {
+        let mut values = self.values(config);
+        if values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+            return None;
+        }
+        values.push(value.to_string());
+        Some(values)
+    }
+
+    /// Set the values of the field in the config. An empty list unsets the optional
+    /// fields.
+    pub fn set_values(self, config: &mut Config, values: Vec<String>) {
+        let optional = (!values.is_empty()).then(|| values.clone());
+        match self {
+            ConfigField::Category => config.query.categories = values,
+            ConfigField::PinnedAuthors => config.pinned.authors = optional,
+            ConfigField::Keywords => config.highlight.keywords = optional,
+        }
+    }
+}
+
+/// Split a comma separated list, dropping the empty values.
+pub fn parse_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Start shared by all the candidates, which are ASCII.
+pub fn shared_prefix(candidates: &[String]) -> &str {
+    let Some((first, others)) = candidates.split_first() else {
+        return "";
+    };
+    others.iter().fold(first.as_str(), |shared, candidate| {
+        let len = shared
+            .bytes()
+            .zip(candidate.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        &shared[..len]
+    })
+}
+
+/// State of the config popup.
+#[derive(Debug, Default)]
+pub struct ConfigEditor {
+    /// Position of the selected field in [`ConfigField::ALL`].
+    selected: usize,
+    /// Text of the field being edited, if any.
+    pub input: Option<String>,
+}
+
+impl ConfigEditor {
+    pub fn selected_field(&self) -> ConfigField {
+        ConfigField::ALL[self.selected]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(ConfigField::ALL.len() - 1);
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// Start editing the selected field, from its current values.
+    pub fn start_edit(&mut self, config: &Config) {
+        self.input = Some(self.selected_field().values(config).join(", "));
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if let Some(input) = &mut self.input {
+            input.push(c);
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if let Some(input) = &mut self.input {
+            input.pop();
+        }
+    }
+
+    /// Stop editing, dropping the typed text.
+    pub fn cancel(&mut self) {
+        self.input = None;
+    }
+
+    /// Stop editing and return the edited field with its new values, or why they are
+    /// invalid, in which case the edit goes on so the text can be fixed.
+    pub fn commit(&mut self) -> Option<Result<(ConfigField, Vec<String>), String>> {
+        let values = parse_list(self.input.as_ref()?);
+        let field = self.selected_field();
+        if let Err(e) = field.validate(&values) {
+            return Some(Err(e));
+        }
+        self.input = None;
+        Some(Ok((field, values)))
+    }
+}
+
+/// Fields of the query popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryField {
+    Category,
+    Author,
+    Title,
+    MaxResults,
+    Sort,
+}
+
+impl QueryField {
+    /// The fields, in the order of the popup.
+    pub const ALL: [QueryField; 5] = [
+        QueryField::Category,
+        QueryField::Author,
+        QueryField::Title,
+        QueryField::MaxResults,
+        QueryField::Sort,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            QueryField::Category => "Category",
+            QueryField::Author => "Author",
+            QueryField::Title => "Title",
+            QueryField::MaxResults => "Max results",
+            QueryField::Sort => "Sort",
+        }
+    }
+}
+
+/// Whether the search query is one of the fields of the query popup: the categories, the
+/// authors or the title terms.
+fn is_form_query(query: &SearchQuery) -> bool {
+    match query {
+        SearchQuery::Category(_) | SearchQuery::Author(_) | SearchQuery::Title(_) => true,
+        SearchQuery::AnyOf(queries) => queries.iter().all(is_form_query),
+        _ => false,
+    }
+}
+
+/// Terms of the search queries of one kind, e.g. the authors, with the alternatives of
+/// the `AnyOf` groups.
+fn terms_of(queries: &[SearchQuery], term: fn(&SearchQuery) -> Option<&str>) -> Vec<&str> {
+    queries
+        .iter()
+        .flat_map(|query| match query {
+            SearchQuery::AnyOf(queries) => queries.iter().filter_map(term).collect(),
+            query => term(query).into_iter().collect::<Vec<_>>(),
+        })
+        .collect()
+}
+
+/// State of the query popup: the text of each field, typed in place.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QueryEditor {
+    /// Position of the selected field in [`QueryField::ALL`].
+    selected: usize,
+    /// Text of each field, in the order of [`QueryField::ALL`].
+    values: [String; 5],
+    /// Why the query can not be run, or the categories completing the typed one.
+    pub message: Option<String>,
+}
+
+impl QueryEditor {
+    /// Fields filled from the query. The categories and authors are comma separated, the
+    /// authors as searched by arXiv, e.g. "Einstein_A".
+    pub fn from_query(query: &ArxivQuery) -> Self {
+        let queries = &query.search_queries;
+        let authors = terms_of(queries, |query| match query {
+            SearchQuery::Author(author) => Some(author),
+            _ => None,
+        });
+        let titles = terms_of(queries, |query| match query {
+            SearchQuery::Title(title) => Some(title),
+            _ => None,
+        });
+        let sort = match query.sort_by {
+            SortBy::Relevance => "relevance",
+            SortBy::LastUpdatedDate => "updated",
+            SortBy::SubmittedDate => "submitted",
+        };
+        Self {
+            selected: 0,
+            values: [
+                query.categories().join(", "),
+                authors.join(", "),
+                titles.join(" "),
+                query.max_results.to_string(),
+                sort.to_string(),
+            ],
+            message: None,
+        }
+    }
+
+    pub fn selected_field(&self) -> QueryField {
+        QueryField::ALL[self.selected]
+    }
+
+    pub fn value(&self, field: QueryField) -> &str {
+        let i = QueryField::ALL.iter().position(|&f| f == field);
+        i.map_or("", |i| self.values[i].as_str())
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(QueryField::ALL.len() - 1);
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.values[self.selected].push(c);
+        self.message = None;
+    }
+
+    pub fn pop_char(&mut self) {
+        self.values[self.selected].pop();
+        self.message = None;
+    }
+
+    /// Complete the last category typed, listing the categories starting with it if there
+    /// are several. Only the category field is completed.
+    pub fn complete_category(&mut self) {
+        if self.selected_field() != QueryField::Category {
+            return;
+        }
+        let input = &mut self.values[self.selected];
+        let partial = input.rsplit(',').next().unwrap_or_default().trim_start();
+        let candidates: Vec<String> = ARXIV_CATEGORIES
+            .iter()
+            .map(|(code, _)| code.to_string())
+            .filter(|code| code.starts_with(partial))
+            .collect();
+        let completed = shared_prefix(&candidates).to_string();
+        if !completed.is_empty() {
+            input.truncate(input.len() - partial.len());
+            input.push_str(&completed);
+        }
+        self.message = (candidates.len() > 1).then(|| candidates.join(" "));
+    }
+
+    /// The query of the fields, from the first page. The other search queries of `base`,
+    /// e.g. on the abstracts, and its sort order are kept.
+    pub fn to_query(&self, base: &ArxivQuery) -> Result<ArxivQuery, String> {
+        let categories = parse_list(self.value(QueryField::Category));
+        if !categories.is_empty() {
+            ConfigField::Category.validate(&categories)?;
+        }
+        let authors = parse_list(self.value(QueryField::Author));
+        let titles = self.value(QueryField::Title).split_whitespace();
+
+        let mut search_queries: Vec<SearchQuery> = Vec::new();
+        let categories = categories.into_iter().map(SearchQuery::Category);
+        search_queries.extend(SearchQuery::any_of(categories.collect()));
+        let authors = authors
+            .iter()
+            .map(|author| SearchQuery::Author(author_term(author)));
+        search_queries.extend(SearchQuery::any_of(authors.collect()));
+        search_queries.extend(titles.map(|title| SearchQuery::Title(title.to_string())));
+        if search_queries.is_empty() {
+            return Err("Set a category, an author or title words".to_string());
+        }
+        let others = base.search_queries.iter().filter(|q| !is_form_query(q));
+        search_queries.extend(others.cloned());
+
+        let max_results = self.value(QueryField::MaxResults).trim();
+        let max_results = max_results
+            .parse()
+            .ok()
+            .filter(|max_results| (1..=ARXIV_MAX_RESULTS).contains(max_results))
+            .ok_or_else(|| {
+                format!(
+                    "The max results must be between 1 and {ARXIV_MAX_RESULTS}, got `{max_results}`"
+                )
+            })?;
+        let sort_by = self.value(QueryField::Sort).trim().parse()?;
+        Ok(ArxivQuery {
+            search_queries,
+            start_index: 0,
+            max_results,
+            sort_by,
+            id_list: Vec::new(),
+            ..base.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arxiv::SortOrder;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            parse_list(" Curie, Becquerel ,, "),
+            vec!["Curie".to_string(), "Becquerel".to_string()]
+        );
+        assert!(parse_list("").is_empty());
+    }
+
+    #[test]
+    fn test_edit_and_commit() {
+        let config = Config::default();
+        let mut editor = ConfigEditor::default();
+        editor.select_next();
+        assert_eq!(editor.selected_field(), ConfigField::PinnedAuthors);
+
+        editor.start_edit(&config);
+        assert_eq!(editor.input.as_deref(), Some(""));
+        "Curie, Bohr".chars().for_each(|c| editor.push_char(c));
+        editor.pop_char();
+
+        assert_eq!(
+            editor.commit(),
+            Some(Ok((
+                ConfigField::PinnedAuthors,
+                vec!["Curie".to_string(), "Boh".to_string()]
+            )))
+        );
+        assert!(!editor.is_editing());
+        assert_eq!(editor.commit(), None);
+    }
+
+    #[test]
+    fn test_commit_validates_category() {
+        let config = Config::default();
+        let mut editor = ConfigEditor::default();
+        editor.start_edit(&config);
+        editor.input = Some("quant-ph, hep-thh".to_string());
+
+        assert_eq!(
+            editor.commit(),
+            Some(Err(
+                "Unknown arXiv category `hep-thh`, did you mean `hep-th`?".to_string()
+            ))
+        );
+        // The edit goes on, to fix the typo.
+        assert!(editor.is_editing());
+        editor.pop_char();
+        assert_eq!(
+            editor.commit(),
+            Some(Ok((
+                ConfigField::Category,
+                vec!["quant-ph".to_string(), "hep-th".to_string()]
+            )))
+        );
+
+        editor.start_edit(&config);
+        editor.input = Some(" , ".to_string());
+        assert_eq!(
+            editor.commit(),
+            Some(Err("The category can not be empty".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_edit_cancel() {
+        let config = Config::default();
+        let mut editor = ConfigEditor::default();
+        editor.start_edit(&config);
+        assert_eq!(editor.input.as_deref(), Some("quant-ph"));
+        editor.push_char('x');
+
+        editor.cancel();
+
+        assert!(!editor.is_editing());
+        assert_eq!(editor.commit(), None);
+    }
+
+    #[test]
+    fn test_selection_bounds() {
+        let mut editor = ConfigEditor::default();
+        editor.select_previous();
+        assert_eq!(editor.selected_field(), ConfigField::Category);
+        for _ in 0..5 {
+            editor.select_next();
+        }
+        assert_eq!(editor.selected_field(), ConfigField::Keywords);
+    }
+
+    #[test]
+    fn test_with_value() {
+        let mut config = Config::default();
+        config.pinned.authors = Some(vec!["Curie".to_string()]);
+        assert_eq!(
+            ConfigField::PinnedAuthors.with_value(&config, "Bohr"),
+            Some(vec!["Curie".to_string(), "Bohr".to_string()])
+        );
+        assert_eq!(
+            ConfigField::PinnedAuthors.with_value(&config, "curie"),
+            None
+        );
+        assert_eq!(
+            ConfigField::Keywords.with_value(&config, "qubit"),
+            Some(vec!["qubit".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_set_values() {
+        let mut config = Config::default();
+        ConfigField::Keywords.set_values(&mut config, vec!["qubit".to_string()]);
+        assert_eq!(config.highlight.keywords, Some(vec!["qubit".to_string()]));
+        ConfigField::Keywords.set_values(&mut config, Vec::new());
+        assert_eq!(config.highlight.keywords, None);
+    }
+
+    fn query(search_queries: Vec<SearchQuery>) -> ArxivQuery {
+        ArxivQuery {
+            search_queries,
+            start_index: 40,
+            max_results: 20,
+            sort_by: SortBy::SubmittedDate,
+            sort_order: SortOrder::Ascending,
+            id_list: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_query_editor_round_trip() {
+        let base = query(vec![
+            SearchQuery::AnyOf(vec![
+                SearchQuery::Category("quant-ph".to_string()),
+                SearchQuery::Category("cs.LG".to_string()),
+            ]),
+            SearchQuery::Author("Curie_M".to_string()),
+            SearchQuery::Abstract("qubit".to_string()),
+        ]);
+        let editor = QueryEditor::from_query(&base);
+        assert_eq!(editor.value(QueryField::Category), "quant-ph, cs.LG");
+        assert_eq!(editor.value(QueryField::Author), "Curie_M");
+        assert_eq!(editor.value(QueryField::Title), "");
+        assert_eq!(editor.value(QueryField::MaxResults), "20");
+        assert_eq!(editor.value(QueryField::Sort), "submitted");
+
+        // The query is run again from the first page.
+        assert_eq!(
+            editor.to_query(&base),
+            Ok(ArxivQuery {
+                start_index: 0,
+                ..base.clone()
+            })
+        );
+    }
+
+    #[test]
+    fn test_query_editor_to_query() {
+        let base = query(vec![SearchQuery::Category("quant-ph".to_string())]);
+        let mut editor = QueryEditor::from_query(&base);
+        editor.values = [
+            "cs.LG".to_string(),
+            "Geoffrey Hinton, Yann LeCun".to_string(),
+            "deep  learning".to_string(),
+            "50".to_string(),
+            "updated".to_string(),
+        ];
+
+        let query = editor.to_query(&base).unwrap();
+
+        assert_eq!(
+            query.search_queries,
+            [
+                SearchQuery::Category("cs.LG".to_string()),
+                SearchQuery::AnyOf(vec![
+                    SearchQuery::Author("Hinton_G".to_string()),
+                    SearchQuery::Author("LeCun_Y".to_string()),
+                ]),
+                SearchQuery::Title("deep".to_string()),
+                SearchQuery::Title("learning".to_string()),
+            ]
+        );
+        assert_eq!(query.max_results, 50);
+        assert_eq!(query.sort_by, SortBy::LastUpdatedDate);
+        assert_eq!(query.sort_order, SortOrder::Ascending);
+    }
+
+    #[test]
+    fn test_query_editor_errors() {
+        let base = query(vec![SearchQuery::Category("quant-ph".to_string())]);
+        let with = |field: QueryField, value: &str| {
+            let mut editor = QueryEditor::from_query(&base);
+            let i = QueryField::ALL.iter().position(|&f| f == field).unwrap();
+            editor.values[i] = value.to_string();
+            editor.to_query(&base).unwrap_err()
+        };
+        assert_eq!(
+            with(QueryField::Category, ""),
+            "Set a category, an author or title words"
+        );
+        assert_eq!(
+            with(QueryField::Category, "quant-phh"),
+            "Unknown arXiv category `quant-phh`, did you mean `quant-ph`?"
+        );
+        assert_eq!(
+            with(QueryField::MaxResults, "0"),
+            "The max results must be between 1 and 2000, got `0`"
+        );
+        assert_eq!(
+            with(QueryField::Sort, "newest"),
+            "unknown sort `newest`, expected one of: relevance, submitted, updated"
+        );
+    }
+
+    #[test]
+    fn test_query_editor_typing_and_completion() {
+        let base = query(vec![SearchQuery::Category("quant-ph".to_string())]);
+        let mut editor = QueryEditor::from_query(&base);
+        ", cs.L".chars().for_each(|c| editor.push_char(c));
+        editor.complete_category();
+        assert_eq!(editor.value(QueryField::Category), "quant-ph, cs.L");
+        assert_eq!(editor.message.as_deref(), Some("cs.LG cs.LO"));
+        editor.push_char('G');
+        assert_eq!(editor.message, None);
+
+        editor.pop_char();
+        editor.pop_char();
+        editor.push_char('R');
+        editor.complete_category();
+        assert_eq!(editor.value(QueryField::Category), "quant-ph, cs.RO");
+
+        // The other fields are not completed.
+        editor.select_next();
+        editor.push_char('x');
+        editor.complete_category();
+        assert_eq!(editor.value(QueryField::Author), "x");
+        for _ in 0..10 {
+            editor.select_next();
+        }
+        assert_eq!(editor.selected_field(), QueryField::Sort);
+    }
+
+    #[test]
+    fn test_shared_prefix() {
+        let candidates = ["cs.LG".to_string(), "cs.LO".to_string()];
+        assert_eq!(shared_prefix(&candidates), "cs.L");
+        assert_eq!(shared_prefix(&candidates[..1]), "cs.LG");
+        assert_eq!(shared_prefix(&[]), "");
+    }
+}
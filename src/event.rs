@@ -2,18 +2,27 @@ use crate::app::AppResult;
 use ratatui::crossterm::event::{
     self, Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent,
 };
+use std::io;
 use std::sync::mpsc;
 use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default interval between two [`Event::Tick`], driving the animations.
+pub const TICK_RATE: Duration = Duration::from_millis(100);
 
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
     /// Key press.
     Key(KeyEvent),
+    /// Text pasted in the terminal, in one piece.
+    Paste(String),
     /// Mouse click/scroll.
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Sent at a steady rate, whatever the other events.
+    Tick,
 }
 
 /// Terminal event handler.
@@ -30,32 +39,59 @@ pub struct EventHandler {
 
 impl Default for EventHandler {
     fn default() -> Self {
-        Self::new()
+        Self::new(TICK_RATE)
     }
 }
 
 impl EventHandler {
-    /// Constructs a new instance of [`EventHandler`].
-    pub fn new() -> Self {
+    /// Constructs a new instance of [`EventHandler`], reading the events of the terminal
+    /// and ticking every `tick_rate`.
+    pub fn new(tick_rate: Duration) -> Self {
+        Self::with_source(tick_rate, |timeout| {
+            if event::poll(timeout)? {
+                event::read().map(Some)
+            } else {
+                Ok(None)
+            }
+        })
+    }
+
+    /// Constructs an [`EventHandler`] reading the events from `source`, which waits at
+    /// most the given timeout for the next event.
+    ///
+    /// The source is polled until the next tick is due, so an event is sent as soon as
+    /// it is read and a steady stream of events does not hold the ticks back.
+    pub fn with_source<F>(tick_rate: Duration, mut source: F) -> Self
+    where
+        F: FnMut(Duration) -> io::Result<Option<CrosstermEvent>> + Send + 'static,
+    {
         let (sender, receiver) = mpsc::channel();
         let handler = {
             let sender = sender.clone();
-            thread::spawn(move || loop {
-                match event::read().expect("unable to read event") {
-                    CrosstermEvent::Key(e) => {
-                        if e.kind == KeyEventKind::Press {
-                            sender.send(Event::Key(e))
-                        } else {
-                            Ok(())
+            thread::spawn(move || {
+                let mut last_tick = Instant::now();
+                loop {
+                    let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+                    let event = match source(timeout).expect("unable to read event") {
+                        Some(CrosstermEvent::Key(e)) if e.kind == KeyEventKind::Press => {
+                            Some(Event::Key(e))
                         }
+                        Some(CrosstermEvent::Mouse(e)) => Some(Event::Mouse(e)),
+                        Some(CrosstermEvent::Resize(w, h)) => Some(Event::Resize(w, h)),
+                        Some(CrosstermEvent::Paste(text)) => Some(Event::Paste(text)),
+                        _ => None,
+                    };
+                    if last_tick.elapsed() >= tick_rate {
+                        last_tick = Instant::now();
+                        if sender.send(Event::Tick).is_err() {
+                            break;
+                        }
+                    }
+                    // The receiver is gone once the application quit.
+                    if event.is_some_and(|event| sender.send(event).is_err()) {
+                        break;
                     }
-                    CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
-                    CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
-                    CrosstermEvent::FocusGained => Ok(()),
-                    CrosstermEvent::FocusLost => Ok(()),
-                    CrosstermEvent::Paste(_) => unimplemented!(),
                 }
-                .expect("failed to send terminal event")
             })
         };
         Self {
@@ -73,3 +109,74 @@ impl EventHandler {
         Ok(self.receiver.recv()?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+
+    /// Handler reading the events sent on the returned channel.
+    fn channel_handler(tick_rate: Duration) -> (mpsc::Sender<CrosstermEvent>, EventHandler) {
+        let (sender, receiver) = mpsc::channel();
+        let handler = EventHandler::with_source(tick_rate, move |timeout| {
+            Ok(receiver.recv_timeout(timeout).ok())
+        });
+        (sender, handler)
+    }
+
+    fn key(c: char) -> CrosstermEvent {
+        CrosstermEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_paste_in_one_event() {
+        let (sender, handler) = channel_handler(Duration::from_secs(60));
+        sender
+            .send(CrosstermEvent::Paste("surface codes".to_string()))
+            .unwrap();
+        let Event::Paste(text) = handler.next().unwrap() else {
+            panic!("expected a paste event");
+        };
+        assert_eq!(text, "surface codes");
+    }
+
+    #[test]
+    fn test_ticks_without_events() {
+        let (_sender, handler) = channel_handler(Duration::from_millis(5));
+        for _ in 0..3 {
+            assert!(matches!(handler.next().unwrap(), Event::Tick));
+        }
+    }
+
+    #[test]
+    fn test_key_not_delayed_by_ticks() {
+        let (sender, handler) = channel_handler(Duration::from_secs(60));
+        let sent = Instant::now();
+        sender.send(key('j')).unwrap();
+        let Event::Key(event) = handler.next().unwrap() else {
+            panic!("expected a key event");
+        };
+        assert_eq!(event.code, KeyCode::Char('j'));
+        assert!(sent.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_ticks_during_key_stream() {
+        let tick_rate = Duration::from_millis(20);
+        let (sender, handler) = channel_handler(tick_rate);
+        thread::spawn(move || {
+            // Keys every few milliseconds, faster than the ticks.
+            for _ in 0..100 {
+                if sender.send(key('j')).is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(2));
+            }
+        });
+        let mut keys = 0;
+        while !matches!(handler.next().unwrap(), Event::Tick) {
+            keys += 1;
+        }
+        assert!(keys < 100, "the ticks were held back by the keys");
+    }
+}
@@ -0,0 +1,662 @@
+//! Exporting arXiv entries to BibTeX, JSON, CSV, plain text or a Markdown or HTML digest,
+//! or citing one of them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::arxiv::ArxivEntry;
+
+const ARXIV_ABS_PREFIXES: [&str; 2] = ["http://arxiv.org/abs/", "https://arxiv.org/abs/"];
+/// Number of characters of the abstract kept in a summary block.
+const SUMMARY_BLOCK_CHARS: usize = 300;
+
+/// Format of the exported file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Bibtex,
+    Json,
+    Csv,
+    /// One `id\ttitle\tauthors\tdate` line per entry.
+    Plain,
+    /// Digest to send around, one section per entry.
+    Markdown,
+    /// The digest as a simple HTML page.
+    Html,
+}
+
+impl ExportFormat {
+    /// Extension of the exported file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Bibtex => "bib",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Plain => "txt",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+/// Serializable view of an [`ArxivEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportEntry {
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub summary: String,
+    pub published: String,
+    pub updated: String,
+}
+
+impl From<&ArxivEntry> for ExportEntry {
+    fn from(entry: &ArxivEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            title: entry.title.clone(),
+            authors: entry.authors.clone(),
+            summary: entry.summary.clone(),
+            published: entry.published.clone(),
+            updated: entry.updated.clone(),
+        }
+    }
+}
+
+/// The arXiv identifier of an entry, e.g. `2401.01234v1` out of its abstract url.
+pub fn arxiv_id(entry: &ArxivEntry) -> &str {
+    ARXIV_ABS_PREFIXES
+        .iter()
+        .find_map(|prefix| entry.id.strip_prefix(prefix))
+        .unwrap_or(&entry.id)
+}
+
+/// Format one entry as a BibTeX record.
+pub fn to_bibtex(entry: &ArxivEntry) -> String {
+    let id = arxiv_id(entry);
+    let year = entry.published.get(..4).unwrap_or_default();
+    format!(
+        "@misc{{{id},\n  title = {{{}}},\n  author = {{{}}},\n  year = {{{year}}},\n  eprint = {{{id}}},\n  archivePrefix = {{arXiv}},\n  url = {{{}}},\n}}\n",
+        entry.title,
+        entry.authors.join(" and "),
+        entry.id,
+    )
+}
+
+/// Style of the citation of an article copied to the clipboard.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CitationStyle {
+    #[default]
+    Apa,
+    Mla,
+}
+
+impl CitationStyle {
+    /// The other style, to cycle through them.
+    pub fn next(self) -> Self {
+        match self {
+            CitationStyle::Apa => CitationStyle::Mla,
+            CitationStyle::Mla => CitationStyle::Apa,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CitationStyle::Apa => "APA",
+            CitationStyle::Mla => "MLA",
+        }
+    }
+}
+
+/// The arXiv identifier of an entry split from its version, e.g.
+/// `("2401.01234", Some("v2"))`.
+fn split_version(entry: &ArxivEntry) -> (&str, Option<&str>) {
+    let id = arxiv_id(entry);
+    match id.rfind('v') {
+        Some(i) if id.len() > i + 1 && id[i + 1..].chars().all(|c| c.is_ascii_digit()) => {
+            (&id[..i], Some(&id[i..]))
+        }
+        _ => (id, None),
+    }
+}
+
+/// The arXiv identifier of an entry without its version, e.g. `2401.01234`.
+fn unversioned_id(entry: &ArxivEntry) -> &str {
+    split_version(entry).0
+}
+
+/// The version of an entry, e.g. `v2`, if its identifier has one.
+pub fn arxiv_version(entry: &ArxivEntry) -> Option<&str> {
+    split_version(entry).1
+}
+
+/// Surname and given names of an author, e.g. `("Curie", ["Marie"])`.
+fn split_name(name: &str) -> (&str, Vec<&str>) {
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    let surname = words.pop().unwrap_or_default();
+    (surname, words)
+}
+
+/// Author in APA style, e.g. "Curie, M.".
+fn apa_author(name: &str) -> String {
+    let (surname, given) = split_name(name);
+    let initials: Vec<String> = given
+        .iter()
+        .filter_map(|word| word.chars().next())
+        .map(|initial| format!("{initial}."))
+        .collect();
+    if initials.is_empty() {
+        surname.to_string()
+    } else {
+        format!("{surname}, {}", initials.join(" "))
+    }
+}
+
+/// Author listed first in MLA style, e.g. "Curie, Marie".
+fn mla_first_author(name: &str) -> String {
+    let (surname, given) = split_name(name);
+    if given.is_empty() {
+        surname.to_string()
+    } else {
+        format!("{surname}, {}", given.join(" "))
+    }
+}
+
+/// Format one entry as a citation of an arXiv preprint, e.g. in APA:
+/// `Curie, M., & Schrödinger, E. (2024). Title. arXiv preprint arXiv:2401.01234.`
+pub fn to_citation(entry: &ArxivEntry, style: CitationStyle) -> String {
+    let id = unversioned_id(entry);
+    let year = entry.published.get(..4).unwrap_or_default();
+    let title = entry.title.trim_end_matches('.');
+    match style {
+        CitationStyle::Apa => {
+            let authors: Vec<String> = entry.authors.iter().map(|a| apa_author(a)).collect();
+            let authors = match authors.as_slice() {
+                [] => String::new(),
+                [author] => author.clone(),
+                [first @ .., last] => format!("{}, & {last}", first.join(", ")),
+            };
+            format!("{authors} ({year}). {title}. arXiv preprint arXiv:{id}.")
+        }
+        CitationStyle::Mla => {
+            let authors = match entry.authors.as_slice() {
+                [] => String::new(),
+                [author] => mla_first_author(author),
+                [first, second] => format!("{}, and {second}", mla_first_author(first)),
+                [first, ..] => format!("{}, et al", mla_first_author(first)),
+            };
+            format!("{authors}. \"{title}.\" arXiv preprint arXiv:{id}, {year}.")
+        }
+    }
+}
+
+/// The first `max_chars` characters of a text, its whitespace collapsed, cut at the last
+/// word boundary with an ellipsis if it is longer.
+fn excerpt(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(max_chars) {
+        None => text,
+        Some((end, _)) => {
+            let cut = &text[..end];
+            let cut = cut.rsplit_once(' ').map_or(cut, |(words, _)| words);
+            format!("{}…", cut.trim_end_matches([',', ';', ':', '.']))
+        }
+    }
+}
+
+/// Format one entry as a plain text block to paste in an email: its title, authors and
+/// url, then the beginning of its abstract.
+pub fn to_summary_block(entry: &ArxivEntry) -> String {
+    format!(
+        "{}\n{}\n{}\n\n{}\n",
+        entry.title,
+        entry.authors.join(", "),
+        entry.id,
+        excerpt(&entry.summary, SUMMARY_BLOCK_CHARS),
+    )
+}
+
+/// Format the entries as a list of BibTeX records.
+pub fn to_bibtex_list(entries: &[ArxivEntry]) -> String {
+    entries.iter().map(to_bibtex).collect::<Vec<_>>().join("\n")
+}
+
+/// Format the entries as a JSON array.
+pub fn to_json(entries: &[ArxivEntry]) -> Result<String, serde_json::Error> {
+    let entries: Vec<ExportEntry> = entries.iter().map(ExportEntry::from).collect();
+    serde_json::to_string_pretty(&entries)
+}
+
+/// Quote a CSV field if it contains a separator, a quote or a newline.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Format the entries as CSV with title, authors, id and published columns.
+pub fn to_csv(entries: &[ArxivEntry]) -> String {
+    let mut csv = String::from("title,authors,id,published\n");
+    for entry in entries {
+        let row = [
+            csv_field(&entry.title),
+            csv_field(entry.get_all_authors()),
+            csv_field(&entry.id),
+            csv_field(&entry.published),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Format the entries as one tab separated `id, title, authors, date` line per entry.
+pub fn to_plain(entries: &[ArxivEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                arxiv_id(entry),
+                entry.title,
+                entry.get_all_authors(),
+                entry.published
+            )
+        })
+        .collect()
+}
+
+/// What the digests of the entries hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestOptions {
+    /// Each entry is followed by its abstract.
+    pub abstracts: bool,
+    /// Only the first entries are kept, all of them if `None`.
+    pub max_entries: Option<usize>,
+}
+
+impl Default for DigestOptions {
+    fn default() -> Self {
+        Self {
+            abstracts: true,
+            max_entries: None,
+        }
+    }
+}
+
+impl DigestOptions {
+    /// The entries kept in the digest.
+    fn entries<'e>(&self, entries: &'e [ArxivEntry]) -> &'e [ArxivEntry] {
+        &entries[..self.max_entries.unwrap_or(usize::MAX).min(entries.len())]
+    }
+}
+
+/// Day of an arXiv timestamp, e.g. `2024-07-08` out of `2024-07-08T10:00:00Z`.
+fn day(timestamp: &str) -> &str {
+    timestamp.split_once('T').map_or(timestamp, |(day, _)| day)
+}
+
+/// Categories of an entry, the primary one first.
+fn categories(entry: &ArxivEntry) -> Vec<&str> {
+    let mut categories: Vec<&str> = entry.primary_category.iter().map(String::as_str).collect();
+    for category in &entry.categories {
+        if !categories.contains(&category.as_str()) {
+            categories.push(category);
+        }
+    }
+    categories
+}
+
+/// Escape the characters Markdown would interpret in running text, so the text is
+/// shown as is.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+    {
+        if r"\`*_[]<>".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape the characters HTML would interpret.
+fn escape_html(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Format the entries as a Markdown digest: one section per entry with its linked title,
+/// authors, categories, publication day and abstract.
+pub fn to_markdown_digest(entries: &[ArxivEntry], options: &DigestOptions) -> String {
+    let entries = options.entries(entries);
+    let mut digest = format!("# arXiv digest\n\n{} articles\n", entries.len());
+    for entry in entries {
+        let categories = categories(entry);
+        let mut details = vec![format!(
+            "**Authors:** {}",
+            escape_markdown(&entry.authors.join(", "))
+        )];
+        if !categories.is_empty() {
+            details.push(format!(
+                "**Categories:** {}",
+                escape_markdown(&categories.join(", "))
+            ));
+        }
+        details.push(format!("**Published:** {}", day(&entry.published)));
+        digest.push_str(&format!(
+            "\n## [{}]({})\n\n{}\n",
+            escape_markdown(&entry.title),
+            entry.id,
+            details.join("  \n"),
+        ));
+        if options.abstracts {
+            digest.push_str(&format!("\n{}\n", escape_markdown(&entry.summary)));
+        }
+    }
+    digest
+}
+
+/// Format the entries as the digest of [`to_markdown_digest`] in a simple HTML page.
+pub fn to_html_digest(entries: &[ArxivEntry], options: &DigestOptions) -> String {
+    let entries = options.entries(entries);
+    let mut digest = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>arXiv digest</title></head>\n\
+         <body>\n<h1>arXiv digest</h1>\n<p>{} articles</p>\n",
+        entries.len()
+    );
+    for entry in entries {
+        let categories = categories(entry);
+        let mut details = vec![format!(
+            "<b>Authors:</b> {}",
+            escape_html(&entry.authors.join(", "))
+        )];
+        if !categories.is_empty() {
+            details.push(format!(
+                "<b>Categories:</b> {}",
+                escape_html(&categories.join(", "))
+            ));
+        }
+        details.push(format!(
+            "<b>Published:</b> {}",
+            escape_html(day(&entry.published))
+        ));
+        digest.push_str(&format!(
+            "<h2><a href=\"{}\">{}</a></h2>\n<p>{}</p>\n",
+            escape_html(&entry.id),
+            escape_html(&entry.title),
+            details.join("<br>\n"),
+        ));
+        if options.abstracts {
+            digest.push_str(&format!("<p>{}</p>\n", escape_html(&entry.summary)));
+        }
+    }
+    digest.push_str("</body>\n</html>\n");
+    digest
+}
+
+/// Format the entries in the given format, the digests following the options.
+pub fn export_articles(
+    entries: &[ArxivEntry],
+    format: ExportFormat,
+    digest: &DigestOptions,
+) -> Result<String, serde_json::Error> {
+    match format {
+        ExportFormat::Bibtex => Ok(to_bibtex_list(entries)),
+        ExportFormat::Json => to_json(entries),
+        ExportFormat::Csv => Ok(to_csv(entries)),
+        ExportFormat::Plain => Ok(to_plain(entries)),
+        ExportFormat::Markdown => Ok(to_markdown_digest(entries, digest)),
+        ExportFormat::Html => Ok(to_html_digest(entries, digest)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arxiv::ArxivQueryResult;
+
+    const FIXTURE_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+          <updated>2024-07-09T20:00:00Z</updated>
+          <entry>
+            <id>http://arxiv.org/abs/2407.00001v1</id>
+            <updated>2024-07-08T10:00:00Z</updated>
+            <published>2024-07-08T10:00:00Z</published>
+            <title>Fixture Title</title>
+            <summary>Fixture summary.</summary>
+            <author><name>Author One</name></author>
+            <author><name>Author Two</name></author>
+          </entry>
+        </feed>"#;
+
+    fn tricky_entries() -> Vec<ArxivEntry> {
+        vec![
+            ArxivEntry::new(
+                "Quantum \"error\" correction, revisited".to_string(),
+                vec!["Erwin Schrödinger".to_string(), "Marie Curie".to_string()],
+                "A summary, with commas\nand newlines.".to_string(),
+                "http://arxiv.org/abs/2401.01234v1".to_string(),
+                "2024-01-02T00:00:00Z".to_string(),
+                "2024-01-02T00:00:00Z".to_string(),
+            ),
+            ArxivEntry::new(
+                "量子 computing".to_string(),
+                vec!["Author One".to_string()],
+                "Plain summary".to_string(),
+                "http://arxiv.org/abs/2312.54321v2".to_string(),
+                "2023-12-31T00:00:00Z".to_string(),
+                "2023-12-31T00:00:00Z".to_string(),
+            ),
+        ]
+    }
+
+    /// Minimal CSV reader to check the round trip of the quoting.
+    fn parse_csv(content: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = content.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, in_quotes) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => in_quotes = !in_quotes,
+                (',', false) => row.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                (c, _) => field.push(c),
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn test_arxiv_id() {
+        let entries = tricky_entries();
+        assert_eq!(arxiv_id(&entries[0]), "2401.01234v1");
+    }
+
+    #[test]
+    fn test_bibtex() {
+        let entries = tricky_entries();
+        let expected = "@misc{2401.01234v1,\n  title = {Quantum \"error\" correction, revisited},\n  author = {Erwin Schrödinger and Marie Curie},\n  year = {2024},\n  eprint = {2401.01234v1},\n  archivePrefix = {arXiv},\n  url = {http://arxiv.org/abs/2401.01234v1},\n}\n";
+        assert_eq!(to_bibtex(&entries[0]), expected);
+
+        let bibtex = to_bibtex_list(&entries);
+        assert_eq!(bibtex.matches("@misc{").count(), 2);
+        assert!(bibtex.contains("title = {量子 computing}"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let entries = tricky_entries();
+        let json = to_json(&entries).unwrap();
+        let parsed: Vec<ExportEntry> = serde_json::from_str(&json).unwrap();
+        let expected: Vec<ExportEntry> = entries.iter().map(ExportEntry::from).collect();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_plain_from_fixture_feed() {
+        let result = ArxivQueryResult::from_xml_content(FIXTURE_FEED).unwrap();
+        let plain = export_articles(
+            &result.articles,
+            ExportFormat::Plain,
+            &DigestOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            plain,
+            "2407.00001v1\tFixture Title\tAuthor One, Author Two\t2024-07-08T10:00:00Z\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_digest() {
+        let mut entries = tricky_entries();
+        entries[0].title = "*Fast* [quantum] decoders_v2 <RL>".to_string();
+        entries[0] = entries[0]
+            .clone()
+            .with_categories(Some("quant-ph".to_string()), vec!["cs.LG".to_string()]);
+
+        let digest = to_markdown_digest(&entries, &DigestOptions::default());
+        assert_eq!(
+            digest,
+            "# arXiv digest\n\n2 articles\n\
+             \n## [\\*Fast\\* \\[quantum\\] decoders\\_v2 \\<RL\\>](http://arxiv.org/abs/2401.01234v1)\n\
+             \n**Authors:** Erwin Schrödinger, Marie Curie  \n**Categories:** quant-ph, cs.LG  \n\
+             **Published:** 2024-01-02\n\
+             \nA summary, with commas and newlines.\n\
+             \n## [量子 computing](http://arxiv.org/abs/2312.54321v2)\n\
+             \n**Authors:** Author One  \n**Published:** 2023-12-31\n\
+             \nPlain summary\n"
+        );
+
+        let options = DigestOptions {
+            abstracts: false,
+            max_entries: Some(1),
+        };
+        let digest = to_markdown_digest(&entries, &options);
+        assert!(digest.contains("1 articles"));
+        assert!(!digest.contains("量子"));
+        assert!(!digest.contains("A summary"));
+    }
+
+    #[test]
+    fn test_html_digest() {
+        let mut entries = tricky_entries();
+        entries[0].title = "Bounds for <i>n</i> & m".to_string();
+
+        let digest =
+            export_articles(&entries, ExportFormat::Html, &DigestOptions::default()).unwrap();
+        assert!(digest.starts_with("<!DOCTYPE html>"));
+        assert!(digest.contains(
+            "<h2><a href=\"http://arxiv.org/abs/2401.01234v1\">Bounds for &lt;i&gt;n&lt;/i&gt; &amp; m</a></h2>"
+        ));
+        assert!(digest.contains("<p>A summary, with commas and newlines.</p>"));
+        assert!(digest.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn test_json_from_fixture_feed() {
+        let result = ArxivQueryResult::from_xml_content(FIXTURE_FEED).unwrap();
+        let json = export_articles(
+            &result.articles,
+            ExportFormat::Json,
+            &DigestOptions::default(),
+        )
+        .unwrap();
+        let parsed: Vec<ExportEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].title, "Fixture Title");
+        assert_eq!(parsed[0].authors, vec!["Author One", "Author Two"]);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let entries = tricky_entries();
+        let rows = parse_csv(&to_csv(&entries));
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], vec!["title", "authors", "id", "published"]);
+        assert_eq!(
+            rows[1],
+            vec![
+                "Quantum \"error\" correction, revisited",
+                "Erwin Schrödinger, Marie Curie",
+                "http://arxiv.org/abs/2401.01234v1",
+                "2024-01-02T00:00:00Z",
+            ]
+        );
+        assert_eq!(rows[2][0], "量子 computing");
+    }
+
+    #[test]
+    fn test_citation_apa() {
+        let entries = tricky_entries();
+        assert_eq!(
+            to_citation(&entries[0], CitationStyle::Apa),
+            "Schrödinger, E., & Curie, M. (2024). Quantum \"error\" correction, revisited. arXiv preprint arXiv:2401.01234."
+        );
+        assert_eq!(
+            to_citation(&entries[1], CitationStyle::Apa),
+            "One, A. (2023). 量子 computing. arXiv preprint arXiv:2312.54321."
+        );
+    }
+
+    #[test]
+    fn test_citation_mla() {
+        let mut entries = tricky_entries();
+        assert_eq!(
+            to_citation(&entries[0], CitationStyle::Mla),
+            "Schrödinger, Erwin, and Marie Curie. \"Quantum \"error\" correction, revisited.\" arXiv preprint arXiv:2401.01234, 2024."
+        );
+        entries[1].authors = vec![
+            "Paul A. M. Dirac".to_string(),
+            "Marie Curie".to_string(),
+            "Max Born".to_string(),
+        ];
+        assert_eq!(
+            to_citation(&entries[1], CitationStyle::Mla),
+            "Dirac, Paul A. M., et al. \"量子 computing.\" arXiv preprint arXiv:2312.54321, 2023."
+        );
+        assert_eq!(
+            to_citation(&entries[1], CitationStyle::Apa),
+            "Dirac, P. A. M., Curie, M., & Born, M. (2023). 量子 computing. arXiv preprint arXiv:2312.54321."
+        );
+    }
+
+    #[test]
+    fn test_summary_block() {
+        let mut entry = tricky_entries().remove(0);
+        assert_eq!(
+            to_summary_block(&entry),
+            "Quantum \"error\" correction, revisited\nErwin Schrödinger, Marie Curie\nhttp://arxiv.org/abs/2401.01234v1\n\nA summary, with commas and newlines.\n"
+        );
+
+        // Long abstracts are cut between words, multi-byte characters included.
+        entry.summary = "Schrödinger équation ".repeat(20);
+        let block = to_summary_block(&entry);
+        let lines: Vec<&str> = block.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[2], "http://arxiv.org/abs/2401.01234v1");
+        assert!(lines[4].ends_with("équation…"));
+        assert!(lines[4].chars().count() <= SUMMARY_BLOCK_CHARS + 1);
+    }
+}
@@ -1,19 +1,339 @@
-use crate::app::{App, AppResult};
+use crate::app::{App, AppResult, Context, FeedStatus};
+use crate::export::ExportFormat;
+use crate::ui::Shortcut;
 use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
+/// Keys listed in the help popup, as `(context, shortcuts)`, matching the handlers below.
+///
+/// The tests press each of these keys in its context, so a key listed here but not
+/// handled is caught.
+pub const KEY_HELP: &[(&str, &[Shortcut])] = &[
+    (
+        "Feed",
+        &[
+            ("move", "j/k ↑/↓"),
+            ("move by 10", "Ctrl-d/Ctrl-u"),
+            ("first/last", "g/G"),
+            ("yank url", "y"),
+            ("yank citation", "Y"),
+            ("yank summary for an email", "m"),
+            ("yank query url", "u"),
+            ("citation style APA/MLA", "S"),
+            ("sort by date/title/feed order", "s"),
+            ("dim/hide/show the cross-lists", "x"),
+            ("first new article", "n"),
+            ("only the new articles", "N"),
+            ("search, or edit the applied one", "/"),
+            ("export", "e"),
+            ("refresh", "r"),
+            ("edit the query", "Q"),
+            ("back to the previous query", "b"),
+            ("config", "c"),
+            ("open all visible", "O"),
+            ("pin author/highlight keyword", "+a/+k"),
+            ("pin/unpin the first author", "p"),
+            ("articles of the first author", "A"),
+            ("articles of the category", "C"),
+            ("pinned feeds", "Tab"),
+            ("expand/collapse the pinned feeds", "Z"),
+            ("feed statistics", "i"),
+            ("command, e.g. :sort updated", ":"),
+            ("help", "?"),
+            ("clear the applied search", "Esc"),
+            ("quit", "q/Esc"),
+        ],
+    ),
+    (
+        "Pinned feeds",
+        &[
+            ("move", "j/k ↑/↓"),
+            ("first/last", "g/G"),
+            ("yank url", "y"),
+            ("yank citation", "Y"),
+            ("yank summary for an email", "m"),
+            ("expand/collapse", "z"),
+            ("expand/collapse all", "Z"),
+            ("pin/unpin the first author", "p"),
+            ("articles of the first author", "A"),
+            ("articles of the category", "C"),
+            ("next feed", "Tab"),
+            ("help", "?"),
+            ("back to the feed", "q/Esc"),
+        ],
+    ),
+    (
+        "Search",
+        &[
+            ("move", "↑/↓"),
+            ("move the cursor", "←/→"),
+            ("start/end of the query", "Home/End"),
+            ("delete the previous word", "Ctrl-w"),
+            ("delete to the start", "Ctrl-u"),
+            ("title only", "Ctrl-t"),
+            ("apply to the feed, or fetch an arXiv id", "Enter"),
+            ("cancel", "Esc"),
+        ],
+    ),
+    (
+        "Command",
+        &[("complete", "Tab"), ("run", "Enter"), ("cancel", "Esc")],
+    ),
+    ("Everywhere", &[("quit", "Ctrl-c")]),
+];
+
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    // The status message is only shown until the next key press.
+    app.status_message = None;
+
+    if matches!(key_event.code, KeyCode::Char('c') | KeyCode::Char('C'))
+        && key_event.modifiers == KeyModifiers::CONTROL
+    {
+        app.quit();
+        return Ok(());
+    }
+
+    if app.feed_status != FeedStatus::Ready {
+        return handle_loading_keys(key_event, app);
+    }
+    match app.context {
+        Context::ArticleList => handle_article_list_keys(key_event, app),
+        Context::Pinned => handle_pinned_keys(key_event, app),
+        Context::Export => handle_export_keys(key_event, app),
+        Context::Search => handle_search_keys(key_event, app),
+        Context::Confirm => handle_confirm_keys(key_event, app),
+        Context::Config => handle_config_keys(key_event, app),
+        Context::Watch => handle_watch_keys(key_event, app),
+        Context::AuthorPicker => handle_author_picker_keys(key_event, app),
+        Context::KeywordPrompt => handle_keyword_prompt_keys(key_event, app),
+        Context::Help => handle_help_keys(key_event, app),
+        Context::Stats => handle_stats_keys(key_event, app),
+        Context::Command => handle_command_keys(key_event, app),
+        Context::QueryEditor => handle_query_editor_keys(key_event, app),
+        Context::Error => {
+            app.dismiss_error();
+            Ok(())
+        }
+    }
+}
+
+/// Handles a text pasted in the terminal, inserted at once in the search query.
+pub fn handle_paste(text: &str, app: &mut App) {
+    if app.feed_status == FeedStatus::Ready && app.context == Context::Search {
+        app.search_insert_str(text);
+    }
+}
+
+/// Handles the key events of the help popup.
+fn handle_help_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_popup(1),
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_popup(-1),
+        KeyCode::PageDown => app.scroll_popup(app.popup_page as isize),
+        KeyCode::PageUp => app.scroll_popup(-(app.popup_page as isize)),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => app.close_help(),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events of the stats popup.
+fn handle_stats_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_popup(1),
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_popup(-1),
+        KeyCode::PageDown => app.scroll_popup(app.popup_page as isize),
+        KeyCode::PageUp => app.scroll_popup(-(app.popup_page as isize)),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => app.close_stats(),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events while the feed is loading or failed to load.
+fn handle_loading_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.quit(),
+        KeyCode::Char('r') if matches!(app.feed_status, FeedStatus::Error(_)) => {
+            app.request_refresh();
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events when navigating a pinned feed.
+fn handle_pinned_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Up | KeyCode::Char('k') => app.select_previous(),
+        KeyCode::Down | KeyCode::Char('j') => app.select_next(),
+        KeyCode::Char('g') => app.select_first(),
+        KeyCode::Char('G') => app.select_last(),
+        KeyCode::Char('y') => app.yank_id(),
+        KeyCode::Char('Y') => app.yank_citation(),
+        KeyCode::Char('m') => app.yank_summary_block(),
+        KeyCode::Char('z') => app.toggle_pinned_expanded(),
+        KeyCode::Char('Z') => app.toggle_all_pinned_expanded(),
+        KeyCode::Char('A') => app.search_selected_author(),
+        KeyCode::Char('C') => app.search_selected_category(),
+        KeyCode::Char('p') => app.toggle_pin_first_author(),
+        KeyCode::Tab => app.focus_next_feed(),
+        KeyCode::Char('?') => app.show_help(),
+        KeyCode::Esc | KeyCode::Char('q') => app.set_context(Context::ArticleList),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key following `+`, choosing what to watch.
+fn handle_watch_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Char('a') => app.start_watch_author(),
+        KeyCode::Char('k') => app.start_watch_keyword(),
+        _ => app.set_context(Context::ArticleList),
+    }
+    Ok(())
+}
+
+/// Handles the key events of the picker over the authors of the selected article.
+fn handle_author_picker_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Down | KeyCode::Char('j') => app.author_choice_next(),
+        KeyCode::Up | KeyCode::Char('k') => app.author_choice_previous(),
+        KeyCode::Enter => app.watch_chosen_author(),
+        KeyCode::Esc | KeyCode::Char('q') => app.set_context(Context::ArticleList),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events when typing a keyword to highlight.
+fn handle_keyword_prompt_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Enter => app.watch_typed_keyword(),
+        KeyCode::Esc => app.set_context(Context::ArticleList),
+        KeyCode::Backspace => {
+            app.keyword_input.pop();
+        }
+        KeyCode::Char(c) => app.keyword_input.push(c),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events of the config popup.
+fn handle_config_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    if app.config_editor.is_editing() {
+        match key_event.code {
+            KeyCode::Enter => app.config_commit_edit(),
+            KeyCode::Esc => app.config_editor.cancel(),
+            KeyCode::Backspace => app.config_editor.pop_char(),
+            KeyCode::Char(c) => app.config_editor.push_char(c),
+            _ => {}
+        }
+    } else {
+        match key_event.code {
+            KeyCode::Down | KeyCode::Char('j') => app.config_editor.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => app.config_editor.select_previous(),
+            KeyCode::Enter => app.config_start_edit(),
+            KeyCode::Esc | KeyCode::Char('q') => app.set_context(Context::ArticleList),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Handles the key events of the confirmation popup.
+fn handle_confirm_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm(),
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+            app.set_context(Context::ArticleList)
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events when typing a search query.
+fn handle_search_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Char('t') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.search_toggle_title_only();
+        }
+        KeyCode::Char('w') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.search_delete_word();
+        }
+        KeyCode::Char('u') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.search_delete_to_start();
+        }
+        KeyCode::Esc => app.set_context(Context::ArticleList),
+        KeyCode::Enter => app.search_submit(),
+        KeyCode::Backspace => app.search_pop_char(),
+        KeyCode::Delete => app.search_delete_char(),
+        KeyCode::Left => app.search_cursor_left(),
+        KeyCode::Right => app.search_cursor_right(),
+        KeyCode::Home => app.search_cursor_home(),
+        KeyCode::End => app.search_cursor_end(),
+        KeyCode::Up => app.select_previous(),
+        KeyCode::Down => app.select_next(),
+        KeyCode::Char(c) => app.search_push_char(c),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events when typing a command.
+fn handle_command_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Esc => app.set_context(Context::ArticleList),
+        KeyCode::Enter => app.command_submit(),
+        KeyCode::Backspace => app.command_pop_char(),
+        KeyCode::Tab => app.command_complete(),
+        KeyCode::Char(c) => app.command_push_char(c),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events of the query popup.
+fn handle_query_editor_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        KeyCode::Esc => app.set_context(Context::ArticleList),
+        KeyCode::Enter => app.query_editor_submit(),
+        KeyCode::Down => app.query_editor.select_next(),
+        KeyCode::Up => app.query_editor.select_previous(),
+        KeyCode::Tab => app.query_editor.complete_category(),
+        KeyCode::Backspace => app.query_editor.pop_char(),
+        KeyCode::Char(c) => app.query_editor.push_char(c),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events when choosing the export format.
+fn handle_export_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    match key_event.code {
+        _ if key_event.modifiers != KeyModifiers::NONE => {}
+        KeyCode::Char('b') => app.export(ExportFormat::Bibtex),
+        KeyCode::Char('j') => app.export(ExportFormat::Json),
+        KeyCode::Char('c') => app.export(ExportFormat::Csv),
+        KeyCode::Char('m') => app.export(ExportFormat::Markdown),
+        KeyCode::Char('h') => app.export(ExportFormat::Html),
+        KeyCode::Esc | KeyCode::Char('q') => app.set_context(Context::ArticleList),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the key events when navigating the article feed.
+fn handle_article_list_keys(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
     match key_event.code {
-        // Exit application on `ESC` or `q`
+        // Clear the applied search on `ESC`, else exit application on `ESC` or `q`
+        KeyCode::Esc if app.clear_search() => {}
         KeyCode::Esc | KeyCode::Char('q') => {
             app.quit();
         }
-        // Exit application on `Ctrl-C`
-        KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.quit();
-            }
-        }
         // Counter handlers
         KeyCode::Up | KeyCode::Char('k') => {
             app.select_previous();
@@ -23,19 +343,15 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
         }
         // Movement a la Vim for 10 lines at a time
         // TODO: Make these movements half screen.
-        KeyCode::Char('d') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                for _ in 0..10 {
-                    app.select_next();
-                }
+        KeyCode::Char('d') if key_event.modifiers == KeyModifiers::CONTROL => {
+            for _ in 0..10 {
+                app.select_next();
             }
         }
         // TODO: Make this movement half screen
-        KeyCode::Char('u') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                for _ in 0..10 {
-                    app.select_previous();
-                }
+        KeyCode::Char('u') if key_event.modifiers == KeyModifiers::CONTROL => {
+            for _ in 0..10 {
+                app.select_previous();
             }
         }
         KeyCode::Char('g') => {
@@ -47,9 +363,344 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
         KeyCode::Char('y') => {
             app.yank_id();
         }
+        KeyCode::Char('Y') => {
+            app.yank_citation();
+        }
+        KeyCode::Char('m') => {
+            app.yank_summary_block();
+        }
+        KeyCode::Char('u') => {
+            app.yank_query_url();
+        }
+        KeyCode::Char('S') => {
+            app.cycle_citation_style();
+        }
+        KeyCode::Char('s') => {
+            app.cycle_sort();
+        }
+        KeyCode::Char('x') => {
+            app.cycle_cross_lists();
+        }
+        KeyCode::Char('n') => {
+            app.select_first_new();
+        }
+        KeyCode::Char('p') => {
+            app.toggle_pin_first_author();
+        }
+        KeyCode::Char('N') => {
+            app.toggle_new_only();
+        }
+        KeyCode::Char('Z') => {
+            app.toggle_all_pinned_expanded();
+        }
+        KeyCode::Char('e') => {
+            app.set_context(Context::Export);
+        }
+        KeyCode::Char('/') => {
+            app.start_search();
+        }
+        KeyCode::Char('O') => {
+            app.open_all_visible();
+        }
+        KeyCode::Char('r') => {
+            app.request_refresh();
+        }
+        KeyCode::Char('Q') => {
+            app.open_query_editor();
+        }
+        KeyCode::Char('b') => {
+            app.go_back();
+        }
+        KeyCode::Char('c') => {
+            app.set_context(Context::Config);
+        }
+        KeyCode::Char('+') => {
+            app.set_context(Context::Watch);
+        }
+        KeyCode::Tab => {
+            app.focus_next_feed();
+        }
+        KeyCode::Char('A') => {
+            app.search_selected_author();
+        }
+        KeyCode::Char('C') => {
+            app.search_selected_category();
+        }
+        KeyCode::Char('i') => {
+            app.show_stats();
+        }
+        KeyCode::Char('?') => {
+            app.show_help();
+        }
+        KeyCode::Char(':') => {
+            app.start_command();
+        }
 
         // Other handlers you could add here.
         _ => {}
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arxiv::{ArxivEntry, ArxivQuery, ArxivQueryResult, SearchQuery, SortBy, SortOrder};
+    use crate::config::Config;
+    use crate::tools::{ClipboardWriter, UrlOpener};
+    use crate::ui::Theme;
+    use std::io;
+
+    fn feed() -> ArxivQueryResult {
+        let articles = [
+            "Surface codes",
+            "Quantum surface states",
+            "Graph neural networks",
+        ]
+        .iter()
+        .map(|title| {
+            ArxivEntry::new(
+                title.to_string(),
+                vec!["Author".to_string()],
+                String::new(),
+                "http://arxiv.org/abs/0000.00000".to_string(),
+                "2024-01-01T00:00:00Z".to_string(),
+                "2024-01-01T00:00:00Z".to_string(),
+            )
+        })
+        .collect();
+        ArxivQueryResult {
+            updated: "2024-01-01T00:00:00Z".to_string(),
+            total_results: None,
+            articles,
+        }
+    }
+
+    /// Clipboard keeping the copied texts.
+    #[derive(Debug, Default)]
+    struct RecordingClipboard(Vec<String>);
+
+    impl ClipboardWriter for RecordingClipboard {
+        fn set_text(&mut self, text: String) -> io::Result<()> {
+            self.0.push(text);
+            Ok(())
+        }
+    }
+
+    /// Records the opened urls instead of launching a browser.
+    #[derive(Debug, Default)]
+    struct RecordingOpener(Vec<String>);
+
+    impl UrlOpener for RecordingOpener {
+        fn open(&mut self, url: &str) -> io::Result<()> {
+            self.0.push(url.to_string());
+            Ok(())
+        }
+    }
+
+    fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+        handle_key_events(KeyEvent::new(code, modifiers), app).unwrap();
+    }
+
+    fn type_str(app: &mut App, text: &str) {
+        text.chars()
+            .for_each(|c| press(app, KeyCode::Char(c), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_search_line_editing() {
+        let feed = feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        press(&mut app, KeyCode::Char('/'), KeyModifiers::NONE);
+        type_str(&mut app, "graph surface");
+        assert_eq!(app.search_state.filtered_indices, Vec::<usize>::new());
+
+        press(&mut app, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(app.search_state.query, "graph ");
+        assert_eq!(app.search_state.filtered_indices, vec![2]);
+
+        type_str(&mut app, "quantum surface");
+        (0..7).for_each(|_| press(&mut app, KeyCode::Left, KeyModifiers::NONE));
+        press(&mut app, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(app.search_state.query, "surface");
+        assert_eq!(app.search_state.filtered_indices.len(), 2);
+        assert_eq!(app.context, Context::Search);
+    }
+
+    #[test]
+    fn test_applied_search() {
+        let feed = feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        press(&mut app, KeyCode::Char('/'), KeyModifiers::NONE);
+        type_str(&mut app, "surface");
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE);
+
+        // Enter keeps the feed filtered, with the selection on the same article.
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.context, Context::ArticleList);
+        assert_eq!(app.search_state.filtered_indices, vec![0, 1]);
+        assert_eq!(app.get_actual_article_index(), Some(1));
+        press(&mut app, KeyCode::Char('k'), KeyModifiers::NONE);
+        assert_eq!(app.get_actual_article_index(), Some(0));
+
+        // / edits the applied search, at its end.
+        press(&mut app, KeyCode::Char('/'), KeyModifiers::NONE);
+        type_str(&mut app, " states");
+        assert_eq!(app.search_state.query, "surface states");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.search_state.filtered_indices, vec![1]);
+
+        // Esc clears it, then quits.
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(!app.search_state.is_active());
+        assert_eq!(app.search_state.filtered_count(), 3);
+        assert!(app.running);
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_ctrl_c_quits_the_export_prompt() {
+        let feed = feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        press(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+        assert_eq!(app.context, Context::Export);
+
+        press(&mut app, KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert!(!app.running);
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn test_ctrl_c_quits_everywhere() {
+        let feed = feed();
+        let config = Config::default();
+        for context in [
+            Context::ArticleList,
+            Context::Export,
+            Context::Config,
+            Context::Confirm,
+            Context::Watch,
+            Context::AuthorPicker,
+            Context::KeywordPrompt,
+            Context::Search,
+            Context::Error,
+        ] {
+            let mut app = App::new(&feed, &config, Theme::default());
+            app.set_context(context);
+            press(&mut app, KeyCode::Char('c'), KeyModifiers::CONTROL);
+            assert!(!app.running, "Ctrl-c did not quit in {context:?}");
+        }
+    }
+
+    /// Key presses of each key written in the help, e.g. `j/k ↑/↓` or `+a/+k`.
+    fn help_keys(keys: &str) -> Vec<Vec<KeyEvent>> {
+        keys.split_whitespace()
+            .flat_map(|word| match word {
+                "/" => vec![word],
+                _ => word.split('/').collect(),
+            })
+            .map(|key| {
+                let code = match key {
+                    "↑" => KeyCode::Up,
+                    "↓" => KeyCode::Down,
+                    "←" => KeyCode::Left,
+                    "→" => KeyCode::Right,
+                    "Home" => KeyCode::Home,
+                    "End" => KeyCode::End,
+                    "Enter" => KeyCode::Enter,
+                    "Esc" => KeyCode::Esc,
+                    "Tab" => KeyCode::Tab,
+                    _ => match key.strip_prefix("Ctrl-") {
+                        Some(c) => {
+                            let c = c.chars().next().unwrap();
+                            return vec![KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)];
+                        }
+                        None => {
+                            return key
+                                .chars()
+                                .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+                                .collect()
+                        }
+                    },
+                };
+                vec![KeyEvent::new(code, KeyModifiers::NONE)]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_help_keys_are_handled() {
+        let mut feed = feed();
+        for article in &mut feed.articles {
+            article.primary_category = Some("cs.LG".to_string());
+        }
+        let mut config = Config::default();
+        config.pinned.authors = Some(vec!["Author".to_string()]);
+        for (section, shortcuts) in KEY_HELP {
+            for (action, keys) in *shortcuts {
+                for presses in help_keys(keys) {
+                    // A fresh app in the context of the section, with something to do for
+                    // every key: the selection in the middle of the feed and the cursor in
+                    // the middle of the query.
+                    let mut app = App::new(&feed, &config, Theme::default());
+                    app.clipboard = Box::<RecordingClipboard>::default();
+                    app.opener = Box::<RecordingOpener>::default();
+                    app.query = Some(ArxivQuery {
+                        search_queries: vec![SearchQuery::Category("quant-ph".to_string())],
+                        start_index: 0,
+                        max_results: 50,
+                        sort_by: SortBy::SubmittedDate,
+                        sort_order: SortOrder::Descending,
+                        id_list: Vec::new(),
+                    });
+                    press(&mut app, KeyCode::Char('j'), KeyModifiers::NONE);
+                    match *section {
+                        "Pinned feeds" => {
+                            press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+                            press(&mut app, KeyCode::Char('j'), KeyModifiers::NONE);
+                        }
+                        "Search" => {
+                            press(&mut app, KeyCode::Char('/'), KeyModifiers::NONE);
+                            type_str(&mut app, "ae");
+                            press(&mut app, KeyCode::Down, KeyModifiers::NONE);
+                            press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+                        }
+                        "Command" => type_str(&mut app, ":so"),
+                        _ => {}
+                    }
+                    let before = format!("{app:?}");
+                    presses
+                        .into_iter()
+                        .for_each(|key_event| handle_key_events(key_event, &mut app).unwrap());
+                    assert_ne!(
+                        format!("{app:?}"),
+                        before,
+                        "{keys} ({action}) does nothing in {section}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_paste_in_search() {
+        let feed = feed();
+        let config = Config::default();
+        let mut app = App::new(&feed, &config, Theme::default());
+        handle_paste("surface", &mut app);
+        assert_eq!(app.search_state.query, "");
+
+        press(&mut app, KeyCode::Char('/'), KeyModifiers::NONE);
+        type_str(&mut app, "codes");
+        press(&mut app, KeyCode::Home, KeyModifiers::NONE);
+        handle_paste("surface ", &mut app);
+        assert_eq!(app.search_state.query, "surface codes");
+        assert_eq!(app.search_state.filtered_indices, vec![0]);
+        press(&mut app, KeyCode::End, KeyModifiers::NONE);
+        assert_eq!(app.search_state.cursor(), app.search_state.query.len());
+    }
+}
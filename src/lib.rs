@@ -16,8 +16,29 @@ pub mod handler;
 /// Searching keyword
 pub mod search_highlight;
 
+/// Fuzzy search through the feed
+pub mod search;
+
 /// Handling config
 pub mod config;
 
 /// Arxiv tools
 pub mod arxiv;
+
+/// Formatting the dates
+pub mod date;
+
+/// Persisting the session state
+pub mod session;
+
+/// Exporting the feed
+pub mod export;
+
+/// Launching external tools
+pub mod tools;
+
+/// Editing the config from the interface
+pub mod editor;
+
+/// Statistics of the feed
+pub mod stats;
@@ -1,14 +1,25 @@
-use arxivlens::app::{App, AppResult};
-use arxivlens::arxiv::{get_query_url, ArxivQueryResult, SearchQuery, SortBy, SortOrder};
+use arxivlens::app::{App, AppResult, FeedStatus};
+use arxivlens::arxiv::categories::{check_category, format_category_list};
+use arxivlens::arxiv::{
+    ArxivClient, ArxivEntry, ArxivQuery, ArxivQueryError, ArxivQueryResult, RequestScheduler,
+    ReqwestClient, SearchQuery, SortBy, SortOrder, ARXIV_MAX_RESULTS,
+};
 use arxivlens::config;
-use arxivlens::event::{Event, EventHandler};
-use arxivlens::handler::handle_key_events;
+use arxivlens::event::{Event, EventHandler, TICK_RATE};
+use arxivlens::export::{
+    export_articles, to_html_digest, to_markdown_digest, DigestOptions, ExportFormat,
+};
+use arxivlens::handler::{handle_key_events, handle_paste};
+use arxivlens::session::{self, Session};
 use arxivlens::tui::Tui;
 use arxivlens::ui::Theme;
 use clap::Parser;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
 /// Default values for the query:
 const DEFAULT_START_INDEX: i32 = 0;
@@ -16,55 +27,385 @@ const DEFAULT_MAX_RESULTS: i32 = 200;
 const DEFAULT_SORT_ORDER: SortOrder = SortOrder::Descending;
 const DEFAULT_SORT_BY: SortBy = SortBy::SubmittedDate;
 
+/// Exit codes of the non-interactive mode:
+const EXIT_NETWORK_FAILURE: i32 = 2;
+const EXIT_NO_RESULTS: i32 = 3;
+const EXIT_FORMAT_FAILURE: i32 = 4;
+
 /// Terminal User Interface to explore arXiv
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
-    /// Name of the author to look
-    #[arg(short, long, default_value = None)]
-    author: Option<String>,
+    /// Name of the author to look (repeatable, any of them matches)
+    #[arg(short, long)]
+    author: Vec<String>,
+
+    /// Category to look (repeatable, any of them matches)
+    #[arg(short, long)]
+    category: Vec<String>,
+
+    /// Terms to look for in the title (repeatable)
+    #[arg(short, long)]
+    title: Vec<String>,
+
+    /// Terms to look for in the abstract (repeatable)
+    #[arg(long = "abstract")]
+    abstract_terms: Vec<String>,
+
+    /// Terms to look for in the comments, e.g. "accepted at NeurIPS" (repeatable)
+    #[arg(long)]
+    comment: Vec<String>,
+
+    /// Terms to look for in the journal reference, e.g. the venue (repeatable)
+    #[arg(long)]
+    journal: Vec<String>,
+
+    /// Report number of the article, e.g. from a lab or institution (repeatable)
+    #[arg(long)]
+    report_number: Vec<String>,
+
+    /// Terms to look for in all the fields, loosely matching (repeatable)
+    #[arg(short, long)]
+    query: Vec<String>,
+
+    /// Number of articles to fetch [default: 200]
+    #[arg(long, value_parser = clap::value_parser!(i32).range(1..=ARXIV_MAX_RESULTS as i64))]
+    max_results: Option<i32>,
+
+    /// Sorting of the articles: relevance, submitted or updated [default: submitted]
+    #[arg(long)]
+    sort_by: Option<SortBy>,
+
+    /// Order of the sorting: asc or desc [default: desc]
+    #[arg(long)]
+    sort_order: Option<SortOrder>,
+
+    /// Path of the config file, instead of the one in the XDG config directory
+    #[arg(long, env = "ARXIVLENS_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Profile of the config file to use, defined in a [profile.<name>] table
+    #[arg(long, env = "ARXIVLENS_PROFILE")]
+    profile: Option<String>,
+
+    /// Do not restore the selection of the previous session
+    #[arg(long)]
+    no_restore: bool,
+
+    /// Print the results in the given format and exit, without starting the interface
+    #[arg(long, value_enum)]
+    format: Option<ExportFormat>,
+
+    /// Write a Markdown digest of the results to the file and exit, as HTML if the file
+    /// ends with .html
+    #[arg(long, value_name = "PATH", conflicts_with = "format")]
+    digest: Option<PathBuf>,
+
+    /// Print the arXiv categories with their description and exit
+    #[arg(long, visible_alias = "category-list")]
+    list_categories: bool,
+
+    /// Query the categories given with -c even if they are not in the list of
+    /// --list-categories, e.g. a category added by arXiv since this version
+    #[arg(long)]
+    allow_unknown_category: bool,
+
+    /// Write a commented default config file and exit
+    #[arg(long)]
+    init_config: bool,
+
+    /// Overwrite an existing config file with --init-config
+    #[arg(long, requires = "init_config")]
+    force: bool,
+}
+
+/// Run the query for the non-interactive mode, reporting the failures.
+///
+/// Returns the exit code on failure: network failures and empty results are
+/// distinguished so the command can be used in scripts.
+fn fetch_articles(client: &dyn ArxivClient, query: &str) -> Result<Vec<ArxivEntry>, i32> {
+    let query_result = match ArxivQueryResult::from_query(client, query) {
+        Ok(query_result) => query_result,
+        Err(e) => {
+            eprintln!("Problem while querying arXiv: {e}");
+            return Err(EXIT_NETWORK_FAILURE);
+        }
+    };
+    if query_result.articles.is_empty() {
+        eprintln!("No articles found.");
+        return Err(EXIT_NO_RESULTS);
+    }
+    Ok(query_result.articles)
+}
+
+/// Run the query and print the entries on stdout in the given format.
+///
+/// Returns the exit code, see [`fetch_articles`].
+fn print_query_result(
+    client: &dyn ArxivClient,
+    query: &str,
+    format: ExportFormat,
+    digest: &DigestOptions,
+) -> i32 {
+    let articles = match fetch_articles(client, query) {
+        Ok(articles) => articles,
+        Err(code) => return code,
+    };
+    match export_articles(&articles, format, digest) {
+        Ok(output) => {
+            print!("{output}");
+            0
+        }
+        Err(e) => {
+            eprintln!("Could not format the articles: {e}");
+            EXIT_FORMAT_FAILURE
+        }
+    }
+}
 
-    /// Number of times to greet
-    #[arg(short, long, default_value = None)]
-    category: Option<String>,
+/// Run the query and write the digest of the entries to `path`, in HTML if it ends with
+/// `.html` and in Markdown otherwise.
+///
+/// Returns the exit code, see [`fetch_articles`].
+fn write_digest(client: &dyn ArxivClient, query: &str, path: &Path, digest: &DigestOptions) -> i32 {
+    let articles = match fetch_articles(client, query) {
+        Ok(articles) => articles,
+        Err(code) => return code,
+    };
+    let content = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("html" | "htm") => to_html_digest(&articles, digest),
+        _ => to_markdown_digest(&articles, digest),
+    };
+    match std::fs::write(path, content) {
+        Ok(()) => {
+            println!(
+                "Digest of {} articles written to {}",
+                articles.len(),
+                path.display()
+            );
+            0
+        }
+        Err(e) => {
+            eprintln!("Could not write the digest: {e}");
+            1
+        }
+    }
+}
+
+/// Write the default config to `path`, or to the XDG directory if none is given.
+///
+/// Returns the exit code.
+fn init_config(path: Option<&Path>, force: bool) -> i32 {
+    let path = match config::Config::path(path) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Could not locate the config file: {e}");
+            return 1;
+        }
+    };
+    match config::Config::init_file(&path, force) {
+        Ok(()) => {
+            println!("Config written to {}", path.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("Could not write the config: {e}");
+            1
+        }
+    }
+}
+
+/// Check the categories given on the command line, unless any category is allowed.
+fn check_categories(args: &Args) -> Result<(), String> {
+    if args.allow_unknown_category {
+        return Ok(());
+    }
+    args.category
+        .iter()
+        .try_for_each(|category| check_category(category))
+        .map_err(|e| format!("{e} (or pass --allow-unknown-category)"))
+}
+
+/// Translate the user args into the search queries, using the config for the defaults.
+fn build_queries(args: &Args, config: &config::Config) -> Vec<SearchQuery> {
+    let mut queries: Vec<SearchQuery> = Vec::new();
+
+    let authors = args.author.iter().cloned().map(SearchQuery::Author);
+    queries.extend(SearchQuery::any_of(authors.collect()));
+
+    let categories = if args.category.is_empty() {
+        &config.query.categories
+    } else {
+        &args.category
+    };
+    let categories = categories.iter().cloned().map(SearchQuery::Category);
+    queries.extend(SearchQuery::any_of(categories.collect()));
+
+    queries.extend(args.title.iter().cloned().map(SearchQuery::Title));
+    queries.extend(
+        args.abstract_terms
+            .iter()
+            .cloned()
+            .map(SearchQuery::Abstract),
+    );
+    queries.extend(args.comment.iter().cloned().map(SearchQuery::Comment));
+    queries.extend(
+        args.journal
+            .iter()
+            .cloned()
+            .map(SearchQuery::JournalReference),
+    );
+    queries.extend(
+        args.report_number
+            .iter()
+            .cloned()
+            .map(SearchQuery::ReportNumber),
+    );
+    queries.extend(args.query.iter().cloned().map(SearchQuery::All));
+
+    queries
+}
+
+/// Options of the query: the command line takes precedence over the config, which takes
+/// precedence over the defaults.
+fn resolve_query_options(args: &Args, config: &config::Config) -> (i32, SortBy, SortOrder) {
+    let max_results = args
+        .max_results
+        .or(config.query.max_results)
+        .unwrap_or(DEFAULT_MAX_RESULTS);
+    let sort_by = args
+        .sort_by
+        .or(config.query.sort_by)
+        .unwrap_or(DEFAULT_SORT_BY);
+    let sort_order = args
+        .sort_order
+        .or(config.query.sort_order)
+        .unwrap_or(DEFAULT_SORT_ORDER);
+    (max_results, sort_by, sort_order)
+}
+
+/// Run the query in a background thread, so the interface keeps drawing meanwhile, once
+/// the wait given by the scheduler is over.
+fn spawn_fetch(
+    query_url: String,
+    client: &Arc<dyn ArxivClient>,
+    scheduler: &mut RequestScheduler,
+) -> mpsc::Receiver<Result<String, ArxivQueryError>> {
+    let (sender, receiver) = mpsc::channel();
+    let client = Arc::clone(client);
+    let wait = scheduler.reserve(Instant::now());
+    std::thread::spawn(move || {
+        std::thread::sleep(wait);
+        // The receiver is gone if the user quit meanwhile.
+        let _ = sender.send(client.fetch(&query_url));
+    });
+    receiver
 }
 
 fn main() -> AppResult<()> {
     // --- Construct the arXiv query with the user args ---
     let args = Args::parse();
-    let config = config::Config::load();
+    if args.list_categories {
+        print!("{}", format_category_list());
+        return Ok(());
+    }
+    if args.init_config {
+        std::process::exit(init_config(args.config.as_deref(), args.force));
+    }
+    if let Err(e) = check_categories(&args) {
+        eprintln!("Invalid category: {e}");
+        std::process::exit(1);
+    }
+    let (config, config_warnings) =
+        match config::Config::load_path(args.config.as_deref(), args.profile.as_deref()) {
+            Ok((config, warnings)) => {
+                for warning in &warnings {
+                    eprintln!("Warning: {warning}");
+                }
+                (config, warnings)
+            }
+            Err(e) => {
+                eprintln!("Invalid config: {e}");
+                std::process::exit(1);
+            }
+        };
 
-    // TODO: Get the them out of the config:
-    let theme = Theme::default();
+    let theme = match Theme::from_config(&config.ui) {
+        Ok(theme) => theme,
+        Err(e) => {
+            eprintln!("Invalid config: {e}");
+            std::process::exit(1);
+        }
+    };
 
-    //
-    let mut queries: Vec<SearchQuery> = Vec::new();
+    let client: Arc<dyn ArxivClient> = Arc::new(ReqwestClient::new(config.query.user_agent()));
+    let mut scheduler = RequestScheduler::new(config.query.request_delay());
+
+    let (max_results, sort_by, sort_order) = resolve_query_options(&args, &config);
+    let arxiv_query = ArxivQuery {
+        search_queries: build_queries(&args, &config),
+        start_index: DEFAULT_START_INDEX,
+        max_results,
+        sort_by,
+        sort_order,
+        id_list: Vec::new(),
+    };
+
+    // --- Query the arxiv API ---
+    let query = arxiv_query.url();
 
-    if let Some(author) = &args.author {
-        queries.push(SearchQuery::Author(author.to_string()))
+    // Non-interactive mode: print the results, or write their digest, and exit.
+    let digest = config.export.digest_options();
+    if let Some(format) = args.format {
+        std::process::exit(print_query_result(client.as_ref(), &query, format, &digest));
     }
-    if let Some(category) = &args.category {
-        queries.push(SearchQuery::Category(category.to_string()))
-    } else {
-        queries.push(SearchQuery::Category(config.query.category))
+    if let Some(path) = &args.digest {
+        std::process::exit(write_digest(client.as_ref(), &query, path, &digest));
     }
 
-    // --- Query the arxiv API ---
-    let query = get_query_url(
-        Some(&queries),
-        Some(DEFAULT_START_INDEX),
-        Some(DEFAULT_MAX_RESULTS),
-        Some(DEFAULT_SORT_BY),
-        Some(DEFAULT_SORT_ORDER),
-    );
-    let query_result = ArxivQueryResult::from_query(query);
-    // Create an application.
-    let mut app = App::new(&query_result, &config.highlight, theme);
-  
+    // Create an application, showing the loading screen until the query returns.
+    let no_articles = ArxivQueryResult::default();
+    let mut app = App::new(&no_articles, &config, theme);
+    app.query = Some(arxiv_query);
+    app.config_path = config::Config::path(args.config.as_deref()).ok();
+    app.profile = args.profile.clone();
+    app.config_warnings = config_warnings;
+    app.start_loading();
+    let mut fetch = Some(spawn_fetch(query.clone(), &client, &mut scheduler));
+    let mut prefetch = None;
+
+    let previous_session = Session::load();
+    // Mark the articles published since the previous run of the query.
+    let terms = app
+        .query
+        .as_ref()
+        .map(ArxivQuery::terms)
+        .unwrap_or_default();
+    if config.ui.new_badge.unwrap_or(true) {
+        app.mark_new_since(session::new_since(
+            previous_session.as_ref(),
+            &terms,
+            config.ui.new_on_first_run.unwrap_or(false),
+        ));
+        if config.ui.new_only.unwrap_or(false) {
+            app.toggle_new_only();
+        }
+    }
+    let last_runs = previous_session
+        .as_ref()
+        .map(|s| s.last_runs.clone())
+        .unwrap_or_default();
+    // Restore the previous session with the first articles, if the same query is run.
+    app.pending_session = previous_session.filter(|s| !args.no_restore && s.matches_query(&query));
+
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new();
+    let tick_rate = config
+        .ui
+        .tick_rate
+        .map_or(TICK_RATE, |millis| Duration::from_millis(millis.max(1)));
+    let events = EventHandler::new(tick_rate);
     let mut tui = Tui::new(terminal, events);
     tui.init()?;
 
@@ -74,13 +415,298 @@ fn main() -> AppResult<()> {
         tui.draw(&mut app)?;
         // Handle events.
         match tui.events.next()? {
-            Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
+            Event::Key(key_event) => {
+                // Show the error instead of quitting the application.
+                if let Err(e) = handle_key_events(key_event, &mut app) {
+                    app.status_message = Some(format!("Error: {e}"));
+                }
+            }
+            Event::Paste(text) => handle_paste(&text, &mut app),
             Event::Mouse(_) => {}
             Event::Resize(_, _) => {}
+            Event::Tick => app.tick(),
+        }
+        // Run the query again if asked.
+        if let Some(refresh_query) = app.take_refresh_query() {
+            fetch = Some(spawn_fetch(refresh_query, &client, &mut scheduler));
+        }
+        // Show the result of the query once it arrives.
+        if let Some(result) = fetch.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            fetch = None;
+            app.finish_refresh(
+                result.and_then(|content| ArxivQueryResult::from_xml_content(&content)),
+            );
+        }
+        // Fetch the next articles in the background when nearing the end of the feed.
+        if let Some((start, prefetch_query)) = app.take_prefetch_query() {
+            prefetch = Some((start, spawn_fetch(prefetch_query, &client, &mut scheduler)));
+        }
+        if let Some((start, result)) = prefetch
+            .as_ref()
+            .and_then(|(start, receiver)| Some((*start, receiver.try_recv().ok()?)))
+        {
+            prefetch = None;
+            app.finish_prefetch(
+                start,
+                result.and_then(|content| ArxivQueryResult::from_xml_content(&content)),
+            );
         }
     }
 
     // Exit the user interface.
     tui.exit()?;
+
+    // Persist the session for the next run, the query may have changed in between. The
+    // feed was never seen if the query did not return, the previous session is kept.
+    if app.feed_status != FeedStatus::Ready {
+        return Ok(());
+    }
+    let query = app.query.as_ref().map_or(query, ArxivQuery::url);
+    let mut session = app.session(&query);
+    session.last_runs = last_runs;
+    session.record_run(&terms, session::now_timestamp());
+    if let Err(e) = session.save() {
+        eprintln!("Could not save the session: {e}");
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arxivlens::arxiv::get_query_url;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse_from(std::iter::once("arxivlens").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_build_queries_default() {
+        let queries = build_queries(&parse(&[]), &config::Config::default());
+        assert_eq!(queries, vec![SearchQuery::Category("quant-ph".to_string())]);
+    }
+
+    #[test]
+    fn test_build_queries_repeated_title() {
+        let args = parse(&["-t", "surface code", "--title", "decoder"]);
+        let queries = build_queries(&args, &config::Config::default());
+        let url = get_query_url(Some(&queries), None, None, None, None);
+        assert!(url.ends_with("search_query=cat:quant-ph+AND+ti:%22surface+code%22+AND+ti:decoder"));
+    }
+
+    #[test]
+    fn test_check_categories() {
+        assert_eq!(
+            check_categories(&parse(&["-c", "quant-ph", "-c", "cs.LG"])),
+            Ok(())
+        );
+        assert_eq!(
+            check_categories(&parse(&["-c", "quant-ph", "-c", "quantph"])),
+            Err(
+                "unknown arXiv category `quantph`, did you mean `quant-ph`? \
+                 (or pass --allow-unknown-category)"
+                    .to_string()
+            )
+        );
+        let args = parse(&["-c", "quantph", "--allow-unknown-category"]);
+        assert_eq!(check_categories(&args), Ok(()));
+    }
+
+    #[test]
+    fn test_build_queries_repeated_category_and_author() {
+        let args = parse(&[
+            "-c",
+            "quant-ph",
+            "-c",
+            "cond-mat.mes-hall",
+            "-a",
+            "J Doe",
+            "-a",
+            "A Smith",
+        ]);
+        assert_eq!(args.category, vec!["quant-ph", "cond-mat.mes-hall"]);
+        assert_eq!(args.author, vec!["J Doe", "A Smith"]);
+
+        let queries = build_queries(&args, &config::Config::default());
+        let url = get_query_url(Some(&queries), None, None, None, None);
+        assert!(url.ends_with(
+            "search_query=%28au:%22J+Doe%22+OR+au:%22A+Smith%22%29\
+             +AND+%28cat:quant-ph+OR+cat:cond-mat.mes-hall%29"
+        ));
+    }
+
+    #[test]
+    fn test_build_queries_config_categories() {
+        let mut config = config::Config::default();
+        config.query.categories = vec!["quant-ph".to_string(), "cs.LG".to_string()];
+        let queries = build_queries(&parse(&[]), &config);
+        assert_eq!(
+            queries,
+            vec![SearchQuery::AnyOf(vec![
+                SearchQuery::Category("quant-ph".to_string()),
+                SearchQuery::Category("cs.LG".to_string()),
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_build_queries_all_fields() {
+        let args = parse(&["--query", "entanglement"]);
+        let queries = build_queries(&args, &config::Config::default());
+        let url = get_query_url(Some(&queries), None, None, None, None);
+        assert!(url.contains("all:entanglement"));
+    }
+
+    #[test]
+    fn test_build_queries_comment() {
+        let args = parse(&["--comment", "accepted at NeurIPS"]);
+        let queries = build_queries(&args, &config::Config::default());
+        let url = get_query_url(Some(&queries), None, None, None, None);
+        assert!(url.ends_with("search_query=cat:quant-ph+AND+cm:%22accepted+at+NeurIPS%22"));
+    }
+
+    #[test]
+    fn test_build_queries_journal() {
+        let args = parse(&["--journal", "Phys. Rev. Lett.", "-t", "qubit"]);
+        let queries = build_queries(&args, &config::Config::default());
+        let url = get_query_url(Some(&queries), None, None, None, None);
+        assert!(
+            url.ends_with("search_query=cat:quant-ph+AND+jr:%22Phys.+Rev.+Lett.%22+AND+ti:qubit")
+        );
+    }
+
+    #[test]
+    fn test_build_queries_report_number() {
+        let args = parse(&["--report-number", "CERN-TH-2024-001"]);
+        let queries = build_queries(&args, &config::Config::default());
+        let url = get_query_url(Some(&queries), None, None, None, None);
+        assert!(url.ends_with("search_query=cat:quant-ph+AND+rn:CERN-TH-2024-001"));
+    }
+
+    #[test]
+    fn test_build_queries_everything() {
+        let args = parse(&[
+            "-a",
+            "Doe",
+            "-c",
+            "cs.LG",
+            "-t",
+            "diffusion",
+            "--abstract",
+            "score matching",
+            "-q",
+            "generative",
+        ]);
+        let queries = build_queries(&args, &config::Config::default());
+        assert_eq!(
+            queries,
+            vec![
+                SearchQuery::Author("Doe".to_string()),
+                SearchQuery::Category("cs.LG".to_string()),
+                SearchQuery::Title("diffusion".to_string()),
+                SearchQuery::Abstract("score matching".to_string()),
+                SearchQuery::All("generative".to_string()),
+            ]
+        );
+        let url = get_query_url(Some(&queries), None, None, None, None);
+        assert!(url.ends_with(
+            "search_query=abs:%22score+matching%22+AND+all:generative+AND+au:Doe\
+             +AND+cat:cs.LG+AND+ti:diffusion"
+        ));
+    }
+
+    #[test]
+    fn test_query_options_defaults() {
+        let options = resolve_query_options(&parse(&[]), &config::Config::default());
+        assert_eq!(
+            options,
+            (DEFAULT_MAX_RESULTS, DEFAULT_SORT_BY, DEFAULT_SORT_ORDER)
+        );
+    }
+
+    #[test]
+    fn test_query_options_precedence() {
+        let mut config = config::Config::default();
+        config.query.max_results = Some(50);
+        config.query.sort_by = Some(SortBy::Relevance);
+
+        // The config overrides the defaults.
+        let options = resolve_query_options(&parse(&[]), &config);
+        assert_eq!(options, (50, SortBy::Relevance, DEFAULT_SORT_ORDER));
+
+        // The command line overrides the config.
+        let args = parse(&[
+            "--max-results",
+            "10",
+            "--sort-by",
+            "updated",
+            "--sort-order",
+            "asc",
+        ]);
+        let options = resolve_query_options(&args, &config);
+        assert_eq!(options, (10, SortBy::LastUpdatedDate, SortOrder::Ascending));
+    }
+
+    #[test]
+    fn test_query_options_invalid_args() {
+        let parse = |args: &[&str]| {
+            Args::try_parse_from(std::iter::once("arxivlens").chain(args.iter().copied()))
+        };
+        assert!(parse(&["--max-results", "5000"]).is_err());
+        let error = parse(&["--sort-by", "submited"]).unwrap_err();
+        assert!(error.to_string().contains("relevance, submitted, updated"));
+    }
+
+    #[test]
+    fn test_config_path_flag_and_env() {
+        assert_eq!(parse(&[]).config, None);
+
+        let args = parse(&["--config", "/tmp/flag.toml"]);
+        assert_eq!(args.config, Some(PathBuf::from("/tmp/flag.toml")));
+
+        // The environment variables stand for the absent flags, read by clap.
+        let command = <Args as clap::CommandFactory>::command();
+        let env = |id: &str| {
+            command
+                .get_arguments()
+                .find(|arg| arg.get_id() == id)
+                .and_then(|arg| arg.get_env())
+                .map(|env| env.to_string_lossy().to_string())
+        };
+        assert_eq!(env("config").as_deref(), Some("ARXIVLENS_CONFIG"));
+        assert_eq!(env("profile").as_deref(), Some("ARXIVLENS_PROFILE"));
+
+        // The tests share the environment, so instead of setting the variables the command
+        // reads the ones cargo sets for the tests.
+        let command = command
+            .mut_arg("config", |arg| arg.env("CARGO_MANIFEST_DIR"))
+            .mut_arg("profile", |arg| arg.env("CARGO_PKG_NAME"));
+        let parse_with_env = |args: &[&str]| {
+            let matches = command
+                .clone()
+                .try_get_matches_from(std::iter::once("arxivlens").chain(args.iter().copied()))
+                .unwrap();
+            <Args as clap::FromArgMatches>::from_arg_matches(&matches).unwrap()
+        };
+        let args = parse_with_env(&[]);
+        assert_eq!(args.config, Some(PathBuf::from(env!("CARGO_MANIFEST_DIR"))));
+        assert_eq!(args.profile.as_deref(), Some(env!("CARGO_PKG_NAME")));
+
+        // The flags win over the environment.
+        let args = parse_with_env(&["--config", "/tmp/flag.toml", "--profile", "ml"]);
+        assert_eq!(args.config, Some(PathBuf::from("/tmp/flag.toml")));
+        assert_eq!(args.profile.as_deref(), Some("ml"));
+
+        // Unlike the XDG file, the file given must exist.
+        let missing = Path::new(env!("CARGO_MANIFEST_DIR")).join("missing-config.toml");
+        let error = config::Config::load_path(Some(&missing), None).unwrap_err();
+        assert!(
+            matches!(&error, config::ConfigError::MissingFile(path) if path == &missing),
+            "{error}"
+        );
+        assert_eq!(
+            error.to_string(),
+            format!("the config file {} does not exist", missing.display())
+        );
+    }
+}
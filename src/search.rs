@@ -0,0 +1,1169 @@
+//! Fuzzy searching through the articles of the feed.
+//!
+//! The [`SearchEngine`] wraps the [`nucleo_matcher`] fuzzy matcher, and the [`SearchState`]
+//! holds the query typed by the user together with the indices of the matching articles.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use serde::{Deserialize, Serialize};
+
+use crate::arxiv::ArxivEntry;
+
+/// Queries up to this number of characters highlight every matched character, longer
+/// ones only highlight the runs of consecutive matched characters.
+const SCATTERED_HIGHLIGHT_MAX_QUERY_LEN: usize = 2;
+/// Minimum length of a run of matched characters to be highlighted.
+const MIN_HIGHLIGHT_RUN_LEN: usize = 2;
+
+/// Keep the runs of consecutive indices of at least `min_len` indices.
+///
+/// Loose queries match single characters scattered across the text, which are noise
+/// rather than a hint of why the article matched.
+fn contiguous_runs(indices: &[u32], min_len: usize) -> Vec<u32> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for end in 1..=indices.len() {
+        if end == indices.len() || indices[end] != indices[end - 1] + 1 {
+            if end - start >= min_len {
+                runs.extend_from_slice(&indices[start..end]);
+            }
+            start = end;
+        }
+    }
+    runs
+}
+
+/// Fields of an article against which the query is matched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFields {
+    pub title: String,
+    /// Authors separated by commas, empty if they are not searched.
+    pub authors: String,
+    pub summary: String,
+    /// Title, authors and summary together, so a query can span them.
+    combined: String,
+}
+
+impl SearchFields {
+    pub fn new(title: &str, authors: &str, summary: &str) -> Self {
+        let combined = if authors.is_empty() {
+            format!("{title} {summary}")
+        } else {
+            format!("{title} {authors} {summary}")
+        };
+        Self {
+            title: title.to_string(),
+            authors: authors.to_string(),
+            summary: summary.to_string(),
+            combined,
+        }
+    }
+}
+
+/// Order of the articles in the feed, applied on top of the search.
+///
+/// Only the indices of the matching articles are sorted, the articles keep their place in
+/// the feed so the indices pointing to them stay valid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    /// Order of arXiv, or the best matches first while searching.
+    #[default]
+    Feed,
+    /// Most recently published first.
+    Published,
+    /// Most recently updated first.
+    Updated,
+    /// Titles in alphabetical order, ignoring the case.
+    Title,
+}
+
+impl SortMode {
+    /// The mode after this one, back to [`SortMode::Feed`] after the last one.
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Feed => SortMode::Published,
+            SortMode::Published => SortMode::Updated,
+            SortMode::Updated => SortMode::Title,
+            SortMode::Title => SortMode::Feed,
+        }
+    }
+
+    /// Name of the order, which is the match score while searching.
+    pub fn label(self, searching: bool) -> &'static str {
+        match self {
+            SortMode::Feed if searching => "match score",
+            SortMode::Feed => "feed order",
+            SortMode::Published => "published date",
+            SortMode::Updated => "updated date",
+            SortMode::Title => "title",
+        }
+    }
+}
+
+/// How the cross-lists, the articles submitted to another category than the queried
+/// ones, are shown in the feed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrossListMode {
+    /// Among the other articles.
+    #[default]
+    Show,
+    /// Dimmed, after the articles of the queried categories.
+    Dim,
+    /// Left out of the feed.
+    Hide,
+}
+
+impl CrossListMode {
+    /// The mode after this one, back to [`CrossListMode::Show`] after the last one.
+    pub fn next(self) -> Self {
+        match self {
+            CrossListMode::Show => CrossListMode::Dim,
+            CrossListMode::Dim => CrossListMode::Hide,
+            CrossListMode::Hide => CrossListMode::Show,
+        }
+    }
+
+    /// Description of the mode, e.g. for a status message.
+    pub fn description(self) -> &'static str {
+        match self {
+            CrossListMode::Show => "Cross-lists shown",
+            CrossListMode::Dim => "Cross-lists dimmed, after the other articles",
+            CrossListMode::Hide => "Cross-lists hidden",
+        }
+    }
+}
+
+/// Values of an article the feed can be sorted or filtered by.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SortKey {
+    published: String,
+    updated: String,
+    /// Lowercase title, to sort ignoring the case.
+    title: String,
+    primary_category: Option<String>,
+}
+
+impl SortKey {
+    fn new(article: &ArxivEntry) -> Self {
+        Self {
+            published: article.published.clone(),
+            updated: article.updated.clone(),
+            title: article.title.to_lowercase(),
+            primary_category: article.primary_category.clone(),
+        }
+    }
+}
+
+/// Fields of an article matched by the query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchedFields {
+    pub title: bool,
+    pub authors: bool,
+    pub summary: bool,
+}
+
+/// Fuzzy matcher used to filter the articles.
+pub struct SearchEngine {
+    matcher: Matcher,
+}
+
+impl std::fmt::Debug for SearchEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchEngine").finish_non_exhaustive()
+    }
+}
+
+impl Default for SearchEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchEngine {
+    pub fn new() -> Self {
+        Self {
+            matcher: Matcher::new(Config::DEFAULT),
+        }
+    }
+
+    fn pattern(query: &str) -> Pattern {
+        Pattern::parse(query, CaseMatching::Ignore, Normalization::Smart)
+    }
+
+    /// Indices of the haystacks matching the query, best matches first.
+    ///
+    /// An article matches if the query matches its title, authors and summary together, or
+    /// its title alone if `title_only` is set. Matching the title alone adds to the score,
+    /// so on-topic articles come before the ones only mentioning the query in their
+    /// summary. Haystacks with the same score keep their original order.
+    pub fn filter(
+        &mut self,
+        query: &str,
+        haystacks: &[SearchFields],
+        title_only: bool,
+    ) -> Vec<usize> {
+        let pattern = Self::pattern(query);
+        let mut buf = Vec::new();
+        let mut scored: Vec<(usize, u32)> = haystacks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, fields)| {
+                let haystack = if title_only {
+                    &fields.title
+                } else {
+                    &fields.combined
+                };
+                let score = pattern.score(Utf32Str::new(haystack, &mut buf), &mut self.matcher)?;
+                let title_score = pattern
+                    .score(Utf32Str::new(&fields.title, &mut buf), &mut self.matcher)
+                    .unwrap_or(0);
+                Some((i, score + title_score))
+            })
+            .collect();
+        scored.sort_by_key(|&(_, score)| Reverse(score));
+        scored.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// Fields of a haystack matched by the query on their own.
+    ///
+    /// A query spanning the fields only matches them together, it then counts as matching
+    /// the title and the summary.
+    pub fn matched_fields(
+        &mut self,
+        query: &str,
+        fields: &SearchFields,
+        title_only: bool,
+    ) -> MatchedFields {
+        let pattern = Self::pattern(query);
+        let mut buf = Vec::new();
+        let mut matches = |text: &str| {
+            pattern
+                .score(Utf32Str::new(text, &mut buf), &mut self.matcher)
+                .is_some()
+        };
+        let title = matches(&fields.title);
+        let authors = !title_only && !fields.authors.is_empty() && matches(&fields.authors);
+        let summary = !title_only && (matches(&fields.summary) || !(title || authors));
+        MatchedFields {
+            title,
+            authors,
+            summary,
+        }
+    }
+
+    /// Candidate closest to `text`, to suggest a fix for a typo.
+    ///
+    /// The text is matched against each candidate and each candidate against the text, so
+    /// both missing and extra characters are forgiven.
+    pub fn closest<'c>(&mut self, text: &str, candidates: &[&'c str]) -> Option<&'c str> {
+        let text_pattern = Self::pattern(text);
+        let mut buf = Vec::new();
+        let mut text_buf = Vec::new();
+        candidates
+            .iter()
+            .filter_map(|&candidate| {
+                let forward =
+                    text_pattern.score(Utf32Str::new(candidate, &mut buf), &mut self.matcher);
+                let backward = Self::pattern(candidate)
+                    .score(Utf32Str::new(text, &mut text_buf), &mut self.matcher);
+                Some((candidate, forward.max(backward)?))
+            })
+            .max_by_key(|&(_, score)| score)
+            .map(|(candidate, _)| candidate)
+    }
+
+    /// Character indices of the text matched by the query, sorted and deduplicated.
+    pub fn get_highlight_indices(&mut self, query: &str, text: &str) -> Vec<u32> {
+        let pattern = Self::pattern(query);
+        let mut buf = Vec::new();
+        let mut indices = Vec::new();
+        pattern.indices(
+            Utf32Str::new(text, &mut buf),
+            &mut self.matcher,
+            &mut indices,
+        );
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+/// State of the search through the feed.
+#[derive(Debug, Default)]
+pub struct SearchState {
+    /// Query typed by the user.
+    pub query: String,
+    /// Position of the cursor in the query, in bytes, where the characters are typed.
+    cursor: usize,
+    /// Indices of the articles matching the query, in the order of the feed: best matches
+    /// first, unless sorted otherwise by `sort_mode`, with the cross-lists dimmed last or
+    /// left out depending on the [`CrossListMode`].
+    pub filtered_indices: Vec<usize>,
+    /// Match the titles only, leaving the summaries out.
+    pub title_only: bool,
+    /// Match the authors too, unless matching the titles only. Only read when the
+    /// articles are set.
+    pub authors: bool,
+    /// Order of the matching articles.
+    pub sort_mode: SortMode,
+    /// Fields of each article, against which the query is matched.
+    haystacks: Vec<SearchFields>,
+    /// Values of each article to sort them by.
+    sort_keys: Vec<SortKey>,
+    /// How the cross-lists are shown.
+    cross_lists: CrossListMode,
+    /// Only the articles published after this time are kept, if set.
+    published_after: Option<String>,
+    /// Categories of the query, the articles submitted to another one are cross-lists.
+    queried_categories: Vec<String>,
+    /// Generation of the articles searched through, to tell the same articles set again,
+    /// see [`Self::set_articles`].
+    generation: Option<u64>,
+    /// Number of haystacks built, to check the existing ones are kept.
+    #[cfg(test)]
+    haystacks_built: usize,
+    /// Indices to highlight in the texts already matched against the query, since the
+    /// details are drawn again on every frame. Cleared when the query changes.
+    highlights: HashMap<String, Vec<u32>>,
+    /// Number of texts matched for their highlights, to check the cache is used.
+    #[cfg(test)]
+    highlight_computations: usize,
+    engine: SearchEngine,
+}
+
+impl SearchState {
+    /// Build the haystacks out of the articles and reset the filter.
+    ///
+    /// The `generation` of the articles changes with every change of them: the articles
+    /// of the generation already searched through are not searched through again.
+    pub fn set_articles(&mut self, articles: &[ArxivEntry], generation: u64) {
+        if self.generation == Some(generation) {
+            return;
+        }
+        self.haystacks.clear();
+        self.sort_keys.clear();
+        self.filtered_indices.clear();
+        self.highlights.clear();
+        self.append_articles(articles, generation);
+    }
+
+    /// Add the articles after the ones searched through.
+    ///
+    /// Only the new articles are matched against the query, their matches coming after
+    /// the existing ones, which keep their order so the selection stays in place. When
+    /// the feed is sorted, the new matches are sorted in with the existing ones.
+    pub fn append_articles(&mut self, articles: &[ArxivEntry], generation: u64) {
+        let start = self.haystacks.len();
+        self.haystacks.extend(articles.iter().map(|article| {
+            let authors = if self.authors {
+                article.get_all_authors()
+            } else {
+                ""
+            };
+            SearchFields::new(&article.title, authors, &article.summary)
+        }));
+        self.sort_keys.extend(articles.iter().map(SortKey::new));
+        #[cfg(test)]
+        {
+            self.haystacks_built += articles.len();
+        }
+        let new = &self.haystacks[start..];
+        let matches = if self.query.is_empty() {
+            (0..new.len()).collect()
+        } else {
+            self.engine.filter(&self.query, new, self.title_only)
+        };
+        self.filtered_indices
+            .extend(matches.into_iter().map(|i| start + i));
+        self.sort_filtered();
+        self.arrange_filtered();
+        self.generation = Some(generation);
+    }
+
+    /// Character indices of the text to highlight for the query, none if no search is
+    /// active.
+    ///
+    /// Only the runs of consecutive matched characters are kept, except for very short
+    /// queries which can not match more than a couple of characters anyway.
+    pub fn highlight_indices(&mut self, text: &str) -> Vec<u32> {
+        if !self.is_active() {
+            return Vec::new();
+        }
+        if let Some(indices) = self.highlights.get(text) {
+            return indices.clone();
+        }
+        #[cfg(test)]
+        {
+            self.highlight_computations += 1;
+        }
+        let indices = self.engine.get_highlight_indices(&self.query, text);
+        let indices = if self.query.chars().count() <= SCATTERED_HIGHLIGHT_MAX_QUERY_LEN {
+            indices
+        } else {
+            contiguous_runs(&indices, MIN_HIGHLIGHT_RUN_LEN)
+        };
+        self.highlights.insert(text.to_string(), indices.clone());
+        indices
+    }
+
+    /// Number of texts matched for their highlights so far.
+    #[cfg(test)]
+    pub fn highlight_computations(&self) -> usize {
+        self.highlight_computations
+    }
+
+    /// Fields of the article at `index` matched by the query, none if no search is
+    /// active.
+    pub fn matched_fields(&mut self, index: usize) -> Option<MatchedFields> {
+        if !self.is_active() {
+            return None;
+        }
+        let fields = self.haystacks.get(index)?;
+        Some(
+            self.engine
+                .matched_fields(&self.query, fields, self.title_only),
+        )
+    }
+
+    /// Position of the cursor in the query, in bytes.
+    pub fn cursor(&self) -> usize {
+        self.cursor.min(self.query.len())
+    }
+
+    /// Insert a character at the cursor, which moves after it.
+    pub fn push_char(&mut self, c: char) {
+        let cursor = self.cursor();
+        self.query.insert(cursor, c);
+        self.cursor = cursor + c.len_utf8();
+        self.run_search();
+    }
+
+    /// Insert a pasted text at the cursor, which moves after it, searching once. The line
+    /// breaks and other control characters become spaces, the query being a single line.
+    pub fn insert_str(&mut self, text: &str) {
+        let text: String = text
+            .trim_end_matches(['\n', '\r'])
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        let cursor = self.cursor();
+        self.query.insert_str(cursor, &text);
+        self.cursor = cursor + text.len();
+        self.run_search();
+    }
+
+    /// Remove the character before the cursor, if any.
+    pub fn pop_char(&mut self) {
+        let cursor = self.cursor();
+        let Some(c) = self.query[..cursor].chars().next_back() else {
+            return;
+        };
+        self.cursor = cursor - c.len_utf8();
+        self.query.remove(self.cursor);
+        self.run_search();
+    }
+
+    /// Remove the character under the cursor, if any.
+    pub fn delete_char(&mut self) {
+        let cursor = self.cursor();
+        if cursor == self.query.len() {
+            return;
+        }
+        self.query.remove(cursor);
+        self.run_search();
+    }
+
+    /// Remove the word before the cursor and the spaces after it, like Ctrl-w in a
+    /// shell.
+    pub fn delete_word(&mut self) {
+        let cursor = self.cursor();
+        let before = self.query[..cursor].trim_end();
+        let start = before
+            .char_indices()
+            .rfind(|(_, c)| c.is_whitespace())
+            .map_or(0, |(space, c)| space + c.len_utf8());
+        if start == cursor {
+            return;
+        }
+        self.query.replace_range(start..cursor, "");
+        self.cursor = start;
+        self.run_search();
+    }
+
+    /// Remove the query before the cursor, like Ctrl-u in a shell.
+    pub fn delete_to_start(&mut self) {
+        let cursor = self.cursor();
+        if cursor == 0 {
+            return;
+        }
+        self.query.replace_range(..cursor, "");
+        self.cursor = 0;
+        self.run_search();
+    }
+
+    /// Move the cursor one character to the left.
+    pub fn move_cursor_left(&mut self) {
+        let cursor = self.cursor();
+        let previous = self.query[..cursor].chars().next_back();
+        self.cursor = cursor - previous.map_or(0, char::len_utf8);
+    }
+
+    /// Move the cursor one character to the right.
+    pub fn move_cursor_right(&mut self) {
+        let cursor = self.cursor();
+        let next = self.query[cursor..].chars().next();
+        self.cursor = cursor + next.map_or(0, char::len_utf8);
+    }
+
+    /// Move the cursor to the start of the query.
+    pub fn move_cursor_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Move the cursor to the end of the query.
+    pub fn move_cursor_end(&mut self) {
+        self.cursor = self.query.len();
+    }
+
+    /// Clear the query, every article matches again.
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.cursor = 0;
+        self.run_search();
+    }
+
+    /// Update the matching articles with the current query.
+    pub fn run_search(&mut self) {
+        self.highlights.clear();
+        self.filtered_indices = if self.query.is_empty() {
+            (0..self.haystacks.len()).collect()
+        } else {
+            self.engine
+                .filter(&self.query, &self.haystacks, self.title_only)
+        };
+        self.sort_filtered();
+        self.arrange_filtered();
+    }
+
+    /// Sort the matching articles by the next [`SortMode`].
+    pub fn cycle_sort_mode(&mut self) {
+        self.set_sort_mode(self.sort_mode.next());
+    }
+
+    /// Sort the matching articles in the given order.
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        self.sort_mode = mode;
+        self.run_search();
+    }
+
+    pub fn cross_lists(&self) -> CrossListMode {
+        self.cross_lists
+    }
+
+    /// Show the cross-lists in the given mode.
+    pub fn set_cross_lists(&mut self, mode: CrossListMode) {
+        if mode != self.cross_lists {
+            self.cross_lists = mode;
+            self.run_search();
+        }
+    }
+
+    /// Set the categories of the query, which tell the cross-lists apart.
+    pub fn set_queried_categories(&mut self, categories: &[&str]) {
+        if self.queried_categories != categories {
+            self.queried_categories = categories.iter().map(|c| c.to_string()).collect();
+            if self.cross_lists != CrossListMode::Show {
+                self.run_search();
+            }
+        }
+    }
+
+    /// Whether the article at `index` was submitted to another category than the queried
+    /// ones. Unknown, so false, without the queried categories or the primary category.
+    pub fn is_cross_list(&self, index: usize) -> bool {
+        let primary = self
+            .sort_keys
+            .get(index)
+            .and_then(|key| key.primary_category.as_ref());
+        match primary {
+            Some(primary) if !self.queried_categories.is_empty() => {
+                !self.queried_categories.contains(primary)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether only the articles published after a time are kept.
+    pub fn is_new_only(&self) -> bool {
+        self.published_after.is_some()
+    }
+
+    /// Keep only the articles published after the given time, or all of them.
+    pub fn set_published_after(&mut self, timestamp: Option<String>) {
+        if timestamp != self.published_after {
+            self.published_after = timestamp;
+            self.run_search();
+        }
+    }
+
+    /// Number of cross-lists among all the articles.
+    pub fn cross_list_count(&self) -> usize {
+        (0..self.sort_keys.len())
+            .filter(|&i| self.is_cross_list(i))
+            .count()
+    }
+
+    /// Put the matching cross-lists last, or leave them out, as set by `cross_lists`, and
+    /// leave out the articles published before `published_after`.
+    fn arrange_filtered(&mut self) {
+        if let Some(timestamp) = &self.published_after {
+            let keys = &self.sort_keys;
+            self.filtered_indices
+                .retain(|&i| keys[i].published.as_str() > timestamp.as_str());
+        }
+        match self.cross_lists {
+            CrossListMode::Show => {}
+            CrossListMode::Dim => {
+                let mut indices = std::mem::take(&mut self.filtered_indices);
+                indices.sort_by_key(|&i| self.is_cross_list(i));
+                self.filtered_indices = indices;
+            }
+            CrossListMode::Hide => {
+                let mut indices = std::mem::take(&mut self.filtered_indices);
+                indices.retain(|&i| !self.is_cross_list(i));
+                self.filtered_indices = indices;
+            }
+        }
+    }
+
+    /// Sort the matching articles by `sort_mode`. The sort is stable, so the articles
+    /// with the same key keep the order of the feed.
+    fn sort_filtered(&mut self) {
+        let keys = &self.sort_keys;
+        match self.sort_mode {
+            SortMode::Feed => {}
+            SortMode::Published => self
+                .filtered_indices
+                .sort_by(|&a, &b| keys[b].published.cmp(&keys[a].published)),
+            SortMode::Updated => self
+                .filtered_indices
+                .sort_by(|&a, &b| keys[b].updated.cmp(&keys[a].updated)),
+            SortMode::Title => self
+                .filtered_indices
+                .sort_by(|&a, &b| keys[a].title.cmp(&keys[b].title)),
+        }
+    }
+
+    /// Switch between matching the titles only and the titles with the summaries.
+    pub fn toggle_title_only(&mut self) {
+        self.title_only = !self.title_only;
+        self.run_search();
+    }
+
+    /// Is the search narrowing down the feed?
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// Number of articles matching the query.
+    pub fn filtered_count(&self) -> usize {
+        self.filtered_indices.len()
+    }
+
+    /// Number of articles searched through.
+    pub fn total_count(&self) -> usize {
+        self.haystacks.len()
+    }
+
+    /// Summary of the search, e.g. `12/200 matches`.
+    pub fn status(&self) -> String {
+        format!("{}/{} matches", self.filtered_count(), self.total_count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, summary: &str) -> ArxivEntry {
+        ArxivEntry::new(
+            title.to_string(),
+            vec!["Author".to_string()],
+            summary.to_string(),
+            format!("http://arxiv.org/abs/{title}"),
+            "2024-01-01T00:00:00Z".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+        )
+    }
+
+    fn articles() -> Vec<ArxivEntry> {
+        vec![
+            article(
+                "Surface codes",
+                "Quantum error correction with surface codes.",
+            ),
+            article("Transmon qubits", "Superconducting circuits."),
+            article("Bosonic codes", "Cat qubits and error correction."),
+        ]
+    }
+
+    #[test]
+    fn test_search_filters_articles() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        assert_eq!(state.filtered_count(), 3);
+
+        for c in "transmon".chars() {
+            state.push_char(c);
+        }
+        assert_eq!(state.filtered_indices, vec![1]);
+
+        state.clear();
+        assert_eq!(state.filtered_count(), 3);
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        let mut articles = articles();
+        articles[0].published = "2024-01-02T00:00:00Z".to_string();
+        articles[1].published = "2024-01-03T00:00:00Z".to_string();
+        articles[2].updated = "2024-01-05T00:00:00Z".to_string();
+        let mut state = SearchState::default();
+        state.set_articles(&articles, 0);
+
+        let mut orders = Vec::new();
+        for _ in 0..4 {
+            state.cycle_sort_mode();
+            orders.push((state.sort_mode, state.filtered_indices.clone()));
+        }
+        assert_eq!(
+            orders,
+            [
+                (SortMode::Published, vec![1, 0, 2]),
+                (SortMode::Updated, vec![2, 0, 1]),
+                (SortMode::Title, vec![2, 0, 1]),
+                (SortMode::Feed, vec![0, 1, 2]),
+            ]
+        );
+
+        // While searching, the matches are sorted instead of the best ones first.
+        for c in "codes".chars() {
+            state.push_char(c);
+        }
+        assert_eq!(state.filtered_count(), 2);
+        state.sort_mode = SortMode::Title;
+        state.run_search();
+        assert_eq!(state.filtered_indices, vec![2, 0]);
+
+        // The articles appended later are sorted in.
+        state.append_articles(&[article("Another codes", "")], 1);
+        assert_eq!(state.filtered_indices, vec![3, 2, 0]);
+    }
+
+    #[test]
+    fn test_cross_list_modes() {
+        let mut articles = articles();
+        articles.push(article("Qubit codes", "Error correction."));
+        articles[0].published = "2024-01-02T00:00:00Z".to_string();
+        for (article, category) in articles
+            .iter_mut()
+            .zip(["quant-ph", "cs.IT", "quant-ph", "math.CO"])
+        {
+            article.primary_category = Some(category.to_string());
+        }
+        let mut state = SearchState::default();
+        state.set_articles(&articles, 0);
+        state.set_queried_categories(&["quant-ph"]);
+        assert!(state.is_cross_list(1));
+        assert!(!state.is_cross_list(2));
+        assert_eq!(state.filtered_indices, vec![0, 1, 2, 3]);
+
+        state.set_cross_lists(CrossListMode::Dim);
+        assert_eq!(state.filtered_indices, vec![0, 2, 1, 3]);
+        state.set_cross_lists(CrossListMode::Hide);
+        assert_eq!(state.filtered_indices, vec![0, 2]);
+
+        // The cross-lists are left out of the search results too.
+        for c in "codes".chars() {
+            state.push_char(c);
+        }
+        assert!(state.filtered_indices.iter().all(|&i| i == 0 || i == 2));
+        assert_eq!(state.filtered_count(), 2);
+        state.set_cross_lists(CrossListMode::Dim);
+        assert_eq!(state.filtered_count(), 3);
+        assert_eq!(state.filtered_indices.last(), Some(&3));
+
+        // Only the new articles are kept, on top of the other filters.
+        state.set_published_after(Some("2024-01-01T00:00:00Z".to_string()));
+        assert!(state.is_new_only());
+        assert_eq!(state.filtered_indices, vec![0]);
+        state.set_published_after(None);
+
+        // Without the queried categories, no article is a cross-list.
+        state.set_queried_categories(&[]);
+        state.set_cross_lists(CrossListMode::Hide);
+        assert_eq!(state.filtered_count(), 3);
+    }
+
+    #[test]
+    fn test_search_matches_authors() {
+        let mut articles = articles();
+        articles[2] = ArxivEntry::new(
+            "Bosonic codes".to_string(),
+            vec!["Geoffrey Hinton".to_string(), "Marie Curie".to_string()],
+            "Cat qubits and error correction.".to_string(),
+            "http://arxiv.org/abs/2".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+        );
+        let mut state = SearchState {
+            authors: true,
+            ..Default::default()
+        };
+        state.set_articles(&articles, 0);
+        "hinton".chars().for_each(|c| state.push_char(c));
+        assert_eq!(state.filtered_indices, vec![2]);
+        let authors = MatchedFields {
+            title: false,
+            authors: true,
+            summary: false,
+        };
+        assert_eq!(state.matched_fields(2), Some(authors));
+
+        // Matching the titles only leaves the authors out.
+        state.toggle_title_only();
+        assert_eq!(state.filtered_count(), 0);
+
+        // Without the authors, the name only matches the summaries.
+        let mut state = SearchState::default();
+        state.set_articles(&articles, 0);
+        "hinton".chars().for_each(|c| state.push_char(c));
+        assert_eq!(state.filtered_count(), 0);
+    }
+
+    #[test]
+    fn test_title_only_drops_summary_matches() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        for c in "superconducting".chars() {
+            state.push_char(c);
+        }
+        assert_eq!(state.filtered_indices, vec![1]);
+
+        state.toggle_title_only();
+        assert!(state.filtered_indices.is_empty());
+
+        state.clear();
+        for c in "bosonic".chars() {
+            state.push_char(c);
+        }
+        assert_eq!(state.filtered_indices, vec![2]);
+    }
+
+    #[test]
+    fn test_delete_word() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        "quantum  surface codes"
+            .chars()
+            .for_each(|c| state.push_char(c));
+
+        state.delete_word();
+        assert_eq!(state.query, "quantum  surface ");
+        assert_eq!(state.cursor(), state.query.len());
+        assert_eq!(state.filtered_indices, vec![0]);
+
+        // From the middle of the query, and with the spaces between the cursor and the
+        // word.
+        state.move_cursor_left();
+        state.delete_word();
+        assert_eq!(state.query, "quantum   ");
+        state.delete_word();
+        assert_eq!(state.query, " ");
+        assert_eq!(state.cursor(), 0);
+        state.delete_word();
+        assert_eq!(state.query, " ");
+    }
+
+    #[test]
+    fn test_delete_word_after_multibyte_space() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        state.insert_str("ab\u{a0}cd\u{3000}ef");
+
+        state.delete_word();
+        assert_eq!(state.query, "ab\u{a0}cd\u{3000}");
+        assert_eq!(state.cursor(), state.query.len());
+        state.delete_word();
+        assert_eq!(state.query, "ab\u{a0}");
+        state.delete_word();
+        assert_eq!(state.query, "");
+    }
+
+    #[test]
+    fn test_paste_and_jump_at_cursor() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        state.insert_str("codes");
+        state.move_cursor_home();
+        state.insert_str("surface\n");
+        assert_eq!(state.query, "surfacecodes");
+        state.insert_str("\t");
+        assert_eq!(state.query, "surface codes");
+        assert_eq!(state.cursor(), "surface ".len());
+
+        // Over multibyte characters, the cursor stays between two of them.
+        state.move_cursor_end();
+        state.insert_str(" 量子");
+        state.move_cursor_left();
+        state.push_char('é');
+        assert_eq!(state.query, "surface codes 量é子");
+        state.move_cursor_right();
+        assert_eq!(state.cursor(), state.query.len());
+        state.pop_char();
+        state.move_cursor_home();
+        state.delete_char();
+        assert_eq!(state.query, "urface codes 量é");
+    }
+
+    #[test]
+    fn test_delete_to_start() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        "old surface".chars().for_each(|c| state.push_char(c));
+        (0..7).for_each(|_| state.move_cursor_left());
+
+        state.delete_to_start();
+        assert_eq!(state.query, "surface");
+        assert_eq!(state.cursor(), 0);
+        assert_eq!(state.filtered_indices, vec![0]);
+        state.delete_to_start();
+        assert_eq!(state.query, "surface");
+    }
+
+    #[test]
+    fn test_edit_at_cursor() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        "surfce".chars().for_each(|c| state.push_char(c));
+        assert_eq!(state.cursor(), 6);
+
+        // Fix the typo in the middle of the query.
+        state.move_cursor_left();
+        state.move_cursor_left();
+        state.push_char('a');
+        assert_eq!(state.query, "surface");
+        assert_eq!(state.cursor(), 5);
+        assert_eq!(state.filtered_indices, vec![0]);
+
+        state.pop_char();
+        state.pop_char();
+        assert_eq!(state.query, "surce");
+        assert_eq!(state.cursor(), 3);
+
+        // The cursor stays within the query, over multibyte characters too.
+        for _ in 0..5 {
+            state.move_cursor_left();
+        }
+        assert_eq!(state.cursor(), 0);
+        state.pop_char();
+        assert_eq!(state.query, "surce");
+        state.push_char('é');
+        state.move_cursor_left();
+        state.move_cursor_right();
+        assert_eq!(state.cursor(), 'é'.len_utf8());
+        for _ in 0..10 {
+            state.move_cursor_right();
+        }
+        assert_eq!(state.cursor(), state.query.len());
+        state.delete_char();
+        assert_eq!(state.query, "ésurce");
+        state.move_cursor_left();
+        state.delete_char();
+        assert_eq!(state.query, "ésurc");
+        assert_eq!(state.cursor(), state.query.len());
+
+        state.clear();
+        assert_eq!(state.cursor(), 0);
+    }
+
+    #[test]
+    fn test_search_status_updates() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        assert_eq!(state.status(), "3/3 matches");
+
+        for c in "error correction".chars() {
+            state.push_char(c);
+        }
+        assert_eq!(state.status(), "2/3 matches");
+
+        state.pop_char();
+        assert_eq!(state.query, "error correctio");
+        assert_eq!(state.status(), "2/3 matches");
+    }
+
+    #[test]
+    fn test_title_match_ranks_first() {
+        let mut state = SearchState::default();
+        state.set_articles(
+            &[
+                article("Superconducting circuits", "A study of decoders."),
+                article("Decoders for surface codes", "A study of circuits."),
+            ],
+            0,
+        );
+        for c in "decoder".chars() {
+            state.push_char(c);
+        }
+        assert_eq!(state.filtered_indices, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_highlight_indices() {
+        let mut engine = SearchEngine::new();
+        assert_eq!(
+            engine.get_highlight_indices("cod", "Surface codes"),
+            vec![8, 9, 10]
+        );
+    }
+
+    #[test]
+    fn test_contiguous_runs() {
+        assert_eq!(
+            contiguous_runs(&[0, 3, 4, 5, 8, 10, 11], 2),
+            vec![3, 4, 5, 10, 11]
+        );
+        assert_eq!(contiguous_runs(&[0, 2, 4], 2), Vec::<u32>::new());
+        assert!(contiguous_runs(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn test_highlight_indices_loose_query() {
+        let title = "Surface codes with decoders";
+        let mut state = SearchState::default();
+        state.set_articles(&[article(title, "")], 0);
+        for c in "scode".chars() {
+            state.push_char(c);
+        }
+
+        // The engine matches the "S" of "Surface" on its own...
+        let raw = state.engine.get_highlight_indices("scode", title);
+        assert_eq!(raw, vec![0, 8, 9, 10, 11]);
+        // ...which is dropped, keeping the "code" run.
+        assert_eq!(state.highlight_indices(title), vec![8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn test_matched_fields() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        assert_eq!(state.matched_fields(0), None);
+
+        "surface".chars().for_each(|c| state.push_char(c));
+        let both = MatchedFields {
+            title: true,
+            authors: false,
+            summary: true,
+        };
+        assert_eq!(state.matched_fields(0), Some(both));
+        state.toggle_title_only();
+        let title = MatchedFields {
+            title: true,
+            authors: false,
+            summary: false,
+        };
+        assert_eq!(state.matched_fields(0), Some(title));
+
+        state.toggle_title_only();
+        state.clear();
+        "circuits".chars().for_each(|c| state.push_char(c));
+        let summary = MatchedFields {
+            title: false,
+            authors: false,
+            summary: true,
+        };
+        assert_eq!(state.matched_fields(1), Some(summary));
+    }
+
+    #[test]
+    fn test_append_articles() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        "codes".chars().for_each(|c| state.push_char(c));
+        assert_eq!(state.filtered_indices, vec![0, 2]);
+
+        // The new matches come after the existing ones, whatever their score.
+        state.append_articles(
+            &[
+                article("Codes", "Codes"),
+                article("Fluxonium", "Heavy fluxonium."),
+            ],
+            1,
+        );
+        assert_eq!(state.filtered_indices, vec![0, 2, 3]);
+        assert_eq!(state.status(), "3/5 matches");
+        assert_eq!(state.haystacks_built, 5);
+
+        // Searching again ranks them together.
+        state.pop_char();
+        let mut filtered = state.filtered_indices.clone();
+        filtered.sort_unstable();
+        assert_eq!(filtered, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_set_same_articles() {
+        let mut state = SearchState::default();
+        state.set_articles(&articles(), 0);
+        state.push_char('q');
+        let filtered = state.filtered_indices.clone();
+
+        state.set_articles(&articles(), 0);
+        assert_eq!(state.haystacks_built, 3);
+        assert_eq!(state.filtered_indices, filtered);
+
+        // A new generation is searched through, even with the same first and last articles.
+        let mut changed = articles();
+        changed[1] = article("Fluxonium", "Heavy fluxonium.");
+        state.set_articles(&changed, 1);
+        assert_eq!(state.haystacks_built, 6);
+        assert_eq!(state.filtered_indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_highlight_indices_cached() {
+        let title = "Surface codes with decoders";
+        let mut state = SearchState::default();
+        state.set_articles(&[article(title, "")], 0);
+        state.push_char('c');
+        state.push_char('o');
+
+        let indices = state.highlight_indices(title);
+        assert_eq!(state.highlight_indices(title), indices);
+        assert_eq!(state.highlight_computations(), 1);
+
+        // A new query matches the text again.
+        state.push_char('d');
+        assert_ne!(state.highlight_indices(title), indices);
+        assert_eq!(state.highlight_computations(), 2);
+    }
+
+    #[test]
+    fn test_highlight_indices_short_query() {
+        let title = "Surface codes";
+        let mut state = SearchState::default();
+        state.set_articles(&[article(title, "")], 0);
+        state.push_char('s');
+
+        assert_eq!(
+            state.highlight_indices(title),
+            state.engine.get_highlight_indices("s", title)
+        );
+        assert!(!state.highlight_indices(title).is_empty());
+    }
+}
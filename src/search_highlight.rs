@@ -1,52 +1,107 @@
 //! Module for highligting keyword in a text.
 
-use ratatui::text::{Line, Span};
 use aho_corasick::AhoCorasick;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
 
-use crate::ui::Theme;
-
+/// Byte ranges of the `patterns` found in the `text`, ignoring the case of the ASCII
+/// letters only.
+///
+/// A pattern matches whole characters, so the ranges fall on character boundaries. The
+/// empty patterns, which would match between the bytes of a multibyte character, are
+/// ignored.
 pub fn search_patterns(text: &str, patterns: &[&str]) -> Vec<(usize, usize)> {
+    let patterns = patterns.iter().filter(|pattern| !pattern.is_empty());
     let ac = AhoCorasick::builder()
         .ascii_case_insensitive(true)
         .build(patterns)
         .unwrap();
     let mut matches = vec![];
     for mat in ac.find_iter(text) {
-        matches.push((mat.start(), mat.end()));
+        if text.is_char_boundary(mat.start()) && text.is_char_boundary(mat.end()) {
+            matches.push((mat.start(), mat.end()));
+        }
     }
     matches
 }
 
-/// Highligh the pattern matched.
+/// Highligh the pattern matched with the `highlight` style, the rest of the text having
+/// the `base` style.
 ///
 /// The lifetime of the output is only due to the lifetime of the text, not of the
 /// patterns.
-pub fn highlight_patterns<'a>(text: &'a str, patterns: Option<&[&str]>, theme: &Theme) -> Line<'a> {
+pub fn highlight_patterns<'a>(
+    text: &'a str,
+    patterns: Option<&[&str]>,
+    base: Style,
+    highlight: Style,
+) -> Line<'a> {
     let patterns = patterns.unwrap_or_default();
     let match_locs = search_patterns(text, patterns);
 
     if match_locs.is_empty() {
-        Line::from(Span::raw(text).style(theme.main))
+        Line::from(Span::raw(text).style(base))
     } else {
         let mut start_chunk: usize = 0;
         let mut highlighted_spans: Vec<Span> = Vec::new();
         for (start, end) in match_locs.iter() {
-            highlighted_spans.push(Span::raw(&text[start_chunk..*start]).style(theme.main));
-            highlighted_spans.push(Span::raw(&text[*start..*end]).style(theme.highlight));
+            highlighted_spans.push(Span::raw(&text[start_chunk..*start]).style(base));
+            highlighted_spans.push(Span::raw(&text[*start..*end]).style(highlight));
             start_chunk = *end;
         }
 
         // Adding the last bit if necessary:
         if start_chunk != text.len() {
-            highlighted_spans.push(Span::raw(&text[start_chunk..]).style(theme.main));
+            highlighted_spans.push(Span::raw(&text[start_chunk..]).style(base));
         }
         Line::from(highlighted_spans)
     }
 }
 
+/// Patch the style of the characters at the given indices, e.g. the ones matched by the
+/// fuzzy search, on top of the styles of the line.
+///
+/// The indices are counted in characters over the whole line, as given by the search
+/// engine.
+pub fn fuzzy_line<'a>(line: Line<'a>, indices: &[u32], style: Style) -> Line<'a> {
+    if indices.is_empty() {
+        return line;
+    }
+    let mut char_index = 0;
+    let mut spans: Vec<Span> = Vec::new();
+    for span in line.spans {
+        let styled = |chunk: String, matched: bool| {
+            Span::styled(
+                chunk,
+                if matched {
+                    span.style.patch(style)
+                } else {
+                    span.style
+                },
+            )
+        };
+        let mut chunk = String::new();
+        let mut chunk_matched = false;
+        for c in span.content.chars() {
+            let matched = indices.binary_search(&char_index).is_ok();
+            if matched != chunk_matched && !chunk.is_empty() {
+                spans.push(styled(std::mem::take(&mut chunk), chunk_matched));
+            }
+            chunk_matched = matched;
+            chunk.push(c);
+            char_index += 1;
+        }
+        if !chunk.is_empty() {
+            spans.push(styled(chunk, chunk_matched));
+        }
+    }
+    Line::from(spans)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::Theme;
 
     // ----- Testing the search patterns function. Example from AhoCorasick docs -----
     #[test]
@@ -72,7 +127,7 @@ mod tests {
             Span::raw("world").style(theme.highlight),
         ];
 
-        let result = highlight_patterns(text, Some(patterns), &theme);
+        let result = highlight_patterns(text, Some(patterns), theme.main, theme.highlight);
 
         assert_eq!(result.spans, expected_spans);
     }
@@ -85,7 +140,7 @@ mod tests {
 
         let expected_spans = vec![Span::raw(text).style(theme.main)];
 
-        let result = highlight_patterns(text, Some(patterns), &theme);
+        let result = highlight_patterns(text, Some(patterns), theme.main, theme.highlight);
 
         assert_eq!(result.spans, expected_spans);
     }
@@ -97,7 +152,7 @@ mod tests {
 
         let expected_spans = vec![Span::raw(text).style(theme.main)];
 
-        let result = highlight_patterns(text, None, &theme);
+        let result = highlight_patterns(text, None, theme.main, theme.highlight);
 
         assert_eq!(result.spans, expected_spans);
     }
@@ -114,8 +169,91 @@ mod tests {
             Span::raw(" and world").style(theme.main),
         ];
 
-        let result = highlight_patterns(text, Some(patterns), &theme);
+        let result = highlight_patterns(text, Some(patterns), theme.main, theme.highlight);
 
         assert_eq!(result.spans, expected_spans);
     }
+
+    #[test]
+    fn test_highlight_patterns_multibyte() {
+        let theme = Theme::default();
+        let text = "Erwin Schrödinger, Paul Dirac";
+
+        let result = highlight_patterns(
+            text,
+            Some(&["schrödinger", "dirac"]),
+            theme.main,
+            theme.highlight,
+        );
+        assert_eq!(
+            result.spans,
+            vec![
+                Span::raw("Erwin ").style(theme.main),
+                Span::raw("Schrödinger").style(theme.highlight),
+                Span::raw(", Paul ").style(theme.main),
+                Span::raw("Dirac").style(theme.highlight),
+            ]
+        );
+
+        // Only the case of the ASCII letters is ignored.
+        let result = highlight_patterns(text, Some(&["SCHRÖDINGER"]), theme.main, theme.highlight);
+        assert_eq!(result.spans, vec![Span::raw(text).style(theme.main)]);
+
+        // The empty pattern would match inside "ö".
+        let result = highlight_patterns(text, Some(&["", "ö"]), theme.main, theme.highlight);
+        assert_eq!(
+            result.spans,
+            vec![
+                Span::raw("Erwin Schr").style(theme.main),
+                Span::raw("ö").style(theme.highlight),
+                Span::raw("dinger, Paul Dirac").style(theme.main),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_line() {
+        let style = Theme::default().search_highlight;
+        let line = Line::from(vec![Span::raw("ab"), Span::raw("cd")]);
+
+        let result = fuzzy_line(line, &[1, 2], style);
+
+        assert_eq!(
+            result.spans,
+            vec![
+                Span::raw("a"),
+                Span::styled("b", style),
+                Span::styled("c", style),
+                Span::raw("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_author_and_search_highlights_differ() {
+        let theme = Theme::default();
+        let authors = "Marie Curie, Pierre Curie";
+
+        let line = highlight_patterns(
+            authors,
+            Some(&["Marie Curie"]),
+            theme.main,
+            theme.author_highlight,
+        );
+        // The search matched "Pierre".
+        let indices: Vec<u32> = (13..19).collect();
+        let result = fuzzy_line(line, &indices, theme.search_highlight);
+
+        let style_of = |content: &str| {
+            result
+                .spans
+                .iter()
+                .find(|span| span.content == content)
+                .map(|span| span.style)
+                .unwrap()
+        };
+        assert_eq!(style_of("Marie Curie"), theme.author_highlight);
+        assert_eq!(style_of("Pierre"), theme.main.patch(theme.search_highlight));
+        assert_ne!(style_of("Marie Curie"), style_of("Pierre"));
+    }
 }
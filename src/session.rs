@@ -0,0 +1,218 @@
+//! Persisting the state of the previous session.
+//!
+//! On quit, a small JSON file is written in the XDG state directory with the query that
+//! was run and the article that was selected. On the next startup, if the same query is
+//! run again, the selection and scroll position are restored. The time of the last run
+//! of each query is stored as well, to mark the articles published since.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+const APP_DIR_NAME: &str = "arxivlens";
+const SESSION_FILE_NAME: &str = "session.json";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// The query url used to fetch the feed.
+    pub query: String,
+    /// The id of the article selected when quitting.
+    pub selected_id: Option<String>,
+    /// Scroll offset of the article feed.
+    #[serde(default)]
+    pub offset: usize,
+    /// Time of the run, in the format of the arXiv dates, e.g. "2024-01-01T00:00:00Z".
+    #[serde(default)]
+    pub last_run: Option<String>,
+    /// Time of the last run of each query, by the terms of the query, e.g.
+    /// "cat:quant-ph", so that each feed has its own new articles.
+    #[serde(default)]
+    pub last_runs: BTreeMap<String, String>,
+}
+
+impl Session {
+    pub fn new(query: &str, selected_id: Option<String>, offset: usize) -> Self {
+        Self {
+            query: query.to_string(),
+            selected_id,
+            offset,
+            last_run: None,
+            last_runs: BTreeMap::new(),
+        }
+    }
+
+    /// Load the previous session, if any.
+    ///
+    /// A missing or unreadable session file is not an error: the app simply starts fresh.
+    pub fn load() -> Option<Session> {
+        let path = xdg::BaseDirectories::with_prefix(APP_DIR_NAME)
+            .ok()?
+            .get_state_file(SESSION_FILE_NAME);
+        let content = std::fs::read_to_string(path).ok()?;
+        Session::from_json(&content)
+    }
+
+    /// Write the session in the XDG state directory.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path =
+            xdg::BaseDirectories::with_prefix(APP_DIR_NAME)?.place_state_file(SESSION_FILE_NAME)?;
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn from_json(content: &str) -> Option<Session> {
+        serde_json::from_str(content).ok()
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Record a run of the query with the given terms at the given time.
+    pub fn record_run(&mut self, terms: &str, timestamp: String) {
+        self.last_runs.insert(terms.to_string(), timestamp.clone());
+        self.last_run = Some(timestamp);
+    }
+
+    /// Time of the last run of the query with the given terms.
+    ///
+    /// The sessions saved before the runs were recorded by query only know the time of
+    /// the last run, whatever the query.
+    pub fn last_run_of(&self, terms: &str) -> Option<&str> {
+        if self.last_runs.is_empty() {
+            return self.last_run.as_deref();
+        }
+        self.last_runs.get(terms).map(String::as_str)
+    }
+
+    /// Whether this session was recorded for the given query.
+    pub fn matches_query(&self, query: &str) -> bool {
+        self.query == query
+    }
+}
+
+/// Time after which the articles of the query with the given terms are new: its last run
+/// in the previous session.
+///
+/// The first time the query is run, either every article is new, or none is.
+pub fn new_since(
+    previous: Option<&Session>,
+    terms: &str,
+    all_new_on_first_run: bool,
+) -> Option<String> {
+    match previous.and_then(|session| session.last_run_of(terms)) {
+        Some(last_run) => Some(last_run.to_string()),
+        // Every date is after the empty one.
+        None if all_new_on_first_run => Some(String::new()),
+        None => None,
+    }
+}
+
+/// The current time, in the format of the arXiv dates.
+pub fn now_timestamp() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    format_timestamp(secs)
+}
+
+/// Format seconds since the Unix epoch as an UTC date, e.g. "2024-01-01T00:00:00Z".
+///
+/// The dates are compared as strings, so the format must match the one of arXiv.
+fn format_timestamp(secs: u64) -> String {
+    let (days, secs) = (secs / 86400, secs % 86400);
+    // Civil date from the number of days, see
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let session = Session::new(
+            "http://export.arxiv.org/api/query?search_query=cat:quant-ph",
+            Some("http://arxiv.org/abs/1212.34567".to_string()),
+            12,
+        );
+        let json = session.to_json().unwrap();
+        assert_eq!(Session::from_json(&json), Some(session));
+    }
+
+    #[test]
+    fn test_session_corrupted() {
+        assert_eq!(Session::from_json("{ not json"), None);
+    }
+
+    #[test]
+    fn test_session_matches_query() {
+        let session = Session::new("query-a", None, 0);
+        assert!(session.matches_query("query-a"));
+        assert!(!session.matches_query("query-b"));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1704067199), "2023-12-31T23:59:59Z");
+        assert_eq!(format_timestamp(1709210096), "2024-02-29T12:34:56Z");
+    }
+
+    #[test]
+    fn test_new_since_by_query() {
+        let path = std::env::temp_dir().join(format!("arxivlens-session-{}", std::process::id()));
+        let mut session = Session::new("query", None, 0);
+        session.record_run("cat:quant-ph", "2024-01-02T00:00:00Z".to_string());
+        session.record_run("cat:cs.LG", "2024-01-05T00:00:00Z".to_string());
+        session.record_run("cat:quant-ph", "2024-01-03T00:00:00Z".to_string());
+        std::fs::write(&path, session.to_json().unwrap()).unwrap();
+        let previous = Session::from_json(&std::fs::read_to_string(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        // Each query is compared to its own last run.
+        let previous = previous.as_ref();
+        assert_eq!(
+            new_since(previous, "cat:quant-ph", false).as_deref(),
+            Some("2024-01-03T00:00:00Z")
+        );
+        assert_eq!(
+            new_since(previous, "cat:cs.LG", true).as_deref(),
+            Some("2024-01-05T00:00:00Z")
+        );
+        // The first run of a query marks every article as new, or none.
+        assert_eq!(new_since(previous, "cat:hep-th", false), None);
+        assert_eq!(new_since(None, "cat:hep-th", true).as_deref(), Some(""));
+        assert_eq!(new_since(None, "cat:hep-th", false), None);
+
+        // An older session only knows its last run.
+        let legacy = Session::from_json(
+            r#"{"query": "q", "selected_id": null, "last_run": "2024-01-01T00:00:00Z"}"#,
+        );
+        assert_eq!(
+            new_since(legacy.as_ref(), "cat:hep-th", false).as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_session_without_last_run() {
+        let session = Session::from_json(r#"{"query": "q", "selected_id": null}"#).unwrap();
+        assert_eq!(session.last_run, None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Statistics of the feed: its most frequent authors, its categories and the number of
+//! articles published each day.
+
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::arxiv::ArxivEntry;
+
+/// Number of authors kept in the statistics, the most frequent ones.
+pub const TOP_AUTHORS: usize = 15;
+
+/// Counts of the articles of a feed, computed once to be shown in the stats popup.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FeedStats {
+    /// Number of articles counted.
+    pub articles: usize,
+    /// The [`TOP_AUTHORS`] authors with the most articles, most articles first.
+    pub authors: Vec<(String, usize)>,
+    /// Every category with its number of articles, cross-lists included, most articles
+    /// first.
+    pub categories: Vec<(String, usize)>,
+    /// Number of articles published each day, from the first day to the last one.
+    pub days: Vec<(NaiveDate, usize)>,
+}
+
+impl FeedStats {
+    pub fn new<'e>(articles: impl IntoIterator<Item = &'e ArxivEntry>) -> Self {
+        let mut count = 0;
+        let mut authors: HashMap<&str, usize> = HashMap::new();
+        let mut categories: HashMap<&str, usize> = HashMap::new();
+        let mut days: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for article in articles {
+            count += 1;
+            for author in &article.authors {
+                *authors.entry(author.as_str()).or_default() += 1;
+            }
+            let primary = article.primary_category.iter();
+            let others = article
+                .categories
+                .iter()
+                .filter(|category| Some(*category) != article.primary_category.as_ref());
+            for category in primary.chain(others) {
+                *categories.entry(category.as_str()).or_default() += 1;
+            }
+            let day = article.published.get(..10).unwrap_or_default();
+            if let Ok(day) = NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+                *days.entry(day).or_default() += 1;
+            }
+        }
+
+        let mut authors = most_first(authors);
+        authors.truncate(TOP_AUTHORS);
+        // The days without articles are counted too.
+        let days = match (days.first_key_value(), days.last_key_value()) {
+            (Some((&first, _)), Some((&last, _))) => first
+                .iter_days()
+                .take_while(|day| *day <= last)
+                .map(|day| (day, days.get(&day).copied().unwrap_or_default()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            articles: count,
+            authors,
+            categories: most_first(categories),
+            days,
+        }
+    }
+}
+
+/// The counts, the largest first and then by name.
+fn most_first(counts: HashMap<&str, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(authors: &[&str], categories: &[&str], published: &str) -> ArxivEntry {
+        ArxivEntry::new(
+            "Title".to_string(),
+            authors.iter().map(|author| author.to_string()).collect(),
+            String::new(),
+            "http://arxiv.org/abs/0000.00000".to_string(),
+            published.to_string(),
+            published.to_string(),
+        )
+        .with_categories(
+            categories.first().map(|category| category.to_string()),
+            categories
+                .iter()
+                .map(|category| category.to_string())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_feed_stats() {
+        let articles = [
+            article(
+                &["Marie Curie", "Pierre Curie"],
+                &["quant-ph", "cs.LG"],
+                "2024-07-08T20:00:00Z",
+            ),
+            article(&["Marie Curie"], &["quant-ph"], "2024-07-08T10:00:00Z"),
+            article(
+                &["Paul Dirac"],
+                &["hep-th", "quant-ph"],
+                "2024-07-10T10:00:00Z",
+            ),
+        ];
+
+        let stats = FeedStats::new(&articles);
+
+        assert_eq!(stats.articles, 3);
+        assert_eq!(
+            stats.authors,
+            [
+                ("Marie Curie".to_string(), 2),
+                ("Paul Dirac".to_string(), 1),
+                ("Pierre Curie".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            stats.categories,
+            [
+                ("quant-ph".to_string(), 3),
+                ("cs.LG".to_string(), 1),
+                ("hep-th".to_string(), 1),
+            ]
+        );
+        let day = |day| NaiveDate::from_ymd_opt(2024, 7, day).unwrap();
+        assert_eq!(stats.days, [(day(8), 2), (day(9), 0), (day(10), 1)]);
+    }
+
+    #[test]
+    fn test_feed_stats_top_authors() {
+        let names: Vec<String> = (0..20).map(|i| format!("Author {i:02}")).collect();
+        let mut articles: Vec<ArxivEntry> = names
+            .iter()
+            .map(|name| article(&[name], &[], "2024-07-08T20:00:00Z"))
+            .collect();
+        articles.push(article(&["Author 19"], &[], "2024-07-08T20:00:00Z"));
+
+        let stats = FeedStats::new(&articles);
+
+        assert_eq!(stats.authors.len(), TOP_AUTHORS);
+        assert_eq!(stats.authors[0], ("Author 19".to_string(), 2));
+        assert_eq!(stats.authors[1], ("Author 00".to_string(), 1));
+        assert_eq!(FeedStats::new(&[]), FeedStats::default());
+    }
+}
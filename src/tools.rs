@@ -0,0 +1,248 @@
+//! External tools launched from the application, e.g. the web browser.
+//!
+//! The commands are templates from the `[tools]` section of the config, e.g.
+//! `xdg-open {url}`. A template is split into arguments like a shell would, then the
+//! placeholder is replaced in each argument, so a value with spaces stays one argument.
+
+use std::fmt::{Debug, Display};
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+#[cfg(target_os = "macos")]
+pub(crate) const DEFAULT_BROWSER_COMMAND: &str = "open";
+#[cfg(not(target_os = "macos"))]
+pub(crate) const DEFAULT_BROWSER_COMMAND: &str = "xdg-open";
+
+/// Placeholder of the url in the browser command.
+pub const URL_PLACEHOLDER: &str = "{url}";
+/// Placeholder of the file in the PDF viewer command.
+pub const PATH_PLACEHOLDER: &str = "{path}";
+
+/// A command template which can not be run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// The template has no program to run.
+    Empty,
+    /// A quote is opened but never closed.
+    UnclosedQuote,
+    /// The placeholder of the value is missing from the template.
+    MissingPlaceholder(&'static str),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::Empty => write!(f, "the command is empty"),
+            TemplateError::UnclosedQuote => write!(f, "a quote is not closed"),
+            TemplateError::MissingPlaceholder(placeholder) => {
+                write!(f, "the command does not contain {placeholder}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Split a command line into arguments, respecting the single and double quotes.
+///
+/// Inside double quotes and outside of quotes, a backslash escapes the next character.
+pub fn split_command(line: &str) -> Result<Vec<String>, TemplateError> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('\''), c) => current.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    current.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(TemplateError::UnclosedQuote);
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+/// Check that a template can be run with the given placeholder.
+pub fn validate_template(template: &str, placeholder: &'static str) -> Result<(), TemplateError> {
+    expand_template(template, placeholder, "").map(|_| ())
+}
+
+/// Arguments of the command, with the placeholder replaced by the value.
+pub fn expand_template(
+    template: &str,
+    placeholder: &'static str,
+    value: &str,
+) -> Result<Vec<String>, TemplateError> {
+    let args = split_command(template)?;
+    if args.is_empty() {
+        return Err(TemplateError::Empty);
+    }
+    if !args.iter().any(|arg| arg.contains(placeholder)) {
+        return Err(TemplateError::MissingPlaceholder(placeholder));
+    }
+    Ok(args
+        .iter()
+        .map(|arg| arg.replace(placeholder, value))
+        .collect())
+}
+
+/// Replace a leading `~` by the home directory.
+pub fn expand_tilde(path: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match (path.strip_prefix('~'), home) {
+        (Some(""), Some(home)) => home,
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Spawn the command without waiting for it.
+///
+/// The output is discarded so the program does not draw over the interface.
+pub fn spawn_detached(args: &[String]) -> io::Result<()> {
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, TemplateError::Empty))?;
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Opens urls, in the web browser by default.
+///
+/// This is a trait so the tests can record the urls instead of spawning a browser.
+pub trait UrlOpener: Debug {
+    fn open(&mut self, url: &str) -> io::Result<()>;
+}
+
+/// Opens the urls with the browser command of the config.
+#[derive(Debug)]
+pub struct BrowserOpener {
+    /// Command template, with the url as `{url}`.
+    command: String,
+}
+
+impl BrowserOpener {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+        }
+    }
+}
+
+impl Default for BrowserOpener {
+    fn default() -> Self {
+        Self::new(&format!("{DEFAULT_BROWSER_COMMAND} {URL_PLACEHOLDER}"))
+    }
+}
+
+impl UrlOpener for BrowserOpener {
+    fn open(&mut self, url: &str) -> io::Result<()> {
+        let args = expand_template(&self.command, URL_PLACEHOLDER, url)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        spawn_detached(&args)
+    }
+}
+
+/// Copies text to the clipboard, the one of the system by default.
+///
+/// This is a trait so the tests can record the copied text instead of touching the
+/// clipboard of the system.
+pub trait ClipboardWriter: Debug {
+    fn set_text(&mut self, text: String) -> io::Result<()>;
+}
+
+/// The clipboard of the system.
+#[derive(Debug, Default)]
+pub struct SystemClipboard;
+
+impl ClipboardWriter for SystemClipboard {
+    fn set_text(&mut self, text: String) -> io::Result<()> {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text))
+            .map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_command_quotes() {
+        assert_eq!(
+            split_command(r#"firefox --new-tab "{url}""#).unwrap(),
+            strings(&["firefox", "--new-tab", "{url}"])
+        );
+        assert_eq!(
+            split_command(r#"'/opt/My Viewer/bin' --page=1 "a \"b\"" c\ d"#).unwrap(),
+            strings(&["/opt/My Viewer/bin", "--page=1", r#"a "b""#, "c d"])
+        );
+        assert_eq!(
+            split_command(r#"viewer """#).unwrap(),
+            strings(&["viewer", ""])
+        );
+        assert_eq!(
+            split_command(r#"viewer "{path}"#),
+            Err(TemplateError::UnclosedQuote)
+        );
+    }
+
+    #[test]
+    fn test_expand_template() {
+        assert_eq!(
+            expand_template("zathura {path}", PATH_PLACEHOLDER, "/tmp/my paper.pdf").unwrap(),
+            strings(&["zathura", "/tmp/my paper.pdf"])
+        );
+        assert_eq!(
+            expand_template("open --url={url}", URL_PLACEHOLDER, "http://arxiv.org").unwrap(),
+            strings(&["open", "--url=http://arxiv.org"])
+        );
+        assert_eq!(
+            validate_template("xdg-open", URL_PLACEHOLDER),
+            Err(TemplateError::MissingPlaceholder(URL_PLACEHOLDER))
+        );
+        assert_eq!(
+            validate_template("  ", URL_PLACEHOLDER),
+            Err(TemplateError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_expand_tilde() {
+        let home = PathBuf::from(std::env::var_os("HOME").unwrap());
+        assert_eq!(
+            expand_tilde("~/Downloads/arxiv"),
+            home.join("Downloads/arxiv")
+        );
+        assert_eq!(expand_tilde("~"), home);
+        assert_eq!(
+            expand_tilde("~other/papers"),
+            PathBuf::from("~other/papers")
+        );
+        assert_eq!(expand_tilde("/tmp/papers"), PathBuf::from("/tmp/papers"));
+    }
+}
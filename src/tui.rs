@@ -1,28 +1,42 @@
 use crate::app::{App, AppResult};
 use crate::event::EventHandler;
 use ratatui::backend::Backend;
-use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use ratatui::crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+};
 use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::Terminal;
-use std::io;
+use std::io::{self, Write};
 use std::panic;
 
+/// Hook called when a thread panics, see [`panic::set_hook`].
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
 /// Representation of a terminal user interface.
 ///
 /// It is responsible for setting up the terminal,
 /// initializing the interface and handling the draw events.
+///
+/// The terminal is restored when the interface is dropped without having exited, so
+/// that an early return on an error does not leave it in raw mode.
 #[derive(Debug)]
 pub struct Tui<B: Backend> {
     /// Interface to the Terminal.
     terminal: Terminal<B>,
     /// Terminal event handler.
     pub events: EventHandler,
+    /// Whether the terminal was initialized and not restored yet.
+    active: bool,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: Terminal<B>, events: EventHandler) -> Self {
-        Self { terminal, events }
+        Self {
+            terminal,
+            events,
+            active: false,
+        }
     }
 
     /// Initializes the terminal interface.
@@ -30,14 +44,18 @@ impl<B: Backend> Tui<B> {
     /// It enables the raw mode and sets terminal properties.
     pub fn init(&mut self) -> AppResult<()> {
         terminal::enable_raw_mode()?;
-        ratatui::crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        self.active = true;
+        ratatui::crossterm::execute!(
+            io::stderr(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableBracketedPaste
+        )?;
 
         // Define a custom panic hook to reset the terminal properties.
         // This way, you won't have your terminal messed up if an unexpected error happens.
-        let panic_hook = panic::take_hook();
-        panic::set_hook(Box::new(move |panic| {
-            Self::reset().expect("failed to reset the terminal");
-            panic_hook(panic);
+        panic::set_hook(restoring_hook(panic::take_hook(), || {
+            Self::reset(&mut io::stderr())
         }));
 
         self.terminal.hide_cursor()?;
@@ -58,9 +76,14 @@ impl<B: Backend> Tui<B> {
     ///
     /// This function is also used for the panic hook to revert
     /// the terminal properties if unexpected errors occur.
-    fn reset() -> AppResult<()> {
+    fn reset(writer: &mut impl Write) -> AppResult<()> {
         terminal::disable_raw_mode()?;
-        ratatui::crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        ratatui::crossterm::execute!(
+            writer,
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste
+        )?;
         Ok(())
     }
 
@@ -68,8 +91,68 @@ impl<B: Backend> Tui<B> {
     ///
     /// It disables the raw mode and reverts back the terminal properties.
     pub fn exit(&mut self) -> AppResult<()> {
-        Self::reset()?;
+        self.active = false;
+        Self::reset(&mut io::stderr())?;
         self.terminal.show_cursor()?;
         Ok(())
     }
 }
+
+impl<B: Backend> Drop for Tui<B> {
+    fn drop(&mut self) {
+        if self.active {
+            // Nothing can be done about an error while dropping.
+            let _ = self.exit();
+        }
+    }
+}
+
+/// Wraps the panic `hook` to first `restore` the terminal, so that the panic message is
+/// printed on the normal screen.
+fn restoring_hook<F>(hook: PanicHook, restore: F) -> PanicHook
+where
+    F: Fn() -> AppResult<()> + Sync + Send + 'static,
+{
+    Box::new(move |panic| restore_then_report(&restore, || hook(panic)))
+}
+
+/// Run `restore`, then `report` even if the terminal could not be restored.
+fn restore_then_report(restore: &dyn Fn() -> AppResult<()>, report: impl FnOnce()) {
+    // Panicking again in the hook would abort without any message.
+    let _ = restore();
+    report();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn test_panic_report_after_restoring_terminal() {
+        let output = RefCell::new(Vec::new());
+        let restore = || Tui::<TestBackend>::reset(&mut *output.borrow_mut());
+        restore_then_report(&restore, || {
+            output.borrow_mut().extend_from_slice(b"<panic message>")
+        });
+
+        let mut expected = Vec::new();
+        ratatui::crossterm::execute!(
+            expected,
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste
+        )
+        .unwrap();
+        expected.extend_from_slice(b"<panic message>");
+        assert_eq!(*output.borrow(), expected);
+    }
+
+    #[test]
+    fn test_panic_reported_when_restore_fails() {
+        let reported = Cell::new(false);
+        restore_then_report(&|| Err("no terminal".into()), || reported.set(true));
+        assert!(reported.get());
+    }
+}
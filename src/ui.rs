@@ -1,15 +1,387 @@
 mod detail;
+mod footer;
+mod header;
 mod list;
+mod loading;
+mod pinned;
+mod popup;
 mod style;
 
 pub use detail::*;
+pub use footer::*;
+pub use header::*;
 pub use list::*;
+pub use loading::*;
+pub use pinned::*;
+pub use popup::*;
 pub use style::*;
 
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const ELLIPSIS: char = '…';
+/// Cursor drawn in the text inputs, between two characters.
+const CURSOR: char = '▏';
 
 fn option_vec_to_option_slice(option_vec: &Option<Vec<String>>) -> Option<Vec<&str>> {
     let binding = option_vec
         .as_deref()
         .map(|v| v.iter().map(String::as_str).collect::<Vec<&str>>());
     binding
-}
\ No newline at end of file
+}
+
+/// Separator of the authors in the author lists.
+const AUTHOR_SEPARATOR: &str = ", ";
+/// End of an author list cut short.
+const ET_AL: &str = "… et al.";
+
+/// Width of a text in terminal columns, e.g. 2 for each CJK character.
+pub fn text_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Truncate a text to at most `max_cols` terminal columns, ending it with an ellipsis
+/// when cut.
+///
+/// The text is cut between graphemes, so a wide character, an emoji or a letter with its
+/// combining accents is either kept whole or left out.
+pub fn truncate_to_width(text: &str, max_cols: usize) -> Cow<'_, str> {
+    if text_width(text) <= max_cols {
+        return Cow::Borrowed(text);
+    }
+    let budget = max_cols.saturating_sub(ELLIPSIS.width().unwrap_or(1));
+    let mut truncated = take_width(text, budget).to_string();
+    if max_cols > 0 {
+        truncated.push(ELLIPSIS);
+    }
+    Cow::Owned(truncated)
+}
+
+/// The longest start of the text fitting in `max_cols` columns, cut between graphemes.
+fn take_width(text: &str, max_cols: usize) -> &str {
+    let mut width = 0;
+    for (i, grapheme) in text.grapheme_indices(true) {
+        width += text_width(grapheme);
+        if width > max_cols {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// The longest end of the text fitting in `max_cols` columns, cut between graphemes.
+fn take_width_end(text: &str, max_cols: usize) -> &str {
+    let mut width = 0;
+    for (i, grapheme) in text.grapheme_indices(true).rev() {
+        width += text_width(grapheme);
+        if width > max_cols {
+            return &text[i + grapheme.len()..];
+        }
+    }
+    text
+}
+
+/// Text of an input with the cursor drawn between `before` and `after`, scrolled to keep
+/// the cursor within `max_cols` columns: the end is truncated, or the start when the text
+/// before the cursor does not fit alone.
+pub fn scroll_to_cursor(before: &str, after: &str, max_cols: usize) -> String {
+    let budget = max_cols.saturating_sub(1);
+    let before_width = text_width(before);
+    if before_width <= budget {
+        let after = truncate_to_width(after, budget - before_width);
+        return format!("{before}{CURSOR}{after}");
+    }
+    let ellipsis = ELLIPSIS.width().unwrap_or(1);
+    let before = take_width_end(before, budget.saturating_sub(ellipsis));
+    format!("{ELLIPSIS}{before}{CURSOR}")
+}
+
+/// Truncate a styled line like [`truncate_to_width`], keeping the styles of the spans.
+pub fn truncate_line(line: Line<'_>, max_cols: usize) -> Line<'_> {
+    if line.width() <= max_cols {
+        return line;
+    }
+    let mut budget = max_cols.saturating_sub(ELLIPSIS.width().unwrap_or(1));
+    let mut spans = Vec::new();
+    for span in line.spans {
+        let width = span.width();
+        if width > budget {
+            let cut = take_width(&span.content, budget).to_string();
+            spans.push(Span::styled(cut, span.style));
+            break;
+        }
+        budget -= width;
+        spans.push(span);
+    }
+    if max_cols > 0 {
+        spans.push(Span::raw(ELLIPSIS.to_string()));
+    }
+    Line::from(spans)
+}
+
+/// The line with its text copied, to outlive the text it was built from.
+fn owned_line(line: Line) -> Line<'static> {
+    Line::from(
+        line.spans
+            .into_iter()
+            .map(|span| Span::styled(span.content.into_owned(), span.style))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Text split into lines of at most `max_cols` columns, between words when possible.
+///
+/// A word wider than a line is cut between graphemes. The text always gives a line, empty
+/// if the text is.
+pub fn wrap_to_width(text: &str, max_cols: usize) -> Vec<String> {
+    wrap_line(&Line::from(Span::raw(text)), max_cols)
+        .iter()
+        .map(Line::to_string)
+        .collect()
+}
+
+/// Split a styled line like [`wrap_to_width`], keeping the styles of the spans. The words
+/// are joined by a space in the style of the whitespace which separated them.
+pub fn wrap_line(line: &Line<'_>, max_cols: usize) -> Vec<Line<'static>> {
+    let max_cols = max_cols.max(1);
+    // Graphemes of each word with their style, after the style of the space before it.
+    let mut words: Vec<(Style, Vec<(&str, Style)>)> = Vec::new();
+    let mut space = Style::default();
+    let mut in_word = false;
+    for span in &line.spans {
+        for grapheme in span.content.graphemes(true) {
+            if grapheme.chars().all(char::is_whitespace) {
+                if in_word {
+                    space = span.style;
+                }
+                in_word = false;
+                continue;
+            }
+            if !in_word {
+                words.push((space, Vec::new()));
+                in_word = true;
+            }
+            if let Some((_, word)) = words.last_mut() {
+                word.push((grapheme, span.style));
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut spans = Vec::new();
+    let mut width = 0;
+    for (space, word) in words {
+        let word_width: usize = word.iter().map(|(grapheme, _)| text_width(grapheme)).sum();
+        if width > 0 && width + 1 + word_width <= max_cols {
+            push_styled(&mut spans, " ", space);
+            width += 1;
+        } else if width > 0 {
+            lines.push(Line::from(std::mem::take(&mut spans)));
+            width = 0;
+        }
+        // A word wider than the line goes on as many lines as needed, and a grapheme
+        // wider than the line still gets a line of its own.
+        for (grapheme, style) in word {
+            let grapheme_width = text_width(grapheme);
+            if width > 0 && width + grapheme_width > max_cols {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+                width = 0;
+            }
+            push_styled(&mut spans, grapheme, style);
+            width += grapheme_width;
+        }
+    }
+    if !spans.is_empty() || lines.is_empty() {
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Append a text to the last span if it has the same style, else in a new span.
+fn push_styled(spans: &mut Vec<Span<'static>>, text: &str, style: Style) {
+    match spans.last_mut() {
+        Some(span) if span.style == style => span.content.to_mut().push_str(text),
+        _ => spans.push(Span::styled(text.to_string(), style)),
+    }
+}
+
+/// Authors joined by commas in at most `max_cols` columns, e.g. "A, B, C, … et al." if
+/// they do not all fit.
+pub fn format_authors(authors: &[String], max_cols: usize) -> String {
+    let all = authors.join(AUTHOR_SEPARATOR);
+    if text_width(&all) <= max_cols {
+        return all;
+    }
+    let suffix = format!("{AUTHOR_SEPARATOR}{ET_AL}");
+    let budget = max_cols.saturating_sub(text_width(&suffix));
+    let mut listed = String::new();
+    for author in authors {
+        let separator = if listed.is_empty() {
+            ""
+        } else {
+            AUTHOR_SEPARATOR
+        };
+        if text_width(&listed) + text_width(separator) + text_width(author) > budget {
+            break;
+        }
+        listed.push_str(separator);
+        listed.push_str(author);
+    }
+    if listed.is_empty() {
+        // Not even the first author fits with the suffix
+        return truncate_to_width(&all, max_cols).into_owned();
+    }
+    listed.push_str(&suffix);
+    listed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_to_width() {
+        assert_eq!(
+            wrap_to_width("Ada Lovelace, Alan Turing, Grace Hopper", 16),
+            ["Ada Lovelace,", "Alan Turing,", "Grace Hopper"]
+        );
+        assert_eq!(wrap_to_width("Feynman", 4), ["Feyn", "man"]);
+        assert_eq!(wrap_to_width("量子計算", 5), ["量子", "計算"]);
+        assert_eq!(wrap_to_width("", 10), [""]);
+    }
+
+    #[test]
+    fn test_scroll_to_cursor() {
+        assert_eq!(scroll_to_cursor("/quan", "tum", 20), "/quan▏tum");
+        assert_eq!(scroll_to_cursor("/quan", "tum codes", 10), "/quan▏tum…");
+        assert_eq!(scroll_to_cursor("/quantum codes", "", 10), "…um codes▏");
+        // A wide character is never cut in half.
+        assert_eq!(scroll_to_cursor("/量子計算", "", 7), "…計算▏");
+        assert_eq!(scroll_to_cursor("/量子", "計算", 7), "/量子▏…");
+    }
+
+    #[test]
+    fn test_wrap_abstract() {
+        let summary = "We present a scheme for quantum error correction with surface codes \
+             whose logical error rate decreases exponentially with the code distance, \
+             and demonstrate it on a superconducting processor.";
+        let lines = wrap_to_width(summary, 40);
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines,
+            [
+                "We present a scheme for quantum error",
+                "correction with surface codes whose",
+                "logical error rate decreases",
+                "exponentially with the code distance,",
+                "and demonstrate it on a superconducting",
+                "processor.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_line_keeps_styles() {
+        let bold = Style::new().add_modifier(ratatui::style::Modifier::BOLD);
+        let line = Line::from(vec![
+            Span::raw("Surface "),
+            Span::styled("quantum codes", bold),
+            Span::raw(" at scale"),
+        ]);
+        let lines = wrap_line(&line, 16);
+        assert_eq!(
+            lines,
+            [
+                Line::from(vec![Span::raw("Surface "), Span::styled("quantum", bold)]),
+                Line::from(vec![Span::styled("codes", bold), Span::raw(" at scale")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncate_short_text() {
+        assert_eq!(truncate_to_width("Short title", 20), "Short title");
+        assert_eq!(truncate_to_width("Exact", 5), "Exact");
+    }
+
+    #[test]
+    fn test_truncate_long_text() {
+        assert_eq!(truncate_to_width("A rather long title", 8), "A rathe…");
+        assert_eq!(truncate_to_width("A rather long title", 1), "…");
+        assert_eq!(truncate_to_width("A rather long title", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_multibyte_text() {
+        let title = "Schrödinger's équation in 量子 systems";
+        let truncated = truncate_to_width(title, 14);
+        assert_eq!(truncated, "Schrödinger's…");
+        assert_eq!(text_width(&truncated), 14);
+    }
+
+    #[test]
+    fn test_truncate_cjk_text() {
+        // Each character takes two columns, the ellipsis one.
+        let truncated = truncate_to_width("量子計算の誤り訂正", 8);
+        assert_eq!(truncated, "量子計…");
+        assert_eq!(text_width(&truncated), 7);
+
+        let truncated = truncate_to_width("量子計算の誤り訂正", 9);
+        assert_eq!(truncated, "量子計算…");
+        assert_eq!(text_width(&truncated), 9);
+    }
+
+    #[test]
+    fn test_truncate_combining_accents() {
+        // "é" written as "e" followed by a combining acute accent.
+        let title = "Re\u{301}sume\u{301} of the results";
+        let truncated = truncate_to_width(title, 7);
+        assert_eq!(truncated, "Re\u{301}sume\u{301}…");
+        assert_eq!(text_width(&truncated), 7);
+
+        let truncated = truncate_to_width(title, 2);
+        assert_eq!(truncated, "R…");
+    }
+
+    #[test]
+    fn test_truncate_emoji() {
+        let title = "Qubits 🚀🚀 and 👩‍🔬 lab notes";
+        for max_cols in 0..text_width(title) {
+            let truncated = truncate_to_width(title, max_cols);
+            assert!(text_width(&truncated) <= max_cols);
+        }
+        assert_eq!(truncate_to_width(title, 10), "Qubits 🚀…");
+    }
+
+    #[test]
+    fn test_truncate_cut_inside_codepoint() {
+        // The 47th byte falls in the middle of the 'é'.
+        let title = format!("{}é and more text after the cut", "a".repeat(46));
+        assert!(!title.is_char_boundary(47));
+
+        let truncated = truncate_to_width(&title, 48);
+        assert_eq!(truncated, format!("{}é…", "a".repeat(46)));
+    }
+
+    #[test]
+    fn test_format_authors() {
+        let authors: Vec<String> = ["Curie", "Bohr", "Schrödinger", "Heisenberg"]
+            .iter()
+            .map(|author| author.to_string())
+            .collect();
+        assert_eq!(
+            format_authors(&authors, 80),
+            "Curie, Bohr, Schrödinger, Heisenberg"
+        );
+        assert_eq!(format_authors(&authors, 30), "Curie, Bohr, … et al.");
+        assert!(text_width(&format_authors(&authors, 30)) <= 30);
+        assert_eq!(format_authors(&authors, 8), "Curie, …");
+        assert_eq!(format_authors(&[], 8), "");
+
+        let authors = vec!["湯川秀樹".to_string(), "朝永振一郎".to_string()];
+        assert_eq!(format_authors(&authors, 19), "湯川秀樹, … et al.");
+    }
+}
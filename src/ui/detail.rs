@@ -1,55 +1,162 @@
 use crate::arxiv::ArxivEntry;
 use crate::config::HighlightConfig;
-use crate::search_highlight::highlight_patterns;
-use crate::ui::Theme;
+use crate::date::{format_date, DateFormat};
+use crate::export::arxiv_version;
+use crate::search::SearchState;
+use crate::search_highlight::{fuzzy_line, highlight_patterns};
+use crate::ui::{format_authors, wrap_line, Theme};
 
-use super::option_vec_to_option_slice;
-use itertools::izip;
+use super::{option_vec_to_option_slice, owned_line};
+use chrono::{DateTime, Utc};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
     Frame,
 };
 
+/// Rows of the authors section filled by the author list, one left for the wrapping.
+const AUTHOR_ROWS: usize = 4;
+/// Columns taken by the margin and the padding of the sections.
+const SECTION_MARGINS: u16 = 8;
+/// Width of the labels of the dates section.
+const DATE_LABEL_WIDTH: usize = 14;
+
+/// Rows of the dates section: the publication, the last update, flagged when the
+/// article was revised, and the version.
+fn date_lines(
+    entry: &ArxivEntry,
+    date_format: &DateFormat,
+    now: DateTime<Utc>,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{label:<DATE_LABEL_WIDTH$}"), theme.title),
+            Span::styled(value, theme.main),
+        ])
+    };
+    let mut updated = row(
+        "Last updated",
+        format_date(&entry.updated, date_format, now),
+    );
+    if entry.is_revised() {
+        updated.spans.push(Span::styled(" revised", theme.tag));
+    }
+    let mut lines = vec![
+        row("Published", format_date(&entry.published, date_format, now)),
+        updated,
+    ];
+    if let Some(version) = arxiv_version(entry) {
+        lines.push(row("Version", version.to_string()));
+    }
+    lines
+}
+
 pub struct ArticleDetails<'a> {
     title: Line<'a>,
     authors: Line<'a>,
     summary: Line<'a>,
-    updated: Line<'a>,
+    /// Maximum width of the lines of the abstract, see [`Self::with_summary_width`].
+    summary_width: Option<usize>,
+    dates: Vec<Line<'a>>,
 }
 
 impl<'a> ArticleDetails<'a> {
-    pub fn new(entry: &'a ArxivEntry, highlight_config: &HighlightConfig, theme: &Theme) -> Self {
+    /// Details of the entry, with the keywords, the authors and the matches of the search
+    /// highlighted in their own style.
+    ///
+    /// A long author list is cut short to fit in a pane of the given width.
+    pub fn new(
+        entry: &'a ArxivEntry,
+        width: u16,
+        highlight_config: &HighlightConfig,
+        search_state: &mut SearchState,
+        date_format: &DateFormat,
+        theme: &Theme,
+    ) -> Self {
+        let now = Utc::now();
+        let authors_width = usize::from(width.saturating_sub(SECTION_MARGINS)) * AUTHOR_ROWS;
         let author_patterns = option_vec_to_option_slice(&highlight_config.authors);
         let keyword_patterns = option_vec_to_option_slice(&highlight_config.keywords);
+        let keywords = |text: &'a str| {
+            highlight_patterns(
+                text,
+                keyword_patterns.as_deref(),
+                theme.main,
+                theme.highlight,
+            )
+        };
+        Self {
+            title: fuzzy_line(
+                keywords(&entry.title),
+                &search_state.highlight_indices(&entry.title),
+                theme.search_highlight,
+            ),
+            authors: owned_line(highlight_patterns(
+                &format_authors(&entry.authors, authors_width),
+                author_patterns.as_deref(),
+                theme.main,
+                theme.author_highlight,
+            )),
+            summary: fuzzy_line(
+                keywords(&entry.summary),
+                &search_state.highlight_indices(&entry.summary),
+                theme.search_highlight,
+            ),
+            summary_width: None,
+            dates: date_lines(entry, date_format, now, theme),
+        }
+    }
+
+    /// Wrap the abstract in lines of at most `max_cols` columns, if narrower than the
+    /// pane.
+    pub fn with_summary_width(mut self, max_cols: Option<usize>) -> Self {
+        self.summary_width = max_cols;
+        self
+    }
+
+    /// Details showing a message instead of an article, when the feed is empty.
+    pub fn placeholder(message: &'a str) -> Self {
         Self {
-            title: highlight_patterns(&entry.title, keyword_patterns.as_deref(), theme),
-            authors: highlight_patterns(entry.get_all_authors(), author_patterns.as_deref(), theme),
-            summary: highlight_patterns(&entry.summary, keyword_patterns.as_deref(), theme),
-            updated: Line::raw(&entry.updated).style(theme.main),
+            title: Line::from(message),
+            authors: Line::default(),
+            summary: Line::default(),
+            summary_width: None,
+            dates: Vec::new(),
         }
     }
 
+    /// Render the sections with some content, the abstract taking the rows left.
     pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        // One row per date, below the border
+        let dates_height = self.dates.len() as u16 + 1;
+        let summary_width = usize::from(area.width.saturating_sub(SECTION_MARGINS));
+        let summary_width = self
+            .summary_width
+            .map_or(summary_width, |max_cols| summary_width.min(max_cols));
+        let sections = [
+            (" Title ", vec![self.title], Constraint::Length(4)),
+            (" Author ", vec![self.authors], Constraint::Length(6)),
+            (
+                " Abstract ",
+                wrap_line(&self.summary, summary_width),
+                Constraint::Min(10),
+            ),
+            (" Dates ", self.dates, Constraint::Length(dates_height)),
+        ]
+        .into_iter()
+        .filter(|(_, lines, _)| lines.iter().any(|line| line.width() > 0))
+        .collect::<Vec<_>>();
         let sub_layout = Layout::default()
             .direction(Direction::Vertical)
             .horizontal_margin(2)
-            .constraints([
-                Constraint::Length(4), // Title
-                Constraint::Length(6), // Authors
-                Constraint::Min(10),   // Abstract/summary
-                Constraint::Length(2), // Last update
-            ])
+            .constraints(sections.iter().map(|(_, _, constraint)| *constraint))
             .split(area);
 
-        let titles_sec = vec![" Title ", " Author ", " Abstract ", "Updated"];
-        let areas = vec![sub_layout[0], sub_layout[1], sub_layout[2], sub_layout[3]];
-        let items = vec![&self.title, &self.authors, &self.summary, &self.updated];
-
-        for (title, entry, area) in izip!(titles_sec, items, areas) {
+        for ((title, lines, _), area) in sections.into_iter().zip(sub_layout.iter()) {
             frame.render_widget(
-                Paragraph::new(entry.clone())
+                Paragraph::new(lines)
                     .block(
                         Block::new()
                             .borders(Borders::TOP)
@@ -62,8 +169,89 @@ impl<'a> ArticleDetails<'a> {
                     .style(theme.main)
                     .left_aligned()
                     .wrap(Wrap { trim: true }),
-                area,
+                *area,
             )
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_wrap_width() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let entry = ArxivEntry::new(
+            "Title".to_string(),
+            vec!["Author".to_string()],
+            "Surface codes protect logical qubits from local noise".to_string(),
+            "http://arxiv.org/abs/2407.01234v1".to_string(),
+            "2024-07-10T12:00:00Z".to_string(),
+            "2024-07-10T12:00:00Z".to_string(),
+        );
+        let lines = |summary_width| {
+            let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+            terminal
+                .draw(|frame| {
+                    ArticleDetails::new(
+                        &entry,
+                        80,
+                        &HighlightConfig::default(),
+                        &mut SearchState::default(),
+                        &DateFormat::default(),
+                        &Theme::mono(),
+                    )
+                    .with_summary_width(summary_width)
+                    .render(frame, frame.size(), &Theme::mono())
+                })
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            (0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer.get(x, y).symbol())
+                        .collect::<String>()
+                        .trim()
+                        .to_string()
+                })
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<String>>()
+        };
+
+        assert!(lines(None)
+            .contains(&"Surface codes protect logical qubits from local noise".to_string()));
+        let narrow = lines(Some(20));
+        assert!(narrow.contains(&"Surface codes".to_string()));
+        assert!(narrow.contains(&"protect logical".to_string()));
+        assert!(narrow.contains(&"qubits from local".to_string()));
+    }
+
+    #[test]
+    fn test_date_lines_revised() {
+        let entry = ArxivEntry::new(
+            "Title".to_string(),
+            vec!["Author".to_string()],
+            String::new(),
+            "http://arxiv.org/abs/2407.01234v2".to_string(),
+            "2024-07-10T12:00:00Z".to_string(),
+            "2024-07-08T12:00:00Z".to_string(),
+        );
+        let now = DateTime::parse_from_rfc3339("2024-07-11T12:00:00Z")
+            .unwrap()
+            .to_utc();
+        let lines: Vec<String> = date_lines(&entry, &DateFormat::Relative, now, &Theme::mono())
+            .iter()
+            .map(Line::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "Published     3 days ago",
+                "Last updated  1 day ago revised",
+                "Version       v2",
+            ]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use crate::ui::{text_width, Theme};
+use ratatui::{
+    layout::Rect,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+/// A shortcut of the footer, as `(action, key)`.
+pub type Shortcut = (&'static str, &'static str);
+
+const MARGIN: &str = "   ";
+const SEPARATOR: &str = " | ";
+/// Number of hints for the secondary actions of the context shown after the shortcuts.
+const MAX_HINTS: usize = 2;
+
+/// Footer of the interface: the shortcuts of the current context, or a status message,
+/// on the left, and the counts of the feed on the right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footer {
+    text: String,
+    counts: String,
+}
+
+impl Footer {
+    /// Footer with an optional label, the main shortcuts then up to two hints, as many
+    /// as fit in `width` next to the counts. The shortcuts which do not fit are left out,
+    /// the hints first; the counts are only left out if they do not fit alone.
+    pub fn shortcuts(
+        label: Option<&str>,
+        shortcuts: &[Shortcut],
+        hints: &[Shortcut],
+        counts: String,
+        width: u16,
+    ) -> Self {
+        let counts_width = text_width(&counts) + 1;
+        let (counts, width) = match usize::from(width).checked_sub(counts_width) {
+            Some(width) => (counts, width),
+            None => (String::new(), usize::from(width)),
+        };
+        let mut text = MARGIN.to_string();
+        let mut first = true;
+        let mut push = |item: String, text: &mut String| {
+            let item = if first {
+                item
+            } else {
+                format!("{SEPARATOR}{item}")
+            };
+            if text_width(text) + text_width(&item) > width {
+                return false;
+            }
+            text.push_str(&item);
+            first = false;
+            true
+        };
+
+        let items = label.map(str::to_string).into_iter().chain(
+            shortcuts
+                .iter()
+                .map(|(action, key)| format!("{action}: {key}")),
+        );
+        for item in items {
+            if !push(item, &mut text) {
+                return Self { text, counts };
+            }
+        }
+        for (action, key) in hints.iter().take(MAX_HINTS) {
+            if !push(format!("{action}: {key}"), &mut text) {
+                break;
+            }
+        }
+        Self { text, counts }
+    }
+
+    /// Footer showing a status message, the counts drawn over its end if too long.
+    pub fn message(message: &str, counts: String) -> Self {
+        Self {
+            text: format!("{MARGIN}{message}"),
+            counts,
+        }
+    }
+
+    /// Footer showing the command typed after `:`, followed by its error or completions.
+    pub fn prompt(input: &str, message: Option<&str>) -> Self {
+        let text = match message {
+            Some(message) => format!("{MARGIN}:{input}{MARGIN}{message}"),
+            None => format!("{MARGIN}:{input}"),
+        };
+        Self {
+            text,
+            counts: String::new(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn counts(&self) -> &str {
+        &self.counts
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        frame.render_widget(
+            Paragraph::new(self.text)
+                .style(theme.shortcut)
+                .left_aligned()
+                .block(Block::new()),
+            area,
+        );
+        if !self.counts.is_empty() {
+            let width = (text_width(&self.counts) as u16 + 1).min(area.width);
+            let counts_area = Rect {
+                x: area.right() - width,
+                width,
+                ..area
+            };
+            frame.render_widget(
+                Paragraph::new(self.counts)
+                    .style(theme.shortcut)
+                    .left_aligned(),
+                counts_area,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORTCUTS: &[Shortcut] = &[("quit", "q"), ("search", "/")];
+    const HINTS: &[Shortcut] = &[("open all", "O"), ("watch", "+a/+k"), ("help", "?")];
+
+    #[test]
+    fn test_footer_with_hints() {
+        let footer = Footer::shortcuts(None, SHORTCUTS, HINTS, String::new(), 120);
+        assert_eq!(
+            footer.text(),
+            "   quit: q | search: / | open all: O | watch: +a/+k"
+        );
+    }
+
+    #[test]
+    fn test_footer_drops_what_does_not_fit() {
+        let footer = Footer::shortcuts(None, SHORTCUTS, HINTS, String::new(), 40);
+        assert_eq!(footer.text(), "   quit: q | search: / | open all: O");
+
+        let footer = Footer::shortcuts(Some("Title-only"), SHORTCUTS, HINTS, String::new(), 30);
+        assert_eq!(footer.text(), "   Title-only | quit: q");
+    }
+
+    #[test]
+    fn test_footer_counts_win_over_shortcuts() {
+        let footer = Footer::shortcuts(None, SHORTCUTS, HINTS, "12/200".to_string(), 40);
+        assert_eq!(footer.text(), "   quit: q | search: /");
+        assert_eq!(footer.counts(), "12/200");
+
+        let footer = Footer::shortcuts(None, SHORTCUTS, HINTS, "12/200".to_string(), 5);
+        assert_eq!(footer.counts(), "");
+    }
+}
@@ -0,0 +1,79 @@
+use crate::ui::{text_width, truncate_to_width, Theme};
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+const MARGIN: &str = "   ";
+const SEPARATOR: &str = " · ";
+
+/// Header of the interface: the query of the feed, when arXiv updated it and when it
+/// was last refreshed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    text: String,
+}
+
+impl Header {
+    /// Header with as many of the parts as fit in `width`, the last ones left out first.
+    /// The first part is truncated if it does not fit alone.
+    pub fn new(parts: &[String], width: u16) -> Self {
+        let width = usize::from(width);
+        let mut text = MARGIN.to_string();
+        for (i, part) in parts.iter().enumerate() {
+            let item = if i == 0 {
+                part.clone()
+            } else {
+                format!("{SEPARATOR}{part}")
+            };
+            if text_width(&text) + text_width(&item) <= width {
+                text.push_str(&item);
+            } else {
+                if i == 0 {
+                    let available = width.saturating_sub(MARGIN.len());
+                    text.push_str(&truncate_to_width(part, available));
+                }
+                break;
+            }
+        }
+        Self { text }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        frame.render_widget(Paragraph::new(self.text).style(theme.title), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts() -> Vec<String> {
+        vec![
+            "cat:quant-ph · sorted by submittedDate desc".to_string(),
+            "updated 2024-07-09 20:00".to_string(),
+            "refreshed 2024-07-10 08:15".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_header_wide() {
+        assert_eq!(
+            Header::new(&parts(), 120).text(),
+            "   cat:quant-ph · sorted by submittedDate desc · updated 2024-07-09 20:00 · refreshed 2024-07-10 08:15"
+        );
+    }
+
+    #[test]
+    fn test_header_collapses() {
+        assert_eq!(
+            Header::new(&parts(), 80).text(),
+            "   cat:quant-ph · sorted by submittedDate desc · updated 2024-07-09 20:00"
+        );
+        assert_eq!(
+            Header::new(&parts(), 30).text(),
+            "   cat:quant-ph · sorted by s…"
+        );
+    }
+}
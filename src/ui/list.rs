@@ -1,60 +1,613 @@
 use crate::arxiv::ArxivQueryResult;
-use crate::ui::Theme;
-use ratatui::widgets::{List, ListState};
+use crate::date::{format_date, DateFormat};
+use crate::search::{CrossListMode, SearchState};
+use crate::search_highlight::{fuzzy_line, highlight_patterns, search_patterns};
+use crate::ui::{owned_line, truncate_line, truncate_to_width, Theme};
+use chrono::Utc;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListState, Paragraph, Wrap};
 use ratatui::{
     layout::{Alignment, Rect},
+    style::{Modifier, Style},
     widgets::{Block, HighlightSpacing, ListDirection, ListItem},
     Frame,
 };
+use serde::{Deserialize, Serialize};
+
+/// Message shown in place of the articles when there are none to show.
+///
+/// arXiv can legitimately return no entries, e.g. for a new category on a weekend; the
+/// search can also filter out every article.
+pub fn empty_feed_message(query_result: &ArxivQueryResult) -> &'static str {
+    if query_result.articles.is_empty() {
+        "The feed is empty: arXiv returned no articles for this query."
+    } else {
+        "No article matches the search."
+    }
+}
+
+/// Highlight symbol of the selected article, always taking its space.
+const HIGHLIGHT_SYMBOL: &str = "> ";
+/// Minimum number of characters of the title for the badges to be shown; below, the
+/// pane is too narrow and the badges are left out.
+const MIN_TITLE_WIDTH: usize = 20;
+/// Marker of the articles whose title contains a keyword of the config.
+const KEYWORD_MARKER: &str = "• ";
+/// Tag of the articles whose title is matched by the search.
+const TITLE_MATCH_TAG: &str = "[T]";
+/// Tag of the articles whose authors are matched by the search.
+const AUTHOR_MATCH_TAG: &str = "[Au]";
+/// Tag of the articles whose abstract is matched by the search.
+const ABSTRACT_MATCH_TAG: &str = "[A]";
+
+/// How the feed scrolls to follow the selection.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollMode {
+    /// Scroll only when the selection would leave the pane, keeping it at the edge.
+    #[default]
+    Edge,
+    /// Keep the selection in the middle of the pane, as far as the articles allow.
+    Centered,
+}
+
+/// How the articles of the feed are shown.
+#[derive(Debug, Default)]
+pub struct FeedOptions<'o> {
+    /// The articles of these authors are highlighted.
+    pub highlight_authors: Option<&'o [&'o str]>,
+    /// These keywords are highlighted in the titles, which get a marker.
+    pub highlight_keywords: Option<&'o [&'o str]>,
+    /// Titles longer than this are truncated with an ellipsis.
+    pub max_title_len: Option<usize>,
+    /// The articles published after this time get a "NEW" badge.
+    pub new_since: Option<&'o str>,
+    /// Format of the publication dates.
+    pub date_format: DateFormat,
+    /// Categories of the query, the articles submitted to another one are cross-lists.
+    pub queried_categories: Vec<&'o str>,
+    /// Page of the results, e.g. "page 1 of 22", shown in the title of the feed.
+    pub page_label: Option<String>,
+    /// Each title is followed by the start of the abstract, on a line of its own.
+    pub abstract_snippet: bool,
+    /// How the feed scrolls to follow the selection.
+    pub scroll_mode: ScrollMode,
+}
+
+/// An article of the feed, laid out once the width of the pane is known.
+#[derive(Debug)]
+struct FeedRow<'a> {
+    /// "NEW" badge and keyword marker, if any.
+    prefix: Vec<Span<'a>>,
+    title: Line<'a>,
+    /// Primary category and tags, shown after the title if the pane is wide enough.
+    badges: Vec<Span<'a>>,
+    date: Span<'a>,
+    /// Abstract on a single line, truncated to the width of the pane when shown.
+    snippet: Option<String>,
+    style: Style,
+}
+
+impl<'a> FeedRow<'a> {
+    /// Line of the article in a pane of the given inner width.
+    ///
+    /// The title is truncated to keep the badges visible, unless that leaves less than
+    /// [`MIN_TITLE_WIDTH`] characters for it, in which case the badges are left out.
+    fn line(&self, width: usize) -> Line<'a> {
+        let prefix: usize = self.prefix.iter().map(Span::width).sum();
+        let fixed = prefix + self.date.width();
+        let badges: usize = self.badges.iter().map(Span::width).sum();
+        let title_room = width.saturating_sub(fixed + badges);
+
+        let mut spans = self.prefix.clone();
+        if !self.badges.is_empty() && title_room >= MIN_TITLE_WIDTH {
+            spans.extend(truncate_line(self.title.clone(), title_room).spans);
+            spans.extend(self.badges.iter().cloned());
+        } else {
+            spans.extend(self.title.spans.iter().cloned());
+        }
+        spans.push(self.date.clone());
+        Line::from(spans)
+    }
+
+    /// Number of lines of the article.
+    fn height(&self) -> usize {
+        1 + usize::from(self.snippet.is_some())
+    }
+
+    /// Lines of the article in a pane of the given inner width: its [`line`](Self::line),
+    /// then the snippet of its abstract, if any.
+    fn lines(&self, width: usize) -> Vec<Line<'a>> {
+        let mut lines = vec![self.line(width)];
+        if let Some(snippet) = &self.snippet {
+            lines.push(Line::styled(
+                truncate_to_width(snippet, width).into_owned(),
+                Modifier::DIM,
+            ));
+        }
+        lines
+    }
+}
+
+/// Offset of a list of items of the given heights keeping the `selected` one in the
+/// middle of a pane of the given inner height.
+///
+/// Near the start and the end of the list, the offset is clamped so that the pane stays
+/// filled with items.
+fn centered_offset(heights: &[usize], selected: usize, height: usize) -> usize {
+    let Some(selected_height) = heights.get(selected) else {
+        return 0;
+    };
+    let mut above = height.saturating_sub(*selected_height) / 2;
+    let mut offset = selected;
+    while offset > 0 && heights[offset - 1] <= above {
+        above -= heights[offset - 1];
+        offset -= 1;
+    }
+    // First item from which all the remaining ones fit in the pane.
+    let mut last_offset = heights.len();
+    let mut filled = 0;
+    while last_offset > 0 && filled + heights[last_offset - 1] <= height {
+        filled += heights[last_offset - 1];
+        last_offset -= 1;
+    }
+    offset.min(last_offset)
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of times the items of a feed were laid out, to check they are reused.
+    static LAYOUTS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
 
 #[derive(Debug)]
 pub struct ArticleFeed<'a> {
-    items: List<'a>,
+    title: String,
+    rows: Vec<FeedRow<'a>>,
+    /// List of the rows laid out for the inner width it was built for, reused by the
+    /// next frames until the width changes.
+    list: Option<(usize, List<'a>)>,
+    /// Shown instead of the list when there are no articles.
+    empty_message: Option<&'static str>,
+    scroll_mode: ScrollMode,
     pub state: ListState,
 }
 
 impl<'a> ArticleFeed<'a> {
+    /// Construct the feed out of the articles matching the search, in its order.
+    ///
+    /// The keywords of the config are highlighted in the titles, with the characters
+    /// matched by the search on top. Each title is followed by the primary category of
+    /// the article, a "cross-list" tag if it is not one of the queried categories, an
+    /// "updated" tag if it was revised, tags telling whether the search matched its title
+    /// or its abstract, and its publication date. The start of the abstract can be shown
+    /// below. The cross-lists are dimmed if the search puts them last.
     pub fn new(
         query_result: &ArxivQueryResult,
-        highlight_authors: Option<&[&str]>,
+        search_state: &mut SearchState,
+        options: &FeedOptions,
         theme: &Theme,
     ) -> Self {
-        let items: Vec<ListItem> = query_result
-            .articles
+        let indices = search_state.filtered_indices.clone();
+        let now = Utc::now();
+        let badge =
+            |text: &str, style: Style| [Span::raw(" "), Span::styled(text.to_string(), style)];
+        let dim_cross_lists = search_state.cross_lists() == CrossListMode::Dim;
+        let rows = indices
             .iter()
-            .map(|entry| {
-                ListItem::from(entry.title.clone()).style(
-                    if entry.contains_author(highlight_authors) {
-                        theme.title
-                    } else {
-                        theme.main
-                    },
-                )
+            .map(|&i| {
+                let entry = &query_result.articles[i];
+                let keywords = highlight_patterns(
+                    &entry.title,
+                    options.highlight_keywords,
+                    Style::default(),
+                    theme.highlight,
+                );
+                let indices = search_state.highlight_indices(&entry.title);
+                let title = owned_line(fuzzy_line(keywords, &indices, theme.search_highlight));
+                let title = match options.max_title_len {
+                    Some(max_len) => truncate_line(title, max_len),
+                    None => title,
+                };
+
+                let mut prefix = Vec::new();
+                if options
+                    .new_since
+                    .is_some_and(|since| entry.is_published_after(since))
+                {
+                    prefix.push(Span::styled(
+                        "NEW ",
+                        theme.title.add_modifier(Modifier::BOLD),
+                    ));
+                }
+                let has_keyword = options
+                    .highlight_keywords
+                    .is_some_and(|keywords| !search_patterns(&entry.title, keywords).is_empty());
+                if has_keyword {
+                    prefix.push(Span::styled(KEYWORD_MARKER, theme.highlight));
+                }
+
+                let mut badges = Vec::new();
+                if let Some(category) = &entry.primary_category {
+                    badges.extend(badge(category, theme.category_badge));
+                }
+                if entry.is_cross_list(&options.queried_categories) {
+                    badges.extend(badge("cross-list", theme.tag));
+                }
+                if entry.is_revised() {
+                    badges.extend(badge("updated", theme.tag));
+                }
+                if let Some(matched) = search_state.matched_fields(i) {
+                    if matched.title {
+                        badges.extend(badge(TITLE_MATCH_TAG, theme.tag));
+                    }
+                    if matched.authors {
+                        badges.extend(badge(AUTHOR_MATCH_TAG, theme.tag));
+                    }
+                    if matched.summary {
+                        badges.extend(badge(ABSTRACT_MATCH_TAG, theme.tag));
+                    }
+                }
+
+                let mut style = if entry.contains_author(options.highlight_authors) {
+                    theme.author_highlight
+                } else {
+                    theme.main
+                };
+                if dim_cross_lists && search_state.is_cross_list(i) {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                let date = format_date(&entry.published, &options.date_format, now);
+                FeedRow {
+                    prefix,
+                    title,
+                    badges,
+                    date: Span::styled(format!("  {date}"), Modifier::DIM),
+                    snippet: options.abstract_snippet.then(|| {
+                        entry
+                            .summary
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    }),
+                    style,
+                }
             })
             .collect();
 
+        let title = match &options.page_label {
+            Some(page) => format!("arXiv Feed · {page}"),
+            None => "arXiv Feed".to_string(),
+        };
+        Self {
+            title,
+            rows,
+            list: None,
+            empty_message: indices.is_empty().then(|| empty_feed_message(query_result)),
+            scroll_mode: options.scroll_mode,
+            state: ListState::default(),
+        }
+    }
+
+    /// Render the feed, with the selection highlighted only if it is `focused`.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme, focused: bool) {
+        if let Some(message) = self.empty_message {
+            frame.render_widget(
+                Paragraph::new(message)
+                    .block(self.block(theme))
+                    .style(theme.main)
+                    .wrap(Wrap { trim: true }),
+                area,
+            );
+            return;
+        }
+
+        // Width of the lines, inside the borders and after the highlight symbol
+        let width = usize::from(area.width).saturating_sub(2 + HIGHLIGHT_SYMBOL.len());
+        if self.list.as_ref().map(|(built_for, _)| *built_for) != Some(width) {
+            self.list = Some((width, self.layout(width, theme)));
+        }
+        let Some((_, list)) = &self.list else {
+            return;
+        };
+        if let (ScrollMode::Centered, Some(selected)) = (self.scroll_mode, self.state.selected()) {
+            let heights: Vec<usize> = self.rows.iter().map(FeedRow::height).collect();
+            let height = usize::from(area.height.saturating_sub(2));
+            *self.state.offset_mut() = centered_offset(&heights, selected, height);
+        }
+        if focused {
+            frame.render_stateful_widget(list, area, &mut self.state);
+        } else {
+            // Hide the selection without losing it, nor resetting the offset
+            let selected = self.state.selected();
+            *self.state.selected_mut() = None;
+            frame.render_stateful_widget(list, area, &mut self.state);
+            *self.state.selected_mut() = selected;
+        }
+    }
+
+    /// Border of the feed, titled with the page of the results.
+    fn block(&self, theme: &Theme) -> Block<'a> {
+        Block::bordered()
+            .title_style(theme.title)
+            .title_alignment(Alignment::Left)
+            .title(self.title.clone())
+    }
+
+    /// List of the rows for a pane of the given inner width.
+    fn layout(&self, width: usize, theme: &Theme) -> List<'a> {
+        #[cfg(test)]
+        LAYOUTS.with(|layouts| layouts.set(layouts.get() + 1));
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| ListItem::new(row.lines(width)).style(row.style))
+            .collect();
+
         // Create a List from all list items and highlight the currently selected one
-        let items = List::new(items.clone())
-            .block(
-                Block::bordered()
-                    .title_style(theme.title)
-                    .title_alignment(Alignment::Left)
-                    .title("arXiv Feed"),
-            )
+        List::new(items)
+            .block(self.block(theme))
             .style(theme.main)
             .highlight_style(theme.selection)
-            .highlight_symbol("> ")
+            .highlight_symbol(HIGHLIGHT_SYMBOL)
             .repeat_highlight_symbol(true)
             .direction(ListDirection::TopToBottom)
-            .highlight_spacing(HighlightSpacing::Always);
+            .highlight_spacing(HighlightSpacing::Always)
+    }
+}
 
-        Self {
-            items,
-            state: ListState::default(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arxiv::ArxivEntry;
+    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+    const KEYWORDS: &[&str] = &["transformer"];
+
+    fn feed() -> ArxivQueryResult {
+        let article = |title: &str, primary: &str, updated: &str| {
+            ArxivEntry::new(
+                title.to_string(),
+                vec!["Author".to_string()],
+                "We study  the abstract\n  of the article.".to_string(),
+                format!("http://arxiv.org/abs/{title}"),
+                updated.to_string(),
+                "2024-07-08T20:00:00Z".to_string(),
+            )
+            .with_categories(Some(primary.to_string()), vec![primary.to_string()])
+        };
+        ArxivQueryResult {
+            updated: "2024-07-09T20:00:00Z".to_string(),
+            total_results: None,
+            articles: vec![
+                article(
+                    "Surface code decoding with transformers",
+                    "cs.LG",
+                    "2024-07-08T20:00:00Z",
+                ),
+                article("Bosonic codes", "quant-ph", "2024-07-09T10:00:00Z"),
+            ],
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
-        frame.render_stateful_widget(&self.items, area, &mut self.state);
+    fn render(width: u16) -> Buffer {
+        render_with(width, 4, false)
+    }
+
+    fn render_with(width: u16, height: u16, abstract_snippet: bool) -> Buffer {
+        let query_result = feed();
+        let options = FeedOptions {
+            // A format without any field, so the output does not depend on the time zone
+            date_format: DateFormat::Strftime("Jul 8".to_string()),
+            queried_categories: vec!["quant-ph"],
+            abstract_snippet,
+            ..Default::default()
+        };
+        let theme = Theme::mono();
+        let mut search_state = SearchState::default();
+        search_state.set_articles(&query_result.articles, 0);
+        let mut feed = ArticleFeed::new(&query_result, &mut search_state, &options, &theme);
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| feed.render(frame, frame.size(), &theme, true))
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, Style::reset());
+        buffer
+    }
+
+    #[test]
+    fn test_scroll_centered() {
+        let article = |i: usize| {
+            ArxivEntry::new(
+                format!("Article {i}"),
+                vec!["Author".to_string()],
+                String::new(),
+                format!("http://arxiv.org/abs/{i}"),
+                "2024-07-08T20:00:00Z".to_string(),
+                "2024-07-08T20:00:00Z".to_string(),
+            )
+        };
+        let query_result = ArxivQueryResult {
+            articles: (0..30).map(article).collect(),
+            ..Default::default()
+        };
+        let offset = |scroll_mode: ScrollMode, selections: &[usize]| {
+            let options = FeedOptions {
+                scroll_mode,
+                ..Default::default()
+            };
+            let theme = Theme::mono();
+            let mut search_state = SearchState::default();
+            search_state.set_articles(&query_result.articles, 0);
+            let mut feed = ArticleFeed::new(&query_result, &mut search_state, &options, &theme);
+            // 10 rows inside the borders.
+            let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+            for &selected in selections {
+                feed.state.select(Some(selected));
+                terminal
+                    .draw(|frame| feed.render(frame, frame.size(), &theme, true))
+                    .unwrap();
+            }
+            feed.state.offset()
+        };
+
+        // Moving down one article at a time keeps the selection in the middle.
+        let down: Vec<usize> = (0..=15).collect();
+        assert_eq!(offset(ScrollMode::Centered, &down), 15 - 4);
+        assert_eq!(offset(ScrollMode::Edge, &down), 15 - 9);
+        // The pane stays filled at both ends of the feed.
+        assert_eq!(offset(ScrollMode::Centered, &[2]), 0);
+        assert_eq!(offset(ScrollMode::Centered, &[28]), 20);
+    }
+
+    #[test]
+    fn test_centered_offset() {
+        // Two lines per article with the snippets: two articles above the selection.
+        assert_eq!(centered_offset(&[2; 10], 5, 10), 3);
+        assert_eq!(centered_offset(&[1; 5], 3, 10), 0);
+        assert_eq!(centered_offset(&[], 0, 10), 0);
+    }
+
+    fn golden(lines: &[&str]) -> Buffer {
+        let mut buffer = Buffer::with_lines(lines.iter().copied());
+        buffer.set_style(buffer.area, Style::reset());
+        buffer
+    }
+
+    #[test]
+    fn test_badges_wide() {
+        assert_eq!(
+            render(70),
+            golden(&[
+                "┌arXiv Feed──────────────────────────────────────────────────────────┐",
+                "│  Surface code decoding with transformers cs.LG cross-list  Jul 8   │",
+                "│  Bosonic codes quant-ph updated  Jul 8                             │",
+                "└────────────────────────────────────────────────────────────────────┘",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_badges_narrow() {
+        assert_eq!(
+            render(40),
+            golden(&[
+                "┌arXiv Feed────────────────────────────┐",
+                "│  Surface code decoding with transform│",
+                "│  Bosonic codes  Jul 8                │",
+                "└──────────────────────────────────────┘",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_abstract_snippet() {
+        // The abstract is on a single line, cut to the width of the pane.
+        assert_eq!(
+            render_with(30, 6, true),
+            golden(&[
+                "┌arXiv Feed──────────────────┐",
+                "│  Surface code decoding with│",
+                "│  We study the abstract of …│",
+                "│  Bosonic codes  Jul 8      │",
+                "│  We study the abstract of …│",
+                "└────────────────────────────┘",
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_layout_reused_across_frames() {
+        let query_result = feed();
+        let options = FeedOptions::default();
+        let theme = Theme::mono();
+        let mut search_state = SearchState::default();
+        search_state.set_articles(&query_result.articles, 0);
+        let mut feed = ArticleFeed::new(&query_result, &mut search_state, &options, &theme);
+        let mut terminal = Terminal::new(TestBackend::new(60, 4)).unwrap();
+        let mut draw = |feed: &mut ArticleFeed, width: u16, focused: bool| {
+            terminal.backend_mut().resize(width, 4);
+            terminal
+                .draw(|frame| feed.render(frame, frame.size(), &theme, focused))
+                .unwrap();
+            LAYOUTS.with(|layouts| layouts.get())
+        };
+
+        let layouts = draw(&mut feed, 60, true);
+        // Moving the selection or the focus only renders the same items again.
+        feed.state.select(Some(1));
+        assert_eq!(draw(&mut feed, 60, true), layouts);
+        assert_eq!(draw(&mut feed, 60, false), layouts);
+        assert_eq!(feed.state.selected(), Some(1));
+        // A new width lays the rows out again.
+        assert_eq!(draw(&mut feed, 40, true), layouts + 1);
+    }
+
+    #[test]
+    fn test_search_match_tags() {
+        let theme = Theme::default();
+        let tags = |query: &str| -> Vec<String> {
+            first_row(query, &theme)
+                .into_iter()
+                .map(|(text, _)| text)
+                .filter(|text| text == TITLE_MATCH_TAG || text == ABSTRACT_MATCH_TAG)
+                .collect()
+        };
+        assert_eq!(tags("bosonic"), [TITLE_MATCH_TAG]);
+        assert_eq!(tags("the article"), [ABSTRACT_MATCH_TAG]);
+        assert!(tags("").is_empty());
+    }
+
+    /// Spans of the first row of the feed, with the given search query.
+    fn first_row(query: &str, theme: &Theme) -> Vec<(String, Style)> {
+        let query_result = feed();
+        let options = FeedOptions {
+            highlight_keywords: Some(KEYWORDS),
+            date_format: DateFormat::Strftime("Jul 8".to_string()),
+            ..Default::default()
+        };
+        let mut search_state = SearchState::default();
+        search_state.set_articles(&query_result.articles, 0);
+        query.chars().for_each(|c| search_state.push_char(c));
+        let feed = ArticleFeed::new(&query_result, &mut search_state, &options, theme);
+        feed.rows[0]
+            .line(80)
+            .spans
+            .into_iter()
+            .map(|span| (span.content.into_owned(), span.style))
+            .collect()
+    }
+
+    #[test]
+    fn test_keywords_in_title() {
+        let theme = Theme::default();
+        assert_eq!(
+            first_row("", &theme)[..4],
+            [
+                (KEYWORD_MARKER.to_string(), theme.highlight),
+                ("Surface code decoding with ".to_string(), Style::default()),
+                ("transformer".to_string(), theme.highlight),
+                ("s".to_string(), Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keywords_under_search() {
+        let theme = Theme::default();
+        assert_eq!(
+            first_row("transformers", &theme)[..4],
+            [
+                (KEYWORD_MARKER.to_string(), theme.highlight),
+                ("Surface code decoding with ".to_string(), Style::default()),
+                (
+                    "transformer".to_string(),
+                    theme.highlight.patch(theme.search_highlight)
+                ),
+                ("s".to_string(), theme.search_highlight),
+            ]
+        );
     }
 }
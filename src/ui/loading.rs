@@ -0,0 +1,177 @@
+use crate::ui::{centered_rect, Theme};
+use ratatui::{
+    layout::{Alignment, Rect},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::time::Duration;
+
+/// Frames of the spinner, one per tick.
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Screen shown in place of the feed while the query runs, when it failed, or when the
+/// terminal is too small for the interface.
+pub struct LoadingScreen<'a> {
+    title: &'static str,
+    lines: Vec<Line<'a>>,
+}
+
+impl<'a> LoadingScreen<'a> {
+    /// Loading screen with the description of the query, a spinner at the given frame
+    /// and the time elapsed since the query started.
+    pub fn loading(description: &'a str, spinner_frame: usize, elapsed: Duration) -> Self {
+        let spinner = SPINNER_FRAMES[spinner_frame % SPINNER_FRAMES.len()];
+        Self {
+            title: " Loading ",
+            lines: vec![
+                Line::from(description),
+                Line::from(""),
+                Line::from(format!("{spinner} {:.1}s", elapsed.as_secs_f32())),
+            ],
+        }
+    }
+
+    /// Error screen with the message of the failed query.
+    pub fn error(message: &'a str) -> Self {
+        Self {
+            title: " Error ",
+            lines: vec![
+                Line::from(message),
+                Line::from(""),
+                Line::from("retry: r | quit: q"),
+            ],
+        }
+    }
+
+    /// Message shown in place of the interface when the terminal is smaller than
+    /// `min_width` x `min_height`.
+    pub fn too_small(min_width: u16, min_height: u16) -> Self {
+        Self {
+            title: "",
+            lines: vec![
+                Line::from("Terminal too small"),
+                Line::from(format!("resize to {min_width}x{min_height}")),
+            ],
+        }
+    }
+
+    /// Render the message of [`LoadingScreen::too_small`], without borders which would
+    /// not fit.
+    pub fn render_bare(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        frame.render_widget(Block::new().style(theme.main), area);
+        let width = area.width.max(1);
+        let rows: u16 = self
+            .lines
+            .iter()
+            .map(|line| (line.width() as u16).div_ceil(width).max(1))
+            .sum();
+        frame.render_widget(
+            Paragraph::new(self.lines)
+                .style(theme.main)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            centered_rect(area.width, rows, area),
+        );
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        frame.render_widget(Block::new().style(theme.main), area);
+        let popup_area = centered_rect(60, self.lines.len() as u16 + 2, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(self.lines)
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .title(self.title)
+                        .title_style(theme.title),
+                )
+                .style(theme.main)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            popup_area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+    fn render(screen: LoadingScreen) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(64, 7)).unwrap();
+        terminal
+            .draw(|frame| screen.render(frame, frame.size(), &Theme::mono()))
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, ratatui::style::Style::reset());
+        buffer
+    }
+
+    fn golden(lines: &[&str]) -> Buffer {
+        let mut buffer = Buffer::with_lines(lines.iter().copied());
+        buffer.set_style(buffer.area, ratatui::style::Style::reset());
+        buffer
+    }
+
+    #[test]
+    fn test_loading_frame() {
+        let screen = LoadingScreen::loading(
+            "Fetching 200 latest quant-ph papers…",
+            2,
+            Duration::from_millis(1250),
+        );
+        assert_eq!(
+            render(screen),
+            golden(&[
+                "                                                                ",
+                "  ╭ Loading ─────────────────────────────────────────────────╮  ",
+                "  │           Fetching 200 latest quant-ph papers…           │  ",
+                "  │                                                          │  ",
+                "  │                          ⠹ 1.2s                          │  ",
+                "  ╰──────────────────────────────────────────────────────────╯  ",
+                "                                                                ",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_too_small_frame() {
+        let mut terminal = Terminal::new(TestBackend::new(12, 4)).unwrap();
+        terminal
+            .draw(|frame| {
+                LoadingScreen::too_small(30, 10).render_bare(frame, frame.size(), &Theme::mono())
+            })
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, ratatui::style::Style::reset());
+        assert_eq!(
+            buffer,
+            golden(&[
+                "Terminal too",
+                "    small   ",
+                "  resize to ",
+                "    30x10   ",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_error_frame() {
+        let screen = LoadingScreen::error("Could not reach arXiv: timed out");
+        assert_eq!(
+            render(screen),
+            golden(&[
+                "                                                                ",
+                "  ╭ Error ───────────────────────────────────────────────────╮  ",
+                "  │             Could not reach arXiv: timed out             │  ",
+                "  │                                                          │  ",
+                "  │                    retry: r | quit: q                    │  ",
+                "  ╰──────────────────────────────────────────────────────────╯  ",
+                "                                                                ",
+            ])
+        );
+    }
+}
@@ -0,0 +1,493 @@
+use crate::arxiv::{ArxivEntry, ArxivQueryResult};
+use crate::date::{format_date, DateFormat};
+use crate::ui::{truncate_to_width, Theme};
+use chrono::Utc;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState},
+    Frame,
+};
+
+/// Maximum width of the titles in the pinned feed, in columns.
+const PINNED_TITLE_LEN: usize = 80;
+/// Default maximum number of rows of the expanded pinned feed, borders included.
+pub const PINNED_MAX_HEIGHT: u16 = 12;
+/// Maximum number of rows of the collapsed pinned feed, borders included.
+pub const PINNED_COLLAPSED_HEIGHT: u16 = 4;
+/// Height of the pinned feed showing a single article, borders included.
+const PINNED_MIN_HEIGHT: u16 = 3;
+
+/// What the articles of a pinned feed have in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinnedKind {
+    /// Written by one of the pinned authors.
+    Authors,
+    /// Mentioning one of the pinned keywords in the title or the abstract.
+    Keywords,
+}
+
+impl PinnedKind {
+    /// The pinned feeds, in the order they are stacked above the main feed.
+    pub const ALL: [PinnedKind; 2] = [PinnedKind::Authors, PinnedKind::Keywords];
+
+    fn title(self) -> &'static str {
+        match self {
+            PinnedKind::Authors => "Pinned Authors",
+            PinnedKind::Keywords => "Pinned Keywords",
+        }
+    }
+}
+
+/// Indices of the articles written by one of the pinned authors.
+///
+/// The articles are sorted by submission date, newest first, and at most `max_articles`
+/// are kept.
+pub fn pinned_article_indices(
+    query_result: &ArxivQueryResult,
+    pinned_authors: Option<&[&str]>,
+    max_articles: Option<usize>,
+) -> Vec<usize> {
+    newest_first(query_result, max_articles, |article| {
+        article.contains_author(pinned_authors)
+    })
+}
+
+/// Indices of the articles mentioning one of the pinned keywords in their title or
+/// abstract, sorted like [`pinned_article_indices`].
+pub fn pinned_keyword_indices(
+    query_result: &ArxivQueryResult,
+    pinned_keywords: Option<&[&str]>,
+    max_articles: Option<usize>,
+) -> Vec<usize> {
+    newest_first(query_result, max_articles, |article| {
+        article.contains_keyword(pinned_keywords)
+    })
+}
+
+/// Indices of the articles passing the filter, newest first, at most `max_articles`.
+fn newest_first(
+    query_result: &ArxivQueryResult,
+    max_articles: Option<usize>,
+    filter: impl Fn(&ArxivEntry) -> bool,
+) -> Vec<usize> {
+    let mut indices: Vec<usize> = query_result
+        .articles
+        .iter()
+        .enumerate()
+        .filter(|(_, article)| filter(article))
+        .map(|(i, _)| i)
+        .collect();
+    indices.sort_by(|&a, &b| {
+        let published = |i: usize| &query_result.articles[i].published;
+        published(b).cmp(published(a))
+    });
+    if let Some(max_articles) = max_articles {
+        indices.truncate(max_articles);
+    }
+    indices
+}
+
+/// Feed of the articles of the pinned authors or keywords, shown above the main feed.
+///
+/// It has its own selection, kept while another feed is focused.
+#[derive(Debug)]
+pub struct PinnedFeed<'a> {
+    pub kind: PinnedKind,
+    items: List<'a>,
+    /// Items of the articles, to show only some of them when collapsed.
+    rows: Vec<ListItem<'a>>,
+    /// Indices of the articles in the query result, in the order of the feed.
+    indices: Vec<usize>,
+    /// Ids of the articles, to find the selected one again in a new feed.
+    ids: Vec<String>,
+    /// Number of pinned articles, including the ones filtered out by the search.
+    total: usize,
+    pub state: ListState,
+    /// Whether the feed shows as many articles as allowed, or only a few.
+    pub expanded: bool,
+    /// Number of articles shown when collapsed, followed by how many more there are.
+    /// Without it, the collapsed feed scrolls through all of them.
+    max_collapsed: Option<usize>,
+}
+
+impl<'a> PinnedFeed<'a> {
+    /// Feed of the articles at the given indices, each title followed by its
+    /// publication date in `date_format`. Nothing is selected.
+    ///
+    /// `total` is the number of pinned articles before the search filtered them; the
+    /// title shows how many are left, e.g. "Pinned Authors (2 of 5)".
+    pub fn new(
+        query_result: &ArxivQueryResult,
+        kind: PinnedKind,
+        indices: &[usize],
+        total: usize,
+        date_format: &DateFormat,
+        theme: &Theme,
+    ) -> Self {
+        let now = Utc::now();
+        let rows: Vec<ListItem> = indices
+            .iter()
+            .map(|&i| {
+                let article = &query_result.articles[i];
+                let title = truncate_to_width(&article.title, PINNED_TITLE_LEN).into_owned();
+                let date = format_date(&article.published, date_format, now);
+                ListItem::from(Line::from(vec![
+                    Span::raw(title),
+                    Span::styled(format!("  {date}"), Modifier::DIM),
+                ]))
+            })
+            .collect();
+
+        let items = List::new(rows.clone())
+            .block(
+                Block::bordered()
+                    .title_style(theme.title)
+                    .title_alignment(Alignment::Left)
+                    .title(if indices.len() == total {
+                        kind.title().to_string()
+                    } else {
+                        format!("{} ({} of {total})", kind.title(), indices.len())
+                    }),
+            )
+            .style(theme.main)
+            .highlight_style(theme.selection);
+
+        Self {
+            kind,
+            items,
+            rows,
+            indices: indices.to_vec(),
+            ids: indices
+                .iter()
+                .map(|&i| query_result.articles[i].id.clone())
+                .collect(),
+            total,
+            state: ListState::default(),
+            expanded: true,
+            max_collapsed: None,
+        }
+    }
+
+    /// The feed, expanded or collapsed.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Show at most `max_collapsed` articles when collapsed, followed by a "+N more" line.
+    pub fn max_collapsed(mut self, max_collapsed: Option<usize>) -> Self {
+        self.max_collapsed = max_collapsed;
+        self
+    }
+
+    /// First of the articles shown when collapsed, keeping the selection in view, and
+    /// their number, if they are capped and some are left out.
+    fn collapsed_window(&self) -> Option<(usize, usize)> {
+        let cap = self
+            .max_collapsed
+            .filter(|&cap| !self.expanded && cap < self.len())?;
+        let start = self
+            .state
+            .selected()
+            .map_or(0, |selected| selected.saturating_sub(cap.saturating_sub(1)))
+            .min(self.len() - cap);
+        Some((start, cap))
+    }
+
+    /// Number of pinned articles.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Index in the query result of the selected article.
+    pub fn selected_index(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|i| self.indices.get(i))
+            .copied()
+    }
+
+    /// Id of the selected article.
+    pub fn selected_id(&self) -> Option<&str> {
+        self.state
+            .selected()
+            .and_then(|i| self.ids.get(i))
+            .map(String::as_str)
+    }
+
+    /// Select the article with the given id, nothing if it is not in the feed.
+    pub fn select_id(&mut self, id: Option<&str>) {
+        let position = id.and_then(|id| self.ids.iter().position(|other| other == id));
+        self.state.select(position);
+    }
+
+    /// Height of the feed, borders included: one row per article, up to `max_height`
+    /// when expanded and [`PINNED_COLLAPSED_HEIGHT`] when collapsed, but at least one
+    /// article. A capped collapsed feed has a row per article shown and one for the
+    /// others. The feed takes no space without pinned articles, but stays visible if the
+    /// search filtered them all out.
+    ///
+    /// The articles which do not fit are reached by scrolling.
+    pub fn height(&self, max_height: u16) -> u16 {
+        let (rows, max_height) = match (self.expanded, self.collapsed_window()) {
+            (true, _) => (self.len(), max_height),
+            (false, Some((_, shown))) => (shown + 1, max_height),
+            (false, None) if self.max_collapsed.is_some() => (self.len(), max_height),
+            (false, None) => (self.len(), max_height.min(PINNED_COLLAPSED_HEIGHT)),
+        };
+        if self.total == 0 {
+            0
+        } else {
+            let rows = u16::try_from(rows).unwrap_or(u16::MAX);
+            rows.saturating_add(2)
+                .clamp(PINNED_MIN_HEIGHT, max_height.max(PINNED_MIN_HEIGHT))
+        }
+    }
+
+    /// Render the feed, with the selection highlighted only if it is `focused`.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
+        if let Some((start, shown)) = self.collapsed_window() {
+            let more = self.len() - shown;
+            let mut rows = self.rows[start..start + shown].to_vec();
+            rows.push(ListItem::new(Line::styled(
+                format!("+{more} more"),
+                Modifier::DIM,
+            )));
+            let selected = self
+                .state
+                .selected()
+                .filter(|_| focused)
+                .map(|selected| selected - start);
+            frame.render_stateful_widget(
+                self.items.clone().items(rows),
+                area,
+                &mut ListState::default().with_selected(selected),
+            );
+            return;
+        }
+        if focused {
+            frame.render_stateful_widget(&self.items, area, &mut self.state);
+        } else {
+            // Hide the selection without losing it, nor resetting the offset
+            let selected = self.state.selected();
+            *self.state.selected_mut() = None;
+            frame.render_stateful_widget(&self.items, area, &mut self.state);
+            *self.state.selected_mut() = selected;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, author: &str, published: &str) -> ArxivEntry {
+        ArxivEntry::new(
+            title.to_string(),
+            vec![author.to_string()],
+            String::new(),
+            format!("http://arxiv.org/abs/{title}"),
+            published.to_string(),
+            published.to_string(),
+        )
+    }
+
+    fn feed() -> ArxivQueryResult {
+        ArxivQueryResult {
+            updated: String::new(),
+            total_results: None,
+            articles: vec![
+                article("old", "Schrodinger", "2024-01-01T00:00:00Z"),
+                article("other", "Someone Else", "2024-01-05T00:00:00Z"),
+                article("newest", "Becquerel", "2024-01-04T00:00:00Z"),
+                article("middle", "Schrodinger", "2024-01-02T00:00:00Z"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_pinned_sorted_newest_first() {
+        let indices = pinned_article_indices(&feed(), Some(&["Schrodinger", "Becquerel"]), None);
+        assert_eq!(indices, vec![2, 3, 0]);
+    }
+
+    #[test]
+    fn test_pinned_capped() {
+        let indices = pinned_article_indices(&feed(), Some(&["Schrodinger", "Becquerel"]), Some(2));
+        assert_eq!(indices, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_pinned_height() {
+        let query_result = ArxivQueryResult {
+            articles: (0..20)
+                .map(|i| article(&i.to_string(), "Curie", "2024-01-01T00:00:00Z"))
+                .collect(),
+            ..Default::default()
+        };
+        let height = |count: usize, max_height: u16| {
+            let indices: Vec<usize> = (0..count).collect();
+            PinnedFeed::new(
+                &query_result,
+                PinnedKind::Authors,
+                &indices,
+                count,
+                &DateFormat::Short,
+                &Theme::mono(),
+            )
+            .height(max_height)
+        };
+        assert_eq!(height(0, PINNED_MAX_HEIGHT), 0);
+        assert_eq!(height(1, PINNED_MAX_HEIGHT), 3);
+        assert_eq!(height(10, PINNED_MAX_HEIGHT), 12);
+        assert_eq!(height(12, PINNED_MAX_HEIGHT), 12);
+        assert_eq!(height(12, 20), 14);
+        assert_eq!(height(12, PINNED_COLLAPSED_HEIGHT), 4);
+        assert_eq!(height(1, PINNED_COLLAPSED_HEIGHT), 3);
+        // At least one article is shown
+        assert_eq!(height(5, 0), 3);
+    }
+
+    #[test]
+    fn test_pinned_collapsed_height() {
+        let query_result = feed();
+        let mut pinned = PinnedFeed::new(
+            &query_result,
+            PinnedKind::Authors,
+            &[0, 1, 2, 3],
+            4,
+            &DateFormat::Short,
+            &Theme::mono(),
+        );
+        assert_eq!(pinned.height(PINNED_MAX_HEIGHT), 6);
+        pinned.expanded = false;
+        assert_eq!(pinned.height(PINNED_MAX_HEIGHT), PINNED_COLLAPSED_HEIGHT);
+        assert_eq!(pinned.height(3), 3);
+    }
+
+    #[test]
+    fn test_pinned_max_collapsed() {
+        use ratatui::{backend::TestBackend, buffer::Buffer, style::Style, Terminal};
+
+        let query_result = ArxivQueryResult {
+            articles: (0..5)
+                .map(|i| article(&format!("Article {i}"), "Curie", "2024-01-01T00:00:00Z"))
+                .collect(),
+            ..Default::default()
+        };
+        let theme = Theme::mono();
+        let mut pinned = PinnedFeed::new(
+            &query_result,
+            PinnedKind::Authors,
+            &[0, 1, 2, 3, 4],
+            5,
+            &DateFormat::Strftime("Jan 1".to_string()),
+            &theme,
+        )
+        .expanded(false)
+        .max_collapsed(Some(2));
+        let height = pinned.height(PINNED_MAX_HEIGHT);
+        assert_eq!(height, 2 + 1 + 2);
+
+        let mut terminal = Terminal::new(TestBackend::new(30, height)).unwrap();
+        let mut render = |pinned: &mut PinnedFeed| {
+            terminal
+                .draw(|frame| pinned.render(frame, frame.size(), true))
+                .unwrap();
+            let mut buffer = terminal.backend().buffer().clone();
+            buffer.set_style(buffer.area, Style::reset());
+            buffer
+        };
+        let golden = |lines: [&str; 5]| {
+            let mut buffer = Buffer::with_lines(lines);
+            buffer.set_style(buffer.area, Style::reset());
+            buffer
+        };
+        assert_eq!(
+            render(&mut pinned),
+            golden([
+                "┌Pinned Authors──────────────┐",
+                "│Article 0  Jan 1            │",
+                "│Article 1  Jan 1            │",
+                "│+3 more                     │",
+                "└────────────────────────────┘",
+            ])
+        );
+        // The articles shown follow the selection.
+        pinned.state.select(Some(3));
+        assert_eq!(
+            render(&mut pinned),
+            golden([
+                "┌Pinned Authors──────────────┐",
+                "│Article 2  Jan 1            │",
+                "│Article 3  Jan 1            │",
+                "│+3 more                     │",
+                "└────────────────────────────┘",
+            ])
+        );
+
+        // Expanded, or with few enough articles, they are all shown.
+        pinned.expanded = true;
+        assert_eq!(pinned.height(PINNED_MAX_HEIGHT), 5 + 2);
+        let few = PinnedFeed::new(
+            &query_result,
+            PinnedKind::Authors,
+            &[0, 1],
+            2,
+            &DateFormat::Short,
+            &theme,
+        )
+        .expanded(false)
+        .max_collapsed(Some(2));
+        assert_eq!(few.height(PINNED_MAX_HEIGHT), 2 + 2);
+    }
+
+    #[test]
+    fn test_pinned_keywords() {
+        let mut query_result = feed();
+        query_result.articles[0].summary = "A transmon qubit.".to_string();
+        query_result.articles[1].title = "Quantum Error Correction".to_string();
+
+        let indices =
+            pinned_keyword_indices(&query_result, Some(&["error correction", "Transmon"]), None);
+        assert_eq!(indices, vec![1, 0]);
+        let indices = pinned_keyword_indices(&query_result, Some(&["transmon"]), None);
+        assert_eq!(indices, vec![0]);
+        assert!(pinned_keyword_indices(&query_result, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_pinned_no_authors() {
+        assert!(pinned_article_indices(&feed(), None, Some(2)).is_empty());
+    }
+
+    #[test]
+    fn test_pinned_selection_by_id() {
+        let query_result = feed();
+        let indices = pinned_article_indices(&query_result, Some(&["Schrodinger"]), None);
+        let mut pinned = PinnedFeed::new(
+            &query_result,
+            PinnedKind::Authors,
+            &indices,
+            3,
+            &DateFormat::Short,
+            &Theme::mono(),
+        );
+        assert_eq!(pinned.selected_index(), None);
+
+        pinned.state.select(Some(1));
+        assert_eq!(pinned.selected_index(), Some(0));
+        assert_eq!(pinned.selected_id(), Some("http://arxiv.org/abs/old"));
+
+        pinned.select_id(Some("http://arxiv.org/abs/middle"));
+        assert_eq!(pinned.selected_index(), Some(3));
+        pinned.select_id(Some("http://arxiv.org/abs/other"));
+        assert_eq!(pinned.selected_index(), None);
+    }
+}
@@ -0,0 +1,967 @@
+use crate::config::{Config, ConfigWarning};
+use crate::editor::{ConfigEditor, ConfigField, QueryEditor, QueryField};
+use crate::stats::FeedStats;
+use crate::ui::{text_width, truncate_to_width, wrap_to_width, Shortcut, Theme};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::path::Path;
+
+/// Rectangle of the given size centered in `area`, clamped to it.
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length((area.height - height) / 2),
+            Constraint::Length(height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length((area.width - width) / 2),
+            Constraint::Length(width),
+            Constraint::Min(0),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Popup asking the user to confirm an action with y/n.
+pub struct ConfirmPopup<'a> {
+    message: &'a str,
+}
+
+impl<'a> ConfirmPopup<'a> {
+    pub fn new(message: &'a str) -> Self {
+        Self { message }
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let popup_area = centered_rect(50, 5, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(format!("{}\n[y]es / [n]o", self.message))
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .title(" Confirm ")
+                        .title_style(theme.title),
+                )
+                .style(theme.main)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            popup_area,
+        );
+    }
+}
+
+/// Width of the config popup, borders included.
+const CONFIG_POPUP_WIDTH: u16 = 70;
+/// Width of the column of the labels in the config popup.
+const CONFIG_LABEL_WIDTH: usize = 16;
+/// Rows above the fields in the config popup: the file, the profile and a blank line, the
+/// warnings coming in between.
+const CONFIG_HEADER_ROWS: usize = 3;
+
+/// Popup listing the editable fields of the config, with the one being edited as a text
+/// input, below the file and the profile they come from and the problems found in them.
+///
+/// Long values are wrapped, and the list scrolls to keep the selected field in view.
+pub struct ConfigPopup<'a> {
+    config: &'a Config,
+    editor: &'a ConfigEditor,
+    path: Option<&'a Path>,
+    profile: Option<&'a str>,
+    warnings: &'a [ConfigWarning],
+}
+
+impl<'a> ConfigPopup<'a> {
+    pub fn new(config: &'a Config, editor: &'a ConfigEditor) -> Self {
+        Self {
+            config,
+            editor,
+            path: None,
+            profile: None,
+            warnings: &[],
+        }
+    }
+
+    /// Show the config file and the profile the config was loaded with.
+    pub fn source(mut self, path: Option<&'a Path>, profile: Option<&'a str>) -> Self {
+        self.path = path;
+        self.profile = profile;
+        self
+    }
+
+    /// List the problems found while loading the config.
+    pub fn warnings(mut self, warnings: &'a [ConfigWarning]) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme, state: &mut ListState) {
+        let popup_width = CONFIG_POPUP_WIDTH.min(area.width);
+        let value_width =
+            usize::from(popup_width.saturating_sub(2)).saturating_sub(CONFIG_LABEL_WIDTH + 1);
+        let item = |label: &str, value: &str, style| {
+            let lines: Vec<Line> = wrap_to_width(value, value_width)
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let label = if i == 0 { label } else { "" };
+                    Line::from(vec![
+                        Span::styled(format!(" {label:<CONFIG_LABEL_WIDTH$}"), theme.title),
+                        Span::styled(text, style),
+                    ])
+                })
+                .collect();
+            ListItem::new(lines)
+        };
+
+        let path = match self.path {
+            Some(path) => path.display().to_string(),
+            None => "none, edits last for this session".to_string(),
+        };
+        let mut items = vec![
+            item("File", &path, theme.main),
+            item("Profile", self.profile.unwrap_or("default"), theme.main),
+        ];
+        items.extend(
+            self.warnings
+                .iter()
+                .map(|warning| item("Warning", &warning.to_string(), theme.highlight)),
+        );
+        items.push(ListItem::from(""));
+        items.extend(ConfigField::ALL.iter().map(|&field| {
+            let selected = field == self.editor.selected_field();
+            let value = match (&self.editor.input, selected) {
+                (Some(input), true) => format!("{input}▏"),
+                _ => field.values(self.config).join(", "),
+            };
+            let style = if selected {
+                theme.selection
+            } else {
+                theme.main
+            };
+            item(field.label(), &value, style)
+        }));
+
+        let rows: usize = items.iter().map(ListItem::height).sum();
+        let popup_area = centered_rect(popup_width, rows as u16 + 2, area);
+        let hint = if self.editor.is_editing() {
+            " Enter save · Esc cancel "
+        } else {
+            " j/k move · Enter edit · Esc close "
+        };
+        let selected = ConfigField::ALL
+            .iter()
+            .position(|&field| field == self.editor.selected_field());
+        state.select(selected.map(|i| CONFIG_HEADER_ROWS + self.warnings.len() + i));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .title(" Config ")
+                        .title_style(theme.title)
+                        .title_bottom(Line::styled(hint, theme.shortcut)),
+                )
+                .style(theme.main),
+            popup_area,
+            state,
+        );
+    }
+}
+
+/// Popup with the fields of the query, typed in place, and why the query can not be run.
+pub struct QueryPopup<'a> {
+    editor: &'a QueryEditor,
+}
+
+impl<'a> QueryPopup<'a> {
+    pub fn new(editor: &'a QueryEditor) -> Self {
+        Self { editor }
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let popup_width = CONFIG_POPUP_WIDTH.min(area.width);
+        let text_width = usize::from(popup_width.saturating_sub(4));
+        let mut lines: Vec<Line> = QueryField::ALL
+            .iter()
+            .map(|&field| {
+                let value = self.editor.value(field);
+                let (value, style) = if field == self.editor.selected_field() {
+                    (format!("{value}▏"), theme.selection)
+                } else {
+                    (value.to_string(), theme.main)
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {:<CONFIG_LABEL_WIDTH$}", field.label()),
+                        theme.title,
+                    ),
+                    Span::styled(value, style),
+                ])
+            })
+            .collect();
+        if let Some(message) = &self.editor.message {
+            lines.push(Line::from(""));
+            lines.extend(
+                wrap_to_width(message, text_width)
+                    .into_iter()
+                    .map(|line| Line::styled(format!(" {line}"), theme.highlight)),
+            );
+        }
+
+        let popup_area = centered_rect(popup_width, lines.len() as u16 + 2, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .title(" Query ")
+                        .title_style(theme.title)
+                        .title_bottom(Line::styled(
+                            " ↑/↓ move · Tab complete · Enter run · Esc cancel ",
+                            theme.shortcut,
+                        )),
+                )
+                .style(theme.main),
+            popup_area,
+        );
+    }
+}
+
+/// Popup listing choices, with the selected one highlighted.
+pub struct ChoicePopup<'a> {
+    title: &'a str,
+    choices: &'a [String],
+    selected: usize,
+}
+
+impl<'a> ChoicePopup<'a> {
+    pub fn new(title: &'a str, choices: &'a [String], selected: usize) -> Self {
+        Self {
+            title,
+            choices,
+            selected,
+        }
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let lines: Vec<Line> = self
+            .choices
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| {
+                let style = if i == self.selected {
+                    theme.selection
+                } else {
+                    theme.main
+                };
+                Line::styled(format!(" {choice}"), style)
+            })
+            .collect();
+
+        let popup_area = centered_rect(50, lines.len() as u16 + 2, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .title(self.title)
+                        .title_style(theme.title),
+                )
+                .style(theme.main),
+            popup_area,
+        );
+    }
+}
+
+/// Width of the error popup, borders included.
+const ERROR_POPUP_WIDTH: u16 = 60;
+
+/// Popup showing an error over the interface until a key is pressed.
+pub struct ErrorPopup<'a> {
+    message: &'a str,
+}
+
+impl<'a> ErrorPopup<'a> {
+    pub fn new(message: &'a str) -> Self {
+        Self { message }
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let inner_width = usize::from(ERROR_POPUP_WIDTH.min(area.width).saturating_sub(2)).max(1);
+        let message_height = text_width(self.message).div_ceil(inner_width).max(1) as u16;
+        let popup_area = centered_rect(ERROR_POPUP_WIDTH, message_height + 4, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(self.message),
+                Line::from(""),
+                Line::styled("press any key to dismiss", theme.shortcut),
+            ])
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title(" Error ")
+                    .title_style(theme.title),
+            )
+            .style(theme.main)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+            popup_area,
+        );
+    }
+}
+
+/// Width of the help popup, borders included.
+const HELP_POPUP_WIDTH: u16 = 60;
+/// Width of the column of the keys in the help popup.
+const HELP_KEY_WIDTH: usize = 14;
+
+/// Popup listing the keys of each context, as `(context, shortcuts)`, scrolled when they
+/// do not all fit.
+pub struct HelpPopup<'a> {
+    sections: &'a [(&'a str, &'a [Shortcut])],
+}
+
+impl<'a> HelpPopup<'a> {
+    pub fn new(sections: &'a [(&'a str, &'a [Shortcut])]) -> Self {
+        Self { sections }
+    }
+
+    /// Render the popup from the offset of `state`, clamped so that the last row is at
+    /// the bottom of the popup. Returns the number of rows shown at once.
+    pub fn render(
+        self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        state: &mut ListState,
+    ) -> usize {
+        let mut items = Vec::new();
+        for (i, (context, shortcuts)) in self.sections.iter().enumerate() {
+            if i > 0 {
+                items.push(ListItem::from(""));
+            }
+            items.push(ListItem::from(Line::styled(
+                format!(" {context}"),
+                theme.title,
+            )));
+            items.extend(shortcuts.iter().map(|(action, key)| {
+                ListItem::from(Line::from(vec![
+                    Span::styled(format!("   {key:<HELP_KEY_WIDTH$}"), theme.shortcut),
+                    Span::raw(*action),
+                ]))
+            }));
+        }
+
+        render_scrolled(frame, area, theme, state, " Help ", HELP_POPUP_WIDTH, items)
+    }
+}
+
+/// Render the rows in a popup of the given width, as tall as the rows but clamped to
+/// `area`, from the offset of `state`. The offset is clamped so that the last row is at
+/// the bottom of the popup. Returns the number of rows shown at once.
+fn render_scrolled(
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    state: &mut ListState,
+    title: &str,
+    width: u16,
+    items: Vec<ListItem>,
+) -> usize {
+    let rows = items.len();
+    let popup_area = centered_rect(width, rows as u16 + 2, area);
+    let visible = usize::from(popup_area.height.saturating_sub(2));
+    let offset = state.offset().min(rows.saturating_sub(visible));
+    *state.offset_mut() = offset;
+
+    let mut block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .title(title)
+        .title_style(theme.title);
+    if visible < rows {
+        let last = (offset + visible).min(rows);
+        block = block
+            .title_bottom(Line::from(format!(" {}-{last} of {rows} ", offset + 1)).right_aligned());
+    }
+    frame.render_widget(Clear, popup_area);
+    frame.render_stateful_widget(
+        List::new(items).block(block).style(theme.main),
+        popup_area,
+        state,
+    );
+    visible
+}
+
+/// Width of the stats popup, borders included.
+const STATS_POPUP_WIDTH: u16 = 60;
+/// Width of the column of the authors, categories and days in the stats popup.
+const STATS_LABEL_WIDTH: usize = 26;
+/// Width of the longest bar in the stats popup.
+const STATS_BAR_WIDTH: usize = 20;
+
+/// Popup showing the statistics of the feed, each count with a bar, scrolled when they
+/// do not all fit.
+pub struct StatsPopup<'a> {
+    stats: &'a FeedStats,
+}
+
+impl<'a> StatsPopup<'a> {
+    pub fn new(stats: &'a FeedStats) -> Self {
+        Self { stats }
+    }
+
+    /// Render the popup from the offset of `state`, clamped so that the last row is at
+    /// the bottom of the popup. Returns the number of rows shown at once.
+    pub fn render(
+        self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        state: &mut ListState,
+    ) -> usize {
+        let days: Vec<(String, usize)> = self
+            .stats
+            .days
+            .iter()
+            .map(|(day, count)| (day.format("%Y-%m-%d %a").to_string(), *count))
+            .collect();
+        let sections = [
+            ("Top authors", self.stats.authors.as_slice()),
+            ("Categories", self.stats.categories.as_slice()),
+            ("Articles per day", days.as_slice()),
+        ];
+
+        let mut items = Vec::new();
+        for (i, (section, counts)) in sections.iter().enumerate() {
+            if i > 0 {
+                items.push(ListItem::from(""));
+            }
+            items.push(ListItem::from(Line::styled(
+                format!(" {section}"),
+                theme.title,
+            )));
+            let max = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+            items.extend(counts.iter().map(|(label, count)| {
+                let label = truncate_to_width(label, STATS_LABEL_WIDTH);
+                let padding = STATS_LABEL_WIDTH.saturating_sub(text_width(&label));
+                let mut bar = "█".repeat((count * STATS_BAR_WIDTH).div_ceil(max.max(1)));
+                if !bar.is_empty() {
+                    bar.push(' ');
+                }
+                ListItem::from(Line::from(vec![
+                    Span::raw(format!("   {label}{} ", " ".repeat(padding))),
+                    Span::styled(bar, theme.highlight),
+                    Span::raw(count.to_string()),
+                ]))
+            }));
+        }
+
+        let title = format!(" Stats · {} articles ", self.stats.articles);
+        render_scrolled(frame, area, theme, state, &title, STATS_POPUP_WIDTH, items)
+    }
+}
+
+/// Popup with a single line text input.
+pub struct InputPopup<'a> {
+    title: &'a str,
+    input: &'a str,
+}
+
+impl<'a> InputPopup<'a> {
+    pub fn new(title: &'a str, input: &'a str) -> Self {
+        Self { title, input }
+    }
+
+    pub fn render(self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let popup_area = centered_rect(50, 3, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(
+            Paragraph::new(format!("{}▏", self.input))
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .title(self.title)
+                        .title_style(theme.title),
+                )
+                .style(theme.main),
+            popup_area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, buffer::Buffer, style::Style, Terminal};
+
+    const HELP_SECTIONS: &[(&str, &[Shortcut])] = &[
+        (
+            "Feed",
+            &[
+                ("move", "j/k ↑/↓"),
+                ("move by 10", "Ctrl-d/Ctrl-u"),
+                ("first/last", "g/G"),
+                ("yank url", "y"),
+                ("search", "/"),
+                ("export", "e"),
+                ("refresh", "r"),
+                ("config", "c"),
+                ("open all visible", "O"),
+                ("pin author/highlight keyword", "+a/+k"),
+                ("articles of the first author", "A"),
+                ("articles of the category", "C"),
+                ("quit", "q/Esc"),
+            ],
+        ),
+        (
+            "Pinned feeds",
+            &[
+                ("move", "j/k ↑/↓"),
+                ("yank url", "y"),
+                ("expand/collapse", "z"),
+                ("next feed", "Tab"),
+                ("back to the feed", "q/Esc"),
+            ],
+        ),
+        (
+            "Search",
+            &[
+                ("move", "↑/↓"),
+                ("title only", "Ctrl-t"),
+                ("apply", "Enter"),
+                ("cancel", "Esc"),
+            ],
+        ),
+    ];
+
+    /// Help popup rendered in a terminal of the given size, scrolled to `offset`, with
+    /// the offset it was clamped to.
+    fn render_help(width: u16, height: u16, offset: usize) -> (Buffer, usize) {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let mut state = ListState::default().with_offset(offset);
+        terminal
+            .draw(|frame| {
+                HelpPopup::new(HELP_SECTIONS).render(
+                    frame,
+                    frame.size(),
+                    &Theme::mono(),
+                    &mut state,
+                );
+            })
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, Style::reset());
+        (buffer, state.offset())
+    }
+
+    fn golden(lines: &[&str]) -> Buffer {
+        let mut buffer = Buffer::with_lines(lines.iter().copied());
+        buffer.set_style(buffer.area, Style::reset());
+        buffer
+    }
+
+    const AUTHORS: [&str; 15] = [
+        "Ada Lovelace",
+        "Alan Turing",
+        "Grace Hopper",
+        "John von Neumann",
+        "Claude Shannon",
+        "Emmy Noether",
+        "Richard Feynman",
+        "Marie Curie",
+        "Paul Dirac",
+        "Lise Meitner",
+        "Niels Bohr",
+        "Katherine Johnson",
+        "Erwin Schrödinger",
+        "Chien-Shiung Wu",
+        "Subrahmanyan Chandrasekhar",
+    ];
+
+    /// Config popup, with 15 pinned authors, rendered in a terminal of the given size.
+    fn render_config(width: u16, height: u16, editor: &ConfigEditor) -> Buffer {
+        render_config_with_warnings(width, height, editor, &[])
+    }
+
+    /// Config popup as in [`render_config`], listing the given warnings.
+    fn render_config_with_warnings(
+        width: u16,
+        height: u16,
+        editor: &ConfigEditor,
+        warnings: &[ConfigWarning],
+    ) -> Buffer {
+        let mut config = Config::default();
+        config.query.categories = vec!["quant-ph".to_string(), "cs.LG".to_string()];
+        config.pinned.authors = Some(AUTHORS.map(str::to_string).to_vec());
+        config.highlight.keywords = Some(vec!["qubit".to_string(), "transformer".to_string()]);
+
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let mut state = ListState::default();
+        terminal
+            .draw(|frame| {
+                ConfigPopup::new(&config, editor)
+                    .source(
+                        Some(Path::new("/home/ada/.config/arxivlens/config.toml")),
+                        Some("quantum"),
+                    )
+                    .warnings(warnings)
+                    .render(frame, frame.size(), &Theme::mono(), &mut state);
+            })
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, Style::reset());
+        buffer
+    }
+
+    #[test]
+    fn test_query_popup() {
+        let query = crate::arxiv::ArxivQuery {
+            search_queries: vec![crate::arxiv::SearchQuery::Category("quant-ph".to_string())],
+            start_index: 0,
+            max_results: 50,
+            sort_by: crate::arxiv::SortBy::SubmittedDate,
+            sort_order: crate::arxiv::SortOrder::Descending,
+            id_list: Vec::new(),
+        };
+        let mut editor = QueryEditor::from_query(&query);
+        ", cs.L".chars().for_each(|c| editor.push_char(c));
+        editor.complete_category();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal
+            .draw(|frame| QueryPopup::new(&editor).render(frame, frame.size(), &Theme::mono()))
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, Style::reset());
+        assert_eq!(
+            buffer,
+            golden(&[
+                "                                                                                ",
+                "     ╭ Query ─────────────────────────────────────────────────────────────╮     ",
+                "     │ Category        quant-ph, cs.L▏                                    │     ",
+                "     │ Author                                                             │     ",
+                "     │ Title                                                              │     ",
+                "     │ Max results     50                                                 │     ",
+                "     │ Sort            submitted                                          │     ",
+                "     │                                                                    │     ",
+                "     │ cs.LG cs.LO                                                        │     ",
+                "     ╰ ↑/↓ move · Tab complete · Enter run · Esc cancel ──────────────────╯     ",
+                "                                                                                ",
+                "                                                                                ",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_config_popup_80x24() {
+        let buffer = render_config(80, 24, &ConfigEditor::default());
+        assert_eq!(
+            buffer,
+            golden(&[
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+                "     ╭ Config ────────────────────────────────────────────────────────────╮     ",
+                "     │ File            /home/ada/.config/arxivlens/config.toml            │     ",
+                "     │ Profile         quantum                                            │     ",
+                "     │                                                                    │     ",
+                "     │ Category        quant-ph, cs.LG                                    │     ",
+                "     │ Pinned authors  Ada Lovelace, Alan Turing, Grace Hopper, John von  │     ",
+                "     │                 Neumann, Claude Shannon, Emmy Noether, Richard     │     ",
+                "     │                 Feynman, Marie Curie, Paul Dirac, Lise Meitner,    │     ",
+                "     │                 Niels Bohr, Katherine Johnson, Erwin Schrödinger,  │     ",
+                "     │                 Chien-Shiung Wu, Subrahmanyan Chandrasekhar        │     ",
+                "     │ Keywords        qubit, transformer                                 │     ",
+                "     ╰ j/k move · Enter edit · Esc close ─────────────────────────────────╯     ",
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+                "                                                                                ",
+            ])
+        );
+
+        // The list scrolls to keep the selected field in view.
+        let mut editor = ConfigEditor::default();
+        editor.select_next();
+        editor.select_next();
+        let buffer = render_config(80, 10, &editor);
+        assert_eq!(
+            buffer,
+            golden(&[
+                "     ╭ Config ────────────────────────────────────────────────────────────╮     ",
+                "     │                                                                    │     ",
+                "     │ Category        quant-ph, cs.LG                                    │     ",
+                "     │ Pinned authors  Ada Lovelace, Alan Turing, Grace Hopper, John von  │     ",
+                "     │                 Neumann, Claude Shannon, Emmy Noether, Richard     │     ",
+                "     │                 Feynman, Marie Curie, Paul Dirac, Lise Meitner,    │     ",
+                "     │                 Niels Bohr, Katherine Johnson, Erwin Schrödinger,  │     ",
+                "     │                 Chien-Shiung Wu, Subrahmanyan Chandrasekhar        │     ",
+                "     │ Keywords        qubit, transformer                                 │     ",
+                "     ╰ j/k move · Enter edit · Esc close ─────────────────────────────────╯     ",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_config_popup_warnings() {
+        let warnings = [
+            ConfigWarning::UnknownKey("hilight".to_string()),
+            ConfigWarning::UnknownCategory {
+                category: "quant-phh".to_string(),
+                suggestion: Some("quant-ph".to_string()),
+            },
+        ];
+        let buffer = render_config_with_warnings(80, 15, &ConfigEditor::default(), &warnings);
+        assert_eq!(
+            buffer,
+            golden(&[
+                "     ╭ Config ────────────────────────────────────────────────────────────╮     ",
+                "     │ File            /home/ada/.config/arxivlens/config.toml            │     ",
+                "     │ Profile         quantum                                            │     ",
+                "     │ Warning         unknown config key `hilight` is ignored            │     ",
+                "     │ Warning         unknown arXiv category `quant-phh`, did you mean   │     ",
+                "     │                 `quant-ph`?                                        │     ",
+                "     │                                                                    │     ",
+                "     │ Category        quant-ph, cs.LG                                    │     ",
+                "     │ Pinned authors  Ada Lovelace, Alan Turing, Grace Hopper, John von  │     ",
+                "     │                 Neumann, Claude Shannon, Emmy Noether, Richard     │     ",
+                "     │                 Feynman, Marie Curie, Paul Dirac, Lise Meitner,    │     ",
+                "     │                 Niels Bohr, Katherine Johnson, Erwin Schrödinger,  │     ",
+                "     │                 Chien-Shiung Wu, Subrahmanyan Chandrasekhar        │     ",
+                "     │ Keywords        qubit, transformer                                 │     ",
+                "     ╰ j/k move · Enter edit · Esc close ─────────────────────────────────╯     ",
+            ])
+        );
+
+        // The selected field stays in view below the warnings.
+        let mut editor = ConfigEditor::default();
+        editor.select_next();
+        editor.select_next();
+        let buffer = render_config_with_warnings(80, 3, &editor, &warnings);
+        assert_eq!(
+            buffer,
+            golden(&[
+                "     ╭ Config ────────────────────────────────────────────────────────────╮     ",
+                "     │ Keywords        qubit, transformer                                 │     ",
+                "     ╰ j/k move · Enter edit · Esc close ─────────────────────────────────╯     ",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_config_popup_100x40() {
+        let mut editor = ConfigEditor::default();
+        editor.select_next();
+        // The input being typed wraps like the values.
+        editor.input = Some(format!("{}, Hedy Lamarr", AUTHORS.join(", ")));
+        let buffer = render_config(100, 40, &editor);
+        assert_eq!(buffer, golden(&[
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "               ╭ Config ────────────────────────────────────────────────────────────╮               ",
+            "               │ File            /home/ada/.config/arxivlens/config.toml            │               ",
+            "               │ Profile         quantum                                            │               ",
+            "               │                                                                    │               ",
+            "               │ Category        quant-ph, cs.LG                                    │               ",
+            "               │ Pinned authors  Ada Lovelace, Alan Turing, Grace Hopper, John von  │               ",
+            "               │                 Neumann, Claude Shannon, Emmy Noether, Richard     │               ",
+            "               │                 Feynman, Marie Curie, Paul Dirac, Lise Meitner,    │               ",
+            "               │                 Niels Bohr, Katherine Johnson, Erwin Schrödinger,  │               ",
+            "               │                 Chien-Shiung Wu, Subrahmanyan Chandrasekhar, Hedy  │               ",
+            "               │                 Lamarr▏                                            │               ",
+            "               │ Keywords        qubit, transformer                                 │               ",
+            "               ╰ Enter save · Esc cancel ───────────────────────────────────────────╯               ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+            "                                                                                                    ",
+        ]));
+    }
+
+    #[test]
+    fn test_help_popup_80x24() {
+        let (buffer, offset) = render_help(80, 24, 0);
+        assert_eq!(offset, 0);
+        assert_eq!(
+            buffer,
+            golden(&[
+                "          ╭ Help ────────────────────────────────────────────────────╮          ",
+                "          │ Feed                                                     │          ",
+                "          │   j/k ↑/↓       move                                     │          ",
+                "          │   Ctrl-d/Ctrl-u move by 10                               │          ",
+                "          │   g/G           first/last                               │          ",
+                "          │   y             yank url                                 │          ",
+                "          │   /             search                                   │          ",
+                "          │   e             export                                   │          ",
+                "          │   r             refresh                                  │          ",
+                "          │   c             config                                   │          ",
+                "          │   O             open all visible                         │          ",
+                "          │   +a/+k         pin author/highlight keyword             │          ",
+                "          │   A             articles of the first author             │          ",
+                "          │   C             articles of the category                 │          ",
+                "          │   q/Esc         quit                                     │          ",
+                "          │                                                          │          ",
+                "          │ Pinned feeds                                             │          ",
+                "          │   j/k ↑/↓       move                                     │          ",
+                "          │   y             yank url                                 │          ",
+                "          │   z             expand/collapse                          │          ",
+                "          │   Tab           next feed                                │          ",
+                "          │   q/Esc         back to the feed                         │          ",
+                "          │                                                          │          ",
+                "          ╰────────────────────────────────────────────── 1-22 of 27 ╯          ",
+            ])
+        );
+
+        // Scrolling stops once the last row is at the bottom.
+        let (buffer, offset) = render_help(80, 24, 100);
+        assert_eq!(offset, 5);
+        assert!(buffer
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>()
+            .contains("6-27 of 27"));
+    }
+
+    #[test]
+    fn test_help_popup_40x12() {
+        let (buffer, offset) = render_help(40, 12, 5);
+        assert_eq!(offset, 5);
+        assert_eq!(
+            buffer,
+            golden(&[
+                "╭ Help ────────────────────────────────╮",
+                "│   /             search               │",
+                "│   e             export               │",
+                "│   r             refresh              │",
+                "│   c             config               │",
+                "│   O             open all visible     │",
+                "│   +a/+k         pin author/highlight │",
+                "│   A             articles of the first│",
+                "│   C             articles of the categ│",
+                "│   q/Esc         quit                 │",
+                "│                                      │",
+                "╰────────────────────────── 6-15 of 27 ╯",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_stats_popup() {
+        let stats = FeedStats {
+            articles: 4,
+            authors: vec![
+                ("Marie Curie".to_string(), 4),
+                ("Subrahmanyan Chandrasekhar-Wu".to_string(), 1),
+            ],
+            categories: vec![("quant-ph".to_string(), 3), ("cs.LG".to_string(), 2)],
+            days: vec![
+                (chrono::NaiveDate::from_ymd_opt(2024, 7, 8).unwrap(), 3),
+                (chrono::NaiveDate::from_ymd_opt(2024, 7, 9).unwrap(), 0),
+                (chrono::NaiveDate::from_ymd_opt(2024, 7, 10).unwrap(), 1),
+            ],
+        };
+        let mut terminal = Terminal::new(TestBackend::new(64, 16)).unwrap();
+        let mut state = ListState::default();
+        let mut visible = 0;
+        terminal
+            .draw(|frame| {
+                visible =
+                    StatsPopup::new(&stats).render(frame, frame.size(), &Theme::mono(), &mut state);
+            })
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, Style::reset());
+        assert_eq!(visible, 12);
+        assert_eq!(
+            buffer,
+            golden(&[
+                "                                                                ",
+                "  ╭ Stats · 4 articles ──────────────────────────────────────╮  ",
+                "  │ Top authors                                              │  ",
+                "  │   Marie Curie                ████████████████████ 4      │  ",
+                "  │   Subrahmanyan Chandrasekha… █████ 1                     │  ",
+                "  │                                                          │  ",
+                "  │ Categories                                               │  ",
+                "  │   quant-ph                   ████████████████████ 3      │  ",
+                "  │   cs.LG                      ██████████████ 2            │  ",
+                "  │                                                          │  ",
+                "  │ Articles per day                                         │  ",
+                "  │   2024-07-08 Mon             ████████████████████ 3      │  ",
+                "  │   2024-07-09 Tue             0                           │  ",
+                "  │   2024-07-10 Wed             ███████ 1                   │  ",
+                "  ╰──────────────────────────────────────────────────────────╯  ",
+                "                                                                ",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_error_popup() {
+        let mut terminal = Terminal::new(TestBackend::new(64, 7)).unwrap();
+        terminal
+            .draw(|frame| {
+                ErrorPopup::new("Refresh failed: timed out").render(
+                    frame,
+                    frame.size(),
+                    &Theme::mono(),
+                )
+            })
+            .unwrap();
+        let mut buffer = terminal.backend().buffer().clone();
+        buffer.set_style(buffer.area, Style::reset());
+        let mut expected = Buffer::with_lines([
+            "                                                                ",
+            "  ╭ Error ───────────────────────────────────────────────────╮  ",
+            "  │                 Refresh failed: timed out                │  ",
+            "  │                                                          │  ",
+            "  │                 press any key to dismiss                 │  ",
+            "  ╰──────────────────────────────────────────────────────────╯  ",
+            "                                                                ",
+        ]);
+        expected.set_style(expected.area, Style::reset());
+        assert_eq!(buffer, expected);
+    }
+}
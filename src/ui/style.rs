@@ -1,16 +1,38 @@
-use ratatui::style::{Color, Style};
+use crate::config::{ColorsConfig, ConfigError, UiConfig};
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 // Using the Tokyonight color palette. See https://lospec.com/palette-list/tokyo-night.
 const ORANGE: Color = Color::Rgb(255, 158, 100);
 const TEAL: Color = Color::Rgb(65, 166, 181);
+const PURPLE: Color = Color::Rgb(187, 154, 247);
+const YELLOW: Color = Color::Rgb(224, 175, 104);
 
-#[derive(Debug, Clone)]
+// Using the Tokyonight Day color palette for the light theme.
+const DAY_BACKGROUND: Color = Color::Rgb(225, 226, 231);
+const DAY_BLUE: Color = Color::Rgb(55, 96, 191);
+const DAY_ORANGE: Color = Color::Rgb(177, 92, 0);
+const DAY_PURPLE: Color = Color::Rgb(120, 71, 189);
+const DAY_YELLOW: Color = Color::Rgb(140, 108, 62);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Theme {
     pub main: Style,
     pub title: Style,
     pub shortcut: Style,
+    /// Keywords of the config found in the titles and abstracts.
     pub highlight: Style,
+    /// Authors of the config, in the feed and the author list.
+    pub author_highlight: Style,
+    /// Characters matched by the search query.
+    pub search_highlight: Style,
     pub selection: Style,
+    /// Primary category of the articles in the feed.
+    pub category_badge: Style,
+    /// Tags of the articles in the feed, e.g. "cross-list".
+    pub tag: Style,
 }
 
 impl Default for Theme {
@@ -20,7 +42,248 @@ impl Default for Theme {
             title: Style::new().fg(ORANGE),
             shortcut: Style::new().fg(Color::Blue).bg(Color::Black),
             highlight: Style::new().fg(ORANGE).bg(Color::Black),
+            author_highlight: Style::new().fg(PURPLE).bg(Color::Black),
+            search_highlight: Style::new()
+                .fg(YELLOW)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
             selection: Style::new().fg(Color::Black).bg(Color::White),
+            category_badge: Style::new().fg(PURPLE).bg(Color::Black),
+            tag: Style::new().fg(YELLOW).add_modifier(Modifier::ITALIC),
+        }
+    }
+}
+
+impl Theme {
+    /// Theme for terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            main: Style::new().fg(DAY_BLUE).bg(DAY_BACKGROUND),
+            title: Style::new().fg(DAY_ORANGE),
+            shortcut: Style::new().fg(DAY_BLUE).bg(DAY_BACKGROUND),
+            highlight: Style::new().fg(DAY_ORANGE).bg(DAY_BACKGROUND),
+            author_highlight: Style::new().fg(DAY_PURPLE).bg(DAY_BACKGROUND),
+            search_highlight: Style::new()
+                .fg(DAY_YELLOW)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            selection: Style::new().fg(Color::White).bg(DAY_BLUE),
+            category_badge: Style::new().fg(DAY_PURPLE).bg(DAY_BACKGROUND),
+            tag: Style::new().fg(DAY_YELLOW).add_modifier(Modifier::ITALIC),
+        }
+    }
+
+    /// Monochrome theme relying on the modifiers only, for terminals without colors and
+    /// for accessibility. The highlights stay distinguishable by their modifiers.
+    pub fn mono() -> Self {
+        Self {
+            main: Style::new(),
+            title: Style::new().add_modifier(Modifier::BOLD),
+            shortcut: Style::new().add_modifier(Modifier::DIM),
+            highlight: Style::new().add_modifier(Modifier::BOLD),
+            author_highlight: Style::new().add_modifier(Modifier::UNDERLINED),
+            search_highlight: Style::new().add_modifier(Modifier::REVERSED),
+            selection: Style::new().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            category_badge: Style::new().add_modifier(Modifier::DIM),
+            tag: Style::new().add_modifier(Modifier::ITALIC),
+        }
+    }
+
+    /// Built-in theme with the given name, the default one if none is given.
+    pub fn from_name(name: Option<&str>) -> Result<Self, ConfigError> {
+        match name {
+            None | Some("default") => Ok(Self::default()),
+            Some("light") => Ok(Self::light()),
+            Some("mono") => Ok(Self::mono()),
+            Some(name) => Err(ConfigError::UnknownTheme(name.to_string())),
+        }
+    }
+
+    /// Load a whole theme from a TOML file, in the serialized format of the theme. The
+    /// missing styles are the ones of the default theme.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::ThemeFile(path.to_path_buf(), e.to_string()))?;
+        toml::from_str(&content)
+            .map_err(|e| ConfigError::ThemeFile(path.to_path_buf(), e.to_string()))
+    }
+
+    /// Theme of the config: the theme file or the named base theme, with the colors of
+    /// the `[ui.colors]` section on top.
+    ///
+    /// The `NO_COLOR` environment variable forces the monochrome theme, see
+    /// <https://no-color.org>.
+    pub fn from_config(ui: &UiConfig) -> Result<Self, ConfigError> {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self::from_config_with_no_color(ui, no_color)
+    }
+
+    /// Theme of the config, or the monochrome theme if `no_color` is set.
+    pub fn from_config_with_no_color(ui: &UiConfig, no_color: bool) -> Result<Self, ConfigError> {
+        if no_color {
+            return Ok(Self::mono());
+        }
+        let theme = match &ui.theme_file {
+            Some(path) => Self::from_file(path)?,
+            None => Self::from_name(ui.theme_name.as_deref())?,
+        };
+        theme.with_colors(&ui.colors)
+    }
+
+    /// Override the colors of the theme, the other attributes of the styles are kept.
+    pub fn with_colors(mut self, colors: &ColorsConfig) -> Result<Self, ConfigError> {
+        if let Some(color) = parse_color("foreground", &colors.foreground)? {
+            self.main = self.main.fg(color);
+        }
+        if let Some(color) = parse_color("background", &colors.background)? {
+            self.main = self.main.bg(color);
+            self.shortcut = self.shortcut.bg(color);
+            self.highlight = self.highlight.bg(color);
+            self.author_highlight = self.author_highlight.bg(color);
+            self.category_badge = self.category_badge.bg(color);
+        }
+        if let Some(color) = parse_color("accent", &colors.accent)? {
+            self.title = self.title.fg(color);
+        }
+        if let Some(color) = parse_color("highlight", &colors.highlight)? {
+            self.highlight = self.highlight.fg(color);
+        }
+        if let Some(color) = parse_color("author_highlight", &colors.author_highlight)? {
+            self.author_highlight = self.author_highlight.fg(color);
         }
+        if let Some(color) = parse_color("search_highlight", &colors.search_highlight)? {
+            self.search_highlight = self.search_highlight.fg(color);
+        }
+        if let Some(color) = parse_color("selection", &colors.selection)? {
+            self.selection = self.selection.bg(color);
+        }
+        if let Some(color) = parse_color("shortcut", &colors.shortcut)? {
+            self.shortcut = self.shortcut.fg(color);
+        }
+        Ok(self)
+    }
+}
+
+/// Parse a color of the `[ui.colors]` section, naming the field on error.
+fn parse_color(field: &str, value: &Option<String>) -> Result<Option<Color>, ConfigError> {
+    value
+        .as_deref()
+        .map(|value| {
+            value.parse().map_err(|_| ConfigError::InvalidColor {
+                field: format!("ui.colors.{field}"),
+                value: value.to_string(),
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(
+            parse_color("accent", &Some("#ff9e64".into())).unwrap(),
+            Some(Color::Rgb(255, 158, 100))
+        );
+        assert_eq!(
+            parse_color("accent", &Some("lightblue".into())).unwrap(),
+            Some(Color::LightBlue)
+        );
+        assert_eq!(parse_color("accent", &None).unwrap(), None);
+
+        let error = parse_color("accent", &Some("#ff9e6".into())).unwrap_err();
+        assert!(error.to_string().contains("ui.colors.accent"));
+    }
+
+    #[test]
+    fn test_partial_color_overrides() {
+        let colors = ColorsConfig {
+            accent: Some("#112233".into()),
+            selection: Some("red".into()),
+            ..Default::default()
+        };
+        let theme = Theme::default().with_colors(&colors).unwrap();
+
+        let default = Theme::default();
+        assert_eq!(theme.title, default.title.fg(Color::Rgb(0x11, 0x22, 0x33)));
+        assert_eq!(theme.selection, default.selection.bg(Color::Red));
+        assert_eq!(theme.main, default.main);
+        assert_eq!(theme.highlight, default.highlight);
+        assert_eq!(theme.search_highlight, default.search_highlight);
+    }
+
+    #[test]
+    fn test_theme_from_name() {
+        assert_eq!(Theme::from_name(None).unwrap(), Theme::default());
+        assert_eq!(Theme::from_name(Some("light")).unwrap(), Theme::light());
+        assert_eq!(Theme::from_name(Some("mono")).unwrap(), Theme::mono());
+        assert!(matches!(
+            Theme::from_name(Some("solarized")),
+            Err(ConfigError::UnknownTheme(_))
+        ));
+    }
+
+    #[test]
+    fn test_no_color_forces_mono() {
+        let ui = UiConfig {
+            theme_name: Some("light".into()),
+            colors: ColorsConfig {
+                accent: Some("#ff9e64".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            Theme::from_config_with_no_color(&ui, true).unwrap(),
+            Theme::mono()
+        );
+        assert_ne!(
+            Theme::from_config_with_no_color(&ui, false).unwrap(),
+            Theme::mono()
+        );
+    }
+
+    #[test]
+    fn test_mono_highlights_distinguishable() {
+        let theme = Theme::mono();
+        let highlights = [
+            theme.highlight,
+            theme.author_highlight,
+            theme.search_highlight,
+            theme.selection,
+        ];
+        for (i, a) in highlights.iter().enumerate() {
+            assert!(a.fg.is_none() && a.bg.is_none());
+            for b in &highlights[i + 1..] {
+                assert_ne!(a.add_modifier, b.add_modifier);
+            }
+        }
+    }
+
+    #[test]
+    fn test_theme_file() {
+        let path =
+            std::env::temp_dir().join(format!("arxivlens-theme-{}.toml", std::process::id()));
+        std::fs::write(&path, toml::to_string(&Theme::light()).unwrap()).unwrap();
+
+        let ui = UiConfig {
+            theme_file: Some(path.clone()),
+            colors: ColorsConfig {
+                foreground: Some("white".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let theme = Theme::from_config(&ui).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(theme.main, Theme::light().main.fg(Color::White));
+        assert_eq!(theme.selection, Theme::light().selection);
+    }
+
+    #[test]
+    fn test_theme_file_missing() {
+        let error = Theme::from_file(Path::new("/nonexistent/theme.toml")).unwrap_err();
+        assert!(matches!(error, ConfigError::ThemeFile(..)));
     }
 }